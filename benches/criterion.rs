@@ -106,7 +106,7 @@ fn read_hdt_benchmarks(c: &mut Criterion) {
     let mut group = c.benchmark_group("read_hdt");
     group.sample_size(10);
 
-    group.bench_function("read_hdt_uncached", |b| b.iter(|| load()));
+    group.bench_function("read_hdt_uncached", |b| b.iter(load));
     #[cfg(feature = "cache")]
     {
         let c = format!("{H}.{}", hdt::hdt::CACHE_EXT);
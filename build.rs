@@ -0,0 +1,19 @@
+//! Generates the C header for the `ffi` feature into `OUT_DIR`.
+//! The committed `include/hdt.h` is compared against it in `tests/ffi.rs`.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        // a missing header only fails the header test, not the build
+        match cbindgen::generate(&dir) {
+            Ok(bindings) => {
+                bindings.write_to_file(format!("{out_dir}/hdt.h"));
+            }
+            Err(e) => println!("cargo:warning=failed to generate the C header: {e}"),
+        }
+    }
+}
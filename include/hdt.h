@@ -0,0 +1,126 @@
+/* C API of the hdt crate, see src/ffi.rs. Generated by cbindgen with the ffi feature, do not edit. */
+
+#ifndef HDT_H
+#define HDT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Position of a term in a triple.
+typedef enum HdtPosition {
+  HDT_POSITION_SUBJECT,
+  HDT_POSITION_PREDICATE,
+  HDT_POSITION_OBJECT,
+} HdtPosition;
+
+// In-memory representation of an RDF graph loaded from an HDT file.
+// Allows queries by triple patterns.
+typedef struct Hdt Hdt;
+
+// Iterator over the triples that fit a pattern.
+typedef struct HdtCursor HdtCursor;
+
+// A triple in the string format of the HDT dictionary.
+// IRIs have no angle brackets, blank nodes start with "_:" and literals with a quotation mark.
+typedef struct HdtTriple {
+  const char *subject;
+  const char *predicate;
+  const char *object;
+} HdtTriple;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Opens the HDT file at the given path.
+// Returns `NULL` on error.
+// # Safety
+// `path` must be a valid zero terminated string.
+const struct Hdt *hdt_open(const char *path);
+
+// Reads an HDT from the given bytes, which are not needed anymore afterwards.
+// Returns `NULL` on error.
+// # Safety
+// `data` must point to at least `len` readable bytes.
+const struct Hdt *hdt_open_buffer(const uint8_t *data, size_t len);
+
+// Closes an HDT, whose memory is released once all of its cursors are freed as well.
+// Does nothing if `hdt` is `NULL`.
+// # Safety
+// `hdt` must have been returned by [`hdt_open`] or [`hdt_open_buffer`] and must not be used afterwards.
+void hdt_close(const struct Hdt *hdt);
+
+// Number of triples.
+// # Safety
+// `hdt` must be an open HDT.
+size_t hdt_triple_count(const struct Hdt *hdt);
+
+// The triples of the header as N-Triples, to be freed with [`hdt_string_free`].
+// Returns `NULL` on error.
+// # Safety
+// `hdt` must be an open HDT.
+char *hdt_header(const struct Hdt *hdt);
+
+// The ID of the given term at the given position, or 0 if it is not in the dictionary.
+// The term uses the string format of [`HdtTriple`].
+// # Safety
+// `hdt` must be an open HDT and `term` a valid zero terminated string.
+uint32_t hdt_string_to_id(const struct Hdt *hdt, const char *term, enum HdtPosition position);
+
+// The term with the given ID at the given position, to be freed with [`hdt_string_free`].
+// Returns `NULL` if the ID does not exist.
+// # Safety
+// `hdt` must be an open HDT.
+char *hdt_id_to_string(const struct Hdt *hdt, uint32_t id, enum HdtPosition position);
+
+// Frees a string returned by this library. Does nothing if `s` is `NULL`.
+// # Safety
+// `s` must have been returned by [`hdt_header`] or [`hdt_id_to_string`] and must not be used afterwards.
+void hdt_string_free(char *s);
+
+// Queries the triples that fit the pattern, where `NULL` stands for a variable.
+// The terms use the string format of [`HdtTriple`], constants that are not in the dictionary result in no triples.
+// Returns a cursor to be freed with [`hdt_cursor_free`] or `NULL` on error.
+// # Safety
+// `hdt` must be an open HDT and `s`, `p` and `o` must be `NULL` or valid zero terminated strings.
+struct HdtCursor *hdt_query(const struct Hdt *hdt,
+                            const char *s,
+                            const char *p,
+                            const char *o);
+
+// Queries the triples that fit the ID pattern, where 0 stands for a variable.
+// Returns a cursor to be freed with [`hdt_cursor_free`] or `NULL` on error.
+// # Safety
+// `hdt` must be an open HDT.
+struct HdtCursor *hdt_query_ids(const struct Hdt *hdt, uint32_t s, uint32_t p, uint32_t o);
+
+// Writes the IDs of the next triple into `ids`, which must have room for three values.
+// Returns false at the end or on error, in which case [`hdt_last_error`] is not `NULL`.
+// # Safety
+// `cursor` must be a cursor that has not been freed and `ids` must point to three writable values.
+bool hdt_cursor_next_ids(struct HdtCursor *cursor, uint32_t *ids);
+
+// Writes the next triple into `triple`, whose strings stay valid until the next call with this cursor.
+// Returns false at the end or on error, in which case [`hdt_last_error`] is not `NULL`.
+// # Safety
+// `cursor` must be a cursor that has not been freed and `triple` must point to a writable [`HdtTriple`].
+bool hdt_cursor_next(struct HdtCursor *cursor,
+                     struct HdtTriple *triple);
+
+// Frees a cursor. Does nothing if `cursor` is `NULL`.
+// # Safety
+// `cursor` must have been returned by [`hdt_query`] or [`hdt_query_ids`] and must not be used afterwards.
+void hdt_cursor_free(struct HdtCursor *cursor);
+
+// The message of the last error on the calling thread or `NULL` if the last call succeeded.
+// The string is valid until the next call on the same thread.
+const char *hdt_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HDT_H */
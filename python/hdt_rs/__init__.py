@@ -0,0 +1,11 @@
+"""Python bindings for the hdt Rust library for the Header Dictionary Triples (HDT) compressed RDF format.
+
+Terms are strings in the format of the HDT dictionary: IRIs without angle brackets,
+blank nodes starting with ``_:`` and literals starting with a quotation mark, e.g. ``"example"@en``.
+For use with rdflib, see :class:`hdt_rs.store.HDTStore`.
+"""
+
+from ._hdt import Hdt, HdtError, TripleIterator
+from . import sparql
+
+__all__ = ["Hdt", "HdtError", "TripleIterator", "sparql"]
@@ -0,0 +1,7 @@
+"""SPARQL queries over an :class:`hdt_rs.Hdt`."""
+
+from ._hdt import sparql as _sparql
+
+query = _sparql.query
+
+__all__ = ["query"]
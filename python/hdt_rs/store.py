@@ -0,0 +1,119 @@
+"""Read-only rdflib store backed by an HDT file, following the interface of the rdflib-hdt ``HDTStore``.
+
+Example::
+
+    from rdflib import Graph
+    from hdt_rs.store import HDTStore
+
+    graph = Graph(store=HDTStore("example.hdt"))
+    for s, p, o in graph.triples((None, None, None)):
+        print(s, p, o)
+
+The store is also registered as the rdflib plugin ``"HDTStore"``, so ``Graph(store="HDTStore")``
+followed by ``graph.open("example.hdt")`` works as well.
+"""
+
+from rdflib import BNode, Literal, URIRef
+from rdflib.namespace import XSD
+from rdflib.store import VALID_STORE, Store
+
+from ._hdt import Hdt
+
+
+def term_to_hdt(term):
+    """Converts an rdflib term to the string format of the HDT dictionary."""
+    if isinstance(term, BNode):
+        return "_:" + str(term)
+    if isinstance(term, Literal):
+        lexical = '"' + str(term) + '"'
+        if term.language:
+            return lexical + "@" + term.language
+        if term.datatype and term.datatype != XSD.string:
+            return lexical + "^^<" + str(term.datatype) + ">"
+        return lexical
+    return str(term)
+
+
+def hdt_to_term(value):
+    """Converts a string in the format of the HDT dictionary to an rdflib term."""
+    if value.startswith('"'):
+        end = value.rindex('"')
+        lexical, suffix = value[1:end], value[end + 1 :]
+        if suffix.startswith("@"):
+            return Literal(lexical, lang=suffix[1:])
+        if suffix.startswith("^^<"):
+            return Literal(lexical, datatype=URIRef(suffix[3:-1]))
+        return Literal(lexical)
+    if value.startswith("_:"):
+        return BNode(value[2:])
+    return URIRef(value)
+
+
+class HDTStore(Store):
+    """Read-only rdflib store over an HDT file, which is loaded when the store is opened."""
+
+    context_aware = False
+    formula_aware = False
+    transaction_aware = False
+    graph_aware = False
+
+    def __init__(self, path=None, configuration=None, identifier=None):
+        super().__init__(configuration=None, identifier=identifier)
+        self._hdt = None
+        if path is not None:
+            self.open(path)
+        elif configuration is not None:
+            self.open(configuration)
+
+    @property
+    def hdt_document(self):
+        """The underlying :class:`hdt_rs.Hdt`."""
+        return self._hdt
+
+    def open(self, configuration, create=False):
+        """Loads the HDT file at the path given as configuration."""
+        if create:
+            raise NotImplementedError("the HDT store cannot create HDT files")
+        self._hdt = Hdt.read(configuration)
+        return VALID_STORE
+
+    def close(self, commit_pending_transaction=False):
+        self._hdt = None
+
+    def triples(self, triple_pattern, context=None):
+        s, p, o = (None if t is None else term_to_hdt(t) for t in triple_pattern)
+        for triple in self._hdt.triples_with_pattern(s, p, o):
+            yield tuple(hdt_to_term(t) for t in triple), iter(())
+
+    def __len__(self, context=None):
+        return len(self._hdt)
+
+    def contexts(self, triple=None):
+        return iter(())
+
+    def add(self, triple, context=None, quoted=False):
+        raise TypeError("The HDT store is read-only")
+
+    def addN(self, quads):
+        raise TypeError("The HDT store is read-only")
+
+    def remove(self, triple, context=None):
+        raise TypeError("The HDT store is read-only")
+
+    def create(self, configuration):
+        raise TypeError("The HDT store is read-only")
+
+    def destroy(self, configuration):
+        raise TypeError("The HDT store is read-only")
+
+    def bind(self, prefix, namespace, override=True):
+        pass
+
+    def namespace(self, prefix):
+        return None
+
+    def prefix(self, namespace):
+        return None
+
+    def namespaces(self):
+        return iter(())
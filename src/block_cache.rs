@@ -0,0 +1,186 @@
+//! Size-bounded least recently used cache of decoded dictionary blocks, see [`BlockCache`].
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Section index and block index of a cached block.
+type Key = (usize, usize);
+
+/// Thread-safe least recently used cache of decoded plain front coding blocks.
+/// Plain front coding needs to decode a block from its start for every extracted string,
+/// so workloads that translate many IDs of the same blocks, such as iterating over the subjects of a popular object,
+/// benefit from keeping the decoded blocks.
+/// Enable it with [`crate::FourSectDict::enable_cache`], which makes [`crate::FourSectDict::id_to_string`]
+/// and thus the Sophia and SPARQL adapters use it.
+pub struct BlockCache {
+    /// maximum number of bytes of the decoded strings
+    capacity: usize,
+    state: Mutex<State>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct State {
+    blocks: HashMap<Key, Entry>,
+    /// keys by the time of their last use, the first one is evicted first
+    recency: BTreeMap<u64, Key>,
+    time: u64,
+    bytes: usize,
+}
+
+struct Entry {
+    strings: Arc<[String]>,
+    bytes: usize,
+    time: u64,
+}
+
+/// Usage statistics of a [`BlockCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// number of lookups that found the block in the cache
+    pub hits: u64,
+    /// number of lookups that had to decode the block
+    pub misses: u64,
+    /// number of blocks currently in the cache
+    pub blocks: usize,
+    /// approximate heap size of the cached blocks in bytes
+    pub bytes: usize,
+}
+
+impl fmt::Debug for BlockCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "capacity {}, {:?}", bytesize::ByteSize(self.capacity as u64), self.stats())
+    }
+}
+
+impl BlockCache {
+    /// Empty cache that holds decoded blocks of up to the given number of bytes.
+    pub fn new(capacity: usize) -> Self {
+        BlockCache {
+            capacity,
+            state: Mutex::new(State::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Maximum size of the cached blocks in bytes.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Current usage statistics.
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            blocks: state.blocks.len(),
+            bytes: state.bytes,
+        }
+    }
+
+    /// Removes all blocks and resets the statistics.
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = State::default();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// The cached block for the key or the result of decoding it, which is then cached.
+    /// The lock is not held while decoding, so concurrent misses of the same block may decode it more than once.
+    pub(crate) fn get_or_decode<E>(
+        &self, key: Key, decode: impl FnOnce() -> Result<Vec<String>, E>,
+    ) -> Result<Arc<[String]>, E> {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.time += 1;
+            let time = state.time;
+            if let Some(entry) = state.blocks.get_mut(&key) {
+                let old = std::mem::replace(&mut entry.time, time);
+                let strings = entry.strings.clone();
+                state.recency.remove(&old);
+                state.recency.insert(time, key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(strings);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let strings: Arc<[String]> = decode()?.into();
+        let bytes = strings.iter().map(|s| s.len() + size_of::<String>()).sum::<usize>();
+        if bytes > self.capacity {
+            return Ok(strings);
+        }
+        let mut state = self.state.lock().unwrap();
+        while state.bytes + bytes > self.capacity {
+            let Some((_, evicted)) = state.recency.pop_first() else { break };
+            let entry = state.blocks.remove(&evicted).unwrap();
+            state.bytes -= entry.bytes;
+        }
+        state.time += 1;
+        let time = state.time;
+        if let Some(old) = state.blocks.insert(key, Entry { strings: strings.clone(), bytes, time }) {
+            // decoded concurrently by another thread
+            state.recency.remove(&old.time);
+            state.bytes -= old.bytes;
+        }
+        state.recency.insert(time, key);
+        state.bytes += bytes;
+        Ok(strings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdKind;
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use pretty_assertions::assert_eq;
+    use std::convert::Infallible;
+
+    #[test]
+    fn evict_least_recently_used() {
+        init();
+        let block = |s: &str| Ok::<_, Infallible>(vec![s.to_owned()]);
+        let entry_bytes = 1 + size_of::<String>();
+        let cache = BlockCache::new(2 * entry_bytes);
+        cache.get_or_decode((0, 0), || block("a")).unwrap();
+        cache.get_or_decode((0, 1), || block("b")).unwrap();
+        // use block 0 so that block 1 is evicted next
+        assert_eq!(&*cache.get_or_decode((0, 0), || block("x")).unwrap(), ["a"]);
+        cache.get_or_decode((0, 2), || block("c")).unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3, blocks: 2, bytes: 2 * entry_bytes });
+        assert_eq!(&*cache.get_or_decode((0, 1), || block("d")).unwrap(), ["d"]);
+        assert_eq!(&*cache.get_or_decode((0, 2), || block("x")).unwrap(), ["c"]);
+        cache.clear();
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 0, blocks: 0, bytes: 0 });
+    }
+
+    #[test]
+    fn dict() -> color_eyre::Result<()> {
+        init();
+        let mut hdt = snikmeta()?;
+        let expected: Vec<_> = hdt.triples_all().collect();
+        hdt.dict.enable_cache(1 << 20);
+        assert_eq!(hdt.triples_all().collect::<Vec<_>>(), expected);
+        let stats = hdt.dict.cache_stats().unwrap();
+        assert!(stats.hits > 0 && stats.misses > 0 && stats.bytes <= 1 << 20);
+        // a tiny cache must not change the results
+        hdt.dict.enable_cache(100);
+        for [s, p, o] in &expected {
+            let id = hdt.dict.string_to_id(s, IdKind::Subject);
+            assert_eq!(hdt.dict.id_to_string(id, IdKind::Subject)?, **s);
+            let id = hdt.dict.string_to_id(p, IdKind::Predicate);
+            assert_eq!(hdt.dict.id_to_string(id, IdKind::Predicate)?, **p);
+            let id = hdt.dict.string_to_id(o, IdKind::Object);
+            assert_eq!(hdt.dict.id_to_string(id, IdKind::Object)?, **o);
+        }
+        assert!(hdt.dict.cache_stats().unwrap().bytes <= 100);
+        hdt.dict.disable_cache();
+        assert_eq!(hdt.dict.cache_stats(), None);
+        Ok(())
+    }
+}
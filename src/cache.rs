@@ -0,0 +1,310 @@
+//! Versioned cache files of the triples section including the object index, see [`crate::Hdt::read_from_path`].
+//! A cache file starts with a [`CacheHeader`] that identifies the build of this library and the HDT triples section
+//! it was created from, followed by the bincode encoded [`TriplesBitmap`].
+//! Caches that do not fit are rejected with a specific [`Error`], so that they can be rebuilt.
+//! # Example
+//! ```
+//! let dir = std::path::Path::new("tests/resources/generated/cache-doc");
+//! let cache = hdt::Hdt::build_cache_in("tests/resources/snikmeta.hdt", Some(dir)).unwrap();
+//! let header = hdt::cache::CacheHeader::read(&mut std::io::BufReader::new(std::fs::File::open(cache).unwrap())).unwrap();
+//! assert_eq!(header.crate_version, env!("CARGO_PKG_VERSION"));
+//! ```
+use crate::ControlInfo;
+use crate::containers::{Bitmap, Sequence, Verify, control_info};
+use crate::triples::{self, Level, TriplesBitmap};
+use std::fs::File;
+use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name extension of cache files, which are placed next to the HDT file unless a cache directory is given.
+pub const CACHE_EXT: &str = "index.v2-rust-cache";
+/// Magic number at the start of every cache file.
+pub const MAGIC: [u8; 8] = *b"HDTRSIDX";
+/// Version of the cache file layout, incremented on incompatible changes.
+pub const FORMAT_VERSION: u32 = 3;
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Pointer width in bits, because the cached structures contain `usize` values.
+const POINTER_WIDTH: u8 = usize::BITS as u8;
+/// CRC-64 with a 16 byte lookup table, which hashes several GB per second.
+static CRC64: crc::Crc<u64, crc::Table<16>> = crc::Crc::<u64, crc::Table<16>>::new(&crc::CRC_64_XZ);
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The reason why a cache file was rejected or could not be written.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("cache IO error")]
+    Io(#[from] std::io::Error),
+    #[error("not an HDT cache file, found magic number {0:?}")]
+    Magic([u8; 8]),
+    #[error("cache format version {found}, expected {expected}")]
+    FormatVersion { found: u32, expected: u32 },
+    #[error("cache for {found} bit pointers, expected {expected} bit")]
+    PointerWidth { found: u8, expected: u8 },
+    #[error("cache written by hdt {found}, expected {expected}")]
+    CrateVersion { found: String, expected: &'static str },
+    #[error("cache of a different triples section with hash {found:016x}, expected {expected:016x}")]
+    TriplesHash { found: u64, expected: u64 },
+    #[error("failed to read HDT triples control info")]
+    ControlInfo(#[from] control_info::Error),
+    #[error("failed to decode cached triples")]
+    Triples(#[from] triples::Error),
+    #[error("failed to encode triples")]
+    Encode(#[from] bincode::error::EncodeError),
+}
+
+/// Identifies the build of this library and the HDT triples section that a cache file was created from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheHeader {
+    /// version of the cache file layout, see [`FORMAT_VERSION`]
+    pub format_version: u32,
+    /// pointer width in bits of the platform that wrote the cache
+    pub pointer_width: u8,
+    /// version of this library that wrote the cache
+    pub crate_version: String,
+    /// CRC-64 of the triples control information and of the headers and stored CRC32 checksums
+    /// of the bitmaps and sequences of the HDT file, see [`triples_hash`]
+    pub triples_hash: u64,
+}
+
+impl CacheHeader {
+    /// Header for a cache of the triples section with the given hash written by the current build.
+    pub fn new(triples_hash: u64) -> Self {
+        CacheHeader {
+            format_version: FORMAT_VERSION,
+            pointer_width: POINTER_WIDTH,
+            crate_version: CRATE_VERSION.to_owned(),
+            triples_hash,
+        }
+    }
+
+    /// Reads the header and checks that it was written by the current build.
+    /// The triples hash is not checked, as this requires reading the HDT file.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::Magic(magic));
+        }
+        let mut format_version = [0u8; 4];
+        reader.read_exact(&mut format_version)?;
+        let format_version = u32::from_le_bytes(format_version);
+        if format_version != FORMAT_VERSION {
+            return Err(Error::FormatVersion { found: format_version, expected: FORMAT_VERSION });
+        }
+        let mut bytes = [0u8; 2];
+        reader.read_exact(&mut bytes)?;
+        let [pointer_width, version_len] = bytes;
+        if pointer_width != POINTER_WIDTH {
+            return Err(Error::PointerWidth { found: pointer_width, expected: POINTER_WIDTH });
+        }
+        let mut crate_version = vec![0u8; version_len.into()];
+        reader.read_exact(&mut crate_version)?;
+        let crate_version = String::from_utf8_lossy(&crate_version).into_owned();
+        if crate_version != CRATE_VERSION {
+            return Err(Error::CrateVersion { found: crate_version, expected: CRATE_VERSION });
+        }
+        let mut triples_hash = [0u8; 8];
+        reader.read_exact(&mut triples_hash)?;
+        Ok(CacheHeader {
+            format_version,
+            pointer_width,
+            crate_version,
+            triples_hash: u64::from_le_bytes(triples_hash),
+        })
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&self.format_version.to_le_bytes())?;
+        writer.write_all(&[self.pointer_width, self.crate_version.len() as u8])?;
+        writer.write_all(self.crate_version.as_bytes())?;
+        writer.write_all(&self.triples_hash.to_le_bytes())
+    }
+}
+
+/// Path of the cache file for the given HDT file, in the cache directory if given or else next to the HDT file.
+pub fn cache_path(hdt_path: &Path, cache_dir: Option<&Path>) -> std::io::Result<PathBuf> {
+    let dir = if let Some(dir) = cache_dir {
+        dir.to_path_buf()
+    } else {
+        let mut dir = std::fs::canonicalize(hdt_path)?;
+        dir.pop();
+        dir
+    };
+    let name = hdt_path.file_name().ok_or_else(|| std::io::Error::other("HDT path has no file name"))?;
+    Ok(dir.join(format!("{}.{CACHE_EXT}", name.to_string_lossy())))
+}
+
+/// Identifies the triples section that starts at the current position of the reader, which is left at its end.
+/// Hashes the control information and the headers and stored CRC32 checksums of the bitmaps and sequences,
+/// which cover their lengths and contents, and seeks over their data so that only a few bytes are read.
+/// The headers are verified but the data is not.
+pub(crate) fn triples_hash<R: BufRead + Seek>(reader: &mut R) -> Result<(ControlInfo, u64)> {
+    let mut reader = HashRead::new(reader);
+    let triples_ci = ControlInfo::read(&mut reader)?;
+    let skip_body = |reader: &mut HashRead<&mut R>, len: usize| -> std::io::Result<()> {
+        reader.seek_relative(i64::try_from(len).map_err(std::io::Error::other)?)?;
+        let mut crc32 = [0u8; 4];
+        reader.read_exact(&mut crc32)
+    };
+    for level in [Level::Y, Level::Z] {
+        let num_bits =
+            Bitmap::read_meta(&mut reader, Verify::Headers).map_err(|e| triples::Error::Bitmap(level, e))?;
+        skip_body(&mut reader, num_bits.div_ceil(8))?;
+    }
+    for level in [Level::Y, Level::Z] {
+        let (bits_per_entry, entries) =
+            Sequence::read_meta(&mut reader, Verify::Headers).map_err(|e| triples::Error::Sequence(level, e))?;
+        skip_body(&mut reader, (bits_per_entry * entries).div_ceil(8))?;
+    }
+    Ok((triples_ci, reader.finish()))
+}
+
+/// Loads the triples from the cache file, given a reader at the start of the triples section of the HDT file.
+/// The cache is rejected if it was written by a different build or for a different triples section.
+pub(crate) fn load<R: BufRead + Seek>(mut hdt_reader: R, cache_path: &Path) -> Result<TriplesBitmap> {
+    let mut cache_reader = std::io::BufReader::new(File::open(cache_path)?);
+    let header = CacheHeader::read(&mut cache_reader)?;
+    let (triples_ci, expected) = triples_hash(&mut hdt_reader)?;
+    if header.triples_hash != expected {
+        return Err(Error::TriplesHash { found: header.triples_hash, expected });
+    }
+    Ok(TriplesBitmap::load_cache(&mut cache_reader, &triples_ci)?)
+}
+
+/// Writes the triples including the object index to a cache file, which is created or overwritten.
+pub(crate) fn write(cache_path: &Path, triples: &TriplesBitmap, triples_hash: u64) -> Result<()> {
+    // the object index is part of the cache
+    triples.op_index();
+    if let Some(dir) = cache_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = std::io::BufWriter::new(File::create(cache_path)?);
+    CacheHeader::new(triples_hash).write(&mut writer)?;
+    bincode::serde::encode_into_std_write(triples, &mut writer, bincode::config::standard())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reader that hashes all bytes that are read or consumed through it, but not those that are skipped by seeking.
+pub(crate) struct HashRead<R> {
+    inner: R,
+    digest: crc::Digest<'static, u64, crc::Table<16>>,
+}
+
+impl<R: BufRead> HashRead<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        HashRead { inner, digest: CRC64.digest() }
+    }
+
+    /// The hash of all bytes read so far.
+    pub(crate) fn finish(self) -> u64 {
+        self.digest.finalize()
+    }
+}
+
+impl<R: Read> Read for HashRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for HashRead<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<R: BufRead> BufRead for HashRead<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // the buffer is still filled from the previous call, so this does not read
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.digest.update(&buf[..amt.min(buf.len())]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hdt;
+    use crate::hdt::tests::snikmeta_check;
+    use crate::tests::init;
+    use fs_err::remove_file;
+    use pretty_assertions::assert_eq;
+    use std::io::Cursor;
+
+    #[test]
+    fn header() -> color_eyre::Result<()> {
+        init();
+        let mut buf = Vec::new();
+        CacheHeader::new(42).write(&mut buf)?;
+        assert_eq!(CacheHeader::read(&mut Cursor::new(&buf))?, CacheHeader::new(42));
+        // corrupt each field and check that it is rejected for the right reason
+        let reject = |pos: usize, value: u8| {
+            let mut buf = buf.clone();
+            buf[pos] = value;
+            CacheHeader::read(&mut Cursor::new(buf)).unwrap_err()
+        };
+        assert!(matches!(reject(0, b'X'), Error::Magic(_)));
+        assert!(matches!(reject(8, 1), Error::FormatVersion { found: 1, expected: FORMAT_VERSION }));
+        assert!(matches!(reject(12, 16), Error::PointerWidth { found: 16, .. }));
+        assert!(matches!(reject(14, b'x'), Error::CrateVersion { .. }));
+        assert!(matches!(CacheHeader::read(&mut Cursor::new(&buf[..10])), Err(Error::Io(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn hash_read() -> color_eyre::Result<()> {
+        init();
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let expected = CRC64.checksum(&data);
+        // mixed buffered and unbuffered reads hash the same bytes
+        let mut reader = HashRead::new(std::io::BufReader::with_capacity(1000, Cursor::new(&data)));
+        let mut buf = [0u8; 10];
+        reader.read_exact(&mut buf)?;
+        let mut line = Vec::new();
+        reader.read_until(200, &mut line)?;
+        reader.read_to_end(&mut line)?;
+        assert_eq!(reader.finish(), expected);
+        // skipped bytes are not hashed
+        let mut reader = HashRead::new(Cursor::new(&data));
+        reader.seek_relative(99_000)?;
+        reader.read_to_end(&mut line)?;
+        assert_eq!(reader.finish(), CRC64.checksum(&data[99_000..]));
+        Ok(())
+    }
+
+    #[test]
+    fn build_and_reject() -> color_eyre::Result<()> {
+        init();
+        let dir = Path::new("tests/resources/generated/cache-test");
+        let hdt_path = Path::new("tests/resources/snikmeta.hdt");
+        let path = cache_path(hdt_path, Some(dir))?;
+        let _ = remove_file(&path);
+        assert_eq!(Hdt::build_cache_in(hdt_path, Some(dir))?, path);
+        let options = crate::hdt::ReadOptions { cache_dir: Some(dir), ..Default::default() };
+        snikmeta_check(&Hdt::read_from_path_with_options(hdt_path, options)?)?;
+        // a cache of another HDT is rejected because of the triples hash
+        let mut reader = std::io::BufReader::new(File::open(hdt_path)?);
+        Hdt::read_header(&mut reader)?;
+        crate::FourSectDict::skip(&mut reader)?;
+        let mut other = Vec::new();
+        CacheHeader::new(0).write(&mut other)?;
+        fs_err::write(&path, other)?;
+        assert!(matches!(load(&mut reader, &path), Err(Error::TriplesHash { found: 0, .. })));
+        // rejected caches are overwritten
+        snikmeta_check(&Hdt::read_from_path_with_options(hdt_path, options)?)?;
+        assert!(CacheHeader::read(&mut std::io::BufReader::new(File::open(&path)?))?.triples_hash != 0);
+        Ok(())
+    }
+}
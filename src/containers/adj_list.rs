@@ -45,7 +45,7 @@ impl AdjList {
     }
 
     /// Find the first position for the given ID, counting from 1.
-    pub fn find(&self, x: Id) -> usize {
+    pub fn find(&self, x: usize) -> usize {
         if x == 0 {
             return 0;
         }
@@ -60,12 +60,12 @@ impl AdjList {
     /// * `element` - a value that may or may not exist in the specified range of the list
     /// * `begin` - first index of the search range
     /// * `end` - end (exclusive) of the search range
-    fn bin_search(&self, element: usize, begin: usize, end: usize) -> Option<usize> {
+    fn bin_search(&self, element: Id, begin: usize, end: usize) -> Option<usize> {
         let mut low = begin;
         let mut high = end;
         while low < high {
             let mid = usize::midpoint(low, high);
-            match (self.sequence.get(mid) as usize).cmp(&element) {
+            match self.sequence.get(mid).cmp(&(element as usize)) {
                 Ordering::Less => low = mid + 1,
                 Ordering::Greater => high = mid,
                 Ordering::Equal => return Some(mid),
@@ -76,12 +76,12 @@ impl AdjList {
 
     /// Find position of element y in the list x.
     // See <https://github.com/rdfhdt/hdt-cpp/blob/develop/libhdt/src/sequence/AdjacencyList.cpp>.
-    pub fn search(&self, x: usize, y: usize) -> Option<usize> {
+    pub fn search(&self, x: usize, y: Id) -> Option<usize> {
         self.bin_search(y, self.find(x), self.last(x) + 1)
     }
 
     /// Find the last position for the given ID, counting from 1.
-    pub fn last(&self, x: Id) -> usize {
+    pub fn last(&self, x: usize) -> usize {
         self.find(x + 1) - 1
     }
 }
@@ -1,4 +1,5 @@
 //! Bitmap with rank and select support read from an HDT file.
+use crate::containers::Verify;
 use crate::containers::vbyte::{encode_vbyte, read_vbyte};
 use bytesize::ByteSize;
 use qwt::mem_dbg::{MemSize, SizeFlags};
@@ -90,8 +91,8 @@ impl Bitmap {
         self.dict.get(word_index).expect("word index out of bounds")
     }
 
-    /// Read bitmap from a suitable point within HDT file data and verify checksums.
-    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
+    /// Read and validate the bitmap metadata, returning the number of bits.
+    pub(crate) fn read_meta<R: BufRead>(reader: &mut R, verify: Verify) -> Result<usize> {
         use Error::*;
         let mut history: Vec<u8> = Vec::with_capacity(5);
 
@@ -117,9 +118,21 @@ impl Bitmap {
         let mut digest = crc8.digest();
         digest.update(&history);
         let crc_calculated = digest.finalize();
-        if crc_calculated != crc_code {
+        if verify != Verify::None && crc_calculated != crc_code {
             return Err(InvalidCrc8Checksum(crc_calculated, crc_code));
         }
+        Ok(num_bits)
+    }
+
+    /// Read bitmap from a suitable point within HDT file data and verify checksums.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
+        Self::read_with(reader, Verify::Full)
+    }
+
+    /// Read bitmap from a suitable point within HDT file data and verify the given checksums.
+    pub fn read_with<R: BufRead>(reader: &mut R, verify: Verify) -> Result<Self> {
+        use Error::*;
+        let num_bits = Self::read_meta(reader, verify)?;
 
         // read all but the last word, last word is byte aligned
         let full_byte_amount = ((num_bits - 1) >> 6) * 8;
@@ -135,7 +148,10 @@ impl Bitmap {
         // initiate computation of CRC32
         let crc32 = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
         let mut digest = crc32.digest();
-        digest.update(&full_words);
+        let full = verify == Verify::Full;
+        if full {
+            digest.update(&full_words);
+        }
 
         let mut bits_read = 0;
         let mut last_value: u64 = 0;
@@ -144,7 +160,9 @@ impl Bitmap {
         while bits_read < last_word_bits {
             let mut buffer = [0u8];
             reader.read_exact(&mut buffer)?;
-            digest.update(&buffer);
+            if full {
+                digest.update(&buffer);
+            }
             last_value |= (buffer[0] as u64) << bits_read;
             bits_read += 8;
         }
@@ -158,7 +176,7 @@ impl Bitmap {
         // validate entry body CRC32
         // not worth it to spawn an extra thread as our bitmaps are comparatively small
         let crc_calculated = digest.finalize();
-        if crc_calculated != crc_code {
+        if full && crc_calculated != crc_code {
             return Err(InvalidCrc32Checksum(crc_calculated, crc_code));
         }
         Ok(Self::new(&data))
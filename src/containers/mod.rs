@@ -16,3 +16,16 @@ pub use adj_list::AdjList;
 pub use bitmap::Bitmap;
 pub use control_info::{ControlInfo, ControlType};
 pub use sequence::Sequence;
+
+/// Which checksums are verified when reading HDT data.
+/// Control information is always checked as its checksum covers only a few bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Verify {
+    /// Verify the CRC8 checksums of the section metadata and the CRC32 checksums of the section data.
+    #[default]
+    Full,
+    /// Only verify the CRC8 checksums of the section metadata, which is much faster for large files.
+    Headers,
+    /// Don't verify any section checksums, only use this for trusted files.
+    None,
+}
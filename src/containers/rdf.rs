@@ -16,6 +16,22 @@ impl Triple {
     pub const fn new(subject: Id, predicate: String, object: Term) -> Self {
         Triple { subject, predicate, object }
     }
+
+    /// Size in bytes including the strings on the heap.
+    pub(crate) fn size_in_bytes(&self) -> usize {
+        let id = |id: &Id| match id {
+            Id::Named(s) | Id::Blank(s) => s.capacity(),
+        };
+        let object = match &self.object {
+            Term::Id(o) => id(o),
+            Term::Literal(l) => {
+                l.form.capacity()
+                    + l.datatype.as_ref().map_or(0, String::capacity)
+                    + l.lang.as_ref().map_or(0, String::capacity)
+            }
+        };
+        size_of::<Self>() + id(&self.subject) + self.predicate.capacity() + object
+    }
 }
 
 /// ntriples format
@@ -1,3 +1,4 @@
+use super::Verify;
 use super::vbyte::encode_vbyte;
 use crate::containers::vbyte::read_vbyte;
 use bytesize::ByteSize;
@@ -21,6 +22,7 @@ pub type Result<T> = core::result::Result<T, Error>;
 /// We could still use an off-the-shelf library for the data which may be even more optimized but they often don't allow you to write or read to the internal data which makes constructing and writing it less comfortable and performant.
 // Update: Now that we are evaluating switching from sucds to QWT, which does not seem to contain such a type, such helper functions could be useful.
 //#[derive(Clone)]
+#[derive(PartialEq, Eq)]
 #[cfg_attr(feature = "cache", derive(Deserialize, Serialize))]
 pub struct Sequence {
     /// Number of integers in the sequence.
@@ -133,8 +135,8 @@ impl Sequence {
         (self.data.len() * USIZE_BITS) >> 3
     }
 
-    /// Read sequence including metadata from HDT data.
-    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
+    /// Read and validate the sequence metadata, returning the number of bits per entry and the number of entries.
+    pub(crate) fn read_meta<R: BufRead>(reader: &mut R, verify: Verify) -> Result<(usize, usize)> {
         // read entry metadata
         // keep track of history for CRC8
         let mut history = Vec::<u8>::new();
@@ -169,9 +171,42 @@ impl Sequence {
         digest.update(&history);
 
         let crc_calculated = digest.finalize();
-        if crc_calculated != crc_code {
+        if verify != Verify::None && crc_calculated != crc_code {
             return Err(Error::InvalidCrc8Checksum(crc_calculated, crc_code));
         }
+        Ok((bits_per_entry, entries))
+    }
+
+    /// Skip over a sequence in HDT data without allocating or validating its body.
+    pub fn skip<R: BufRead>(reader: &mut R) -> Result<()> {
+        let (bits_per_entry, entries) = Self::read_meta(reader, Verify::Headers)?;
+        // body is byte aligned and followed by a CRC32
+        let len = (bits_per_entry * entries).div_ceil(8) as u64 + 4;
+        if std::io::copy(&mut std::io::Read::take(reader, len), &mut std::io::sink())? < len {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(())
+    }
+
+    /// Read sequence including metadata from HDT data.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
+        Self::read_with(reader, Verify::Full)
+    }
+
+    /// Read sequence including metadata from HDT data, verifying the given checksums.
+    pub fn read_with<R: BufRead>(reader: &mut R, verify: Verify) -> Result<Self> {
+        let (sequence, crc_code) = Self::read_unverified(reader, verify)?;
+        if let Some(crc_code) = crc_code {
+            sequence.verify_crc32(crc_code)?;
+        }
+        Ok(sequence)
+    }
+
+    /// Read sequence including metadata from HDT data without verifying the CRC32 checksum of the body.
+    /// Returns the expected checksum in full verification mode so that it can be verified later, for example in parallel
+    /// with building indexes, using [`Sequence::verify_crc32`].
+    pub fn read_unverified<R: BufRead>(reader: &mut R, verify: Verify) -> Result<(Self, Option<u32>)> {
+        let (bits_per_entry, entries) = Self::read_meta(reader, verify)?;
 
         // read body data
         // read all but the last entry, since the last one is byte aligned
@@ -186,9 +221,8 @@ impl Sequence {
         for word in full_words.chunks_exact(size_of::<usize>()) {
             data.push(usize::from_le_bytes(<[u8; size_of::<usize>()]>::try_from(word)?));
         }
+        drop(full_words);
 
-        // keep track of history for CRC32
-        let mut history = full_words;
         // read the last few bits, byte aligned
         let mut bits_read = 0;
         let mut last_value: usize = 0;
@@ -197,7 +231,6 @@ impl Sequence {
         while bits_read < last_entry_bits {
             let mut buffer = [0u8];
             reader.read_exact(&mut buffer)?;
-            history.extend_from_slice(&buffer);
             last_value |= (buffer[0] as usize) << bits_read;
             bits_read += 8;
         }
@@ -205,20 +238,34 @@ impl Sequence {
         // read entry body CRC32
         let mut crc_code = [0_u8; 4];
         reader.read_exact(&mut crc_code)?;
+        let crc_code = (verify == Verify::Full).then(|| u32::from_le_bytes(crc_code));
+
+        Ok((Sequence { entries, bits_per_entry, data }, crc_code))
+    }
 
-        let crc_code32 = u32::from_le_bytes(crc_code);
-        //let start = std::time::Instant::now();
-        // validate entry body CRC32
+    /// Verify the CRC32 checksum of the body, which is computed over the same bytes that are read and written.
+    pub fn verify_crc32(&self, crc_code: u32) -> Result<()> {
         let crc32 = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
         let mut digest = crc32.digest();
-        digest.update(&history);
-        let crc_calculated32 = digest.finalize();
-        //println!("Sequence of {} validated in {:?}", ByteSize(history.len() as u64), start.elapsed());
-        if crc_calculated32 != crc_code32 {
-            return Err(Error::InvalidCrc32Checksum(crc_calculated32, crc_code32));
+        // unused zero bytes in the last usize are not part of the serialization
+        let mut remaining = (self.bits_per_entry * self.entries).div_ceil(8);
+        for word in &self.data {
+            let bytes = word.to_le_bytes();
+            let n = remaining.min(bytes.len());
+            digest.update(&bytes[..n]);
+            remaining -= n;
         }
+        let crc_calculated = digest.finalize();
+        if crc_calculated != crc_code {
+            return Err(Error::InvalidCrc32Checksum(crc_calculated, crc_code));
+        }
+        Ok(())
+    }
 
-        Ok(Sequence { entries, bits_per_entry, data })
+    /// Number of bytes that [`Sequence::write`] produces for the given number of entries and bits per entry.
+    pub(crate) fn disk_size(entries: usize, bits_per_entry: usize) -> usize {
+        // type, bits per entry, number of entries, CRC8, data, CRC32
+        1 + 1 + encode_vbyte(entries).len() + 1 + (bits_per_entry * entries).div_ceil(8) + 4
     }
 
     /// save sequence per HDT spec using CRC
@@ -345,8 +392,8 @@ impl Sequence {
     /// `Vec<usize>` intermediate. The values are widened to `usize` during
     /// bit-packing only — no separate `Vec<usize>` is ever allocated.
     ///
-    /// Used by the op-index builder where every value fits in `u32` (they
-    /// are Y-level positions capped at ~2^28 for any realistic HDT).
+    /// Used by the op-index builder, whose Y-level positions are capped at ~2^28
+    /// for any realistic HDT, and for the 32 bit IDs of new triples.
     pub fn new_from_u32(numbers: &[u32]) -> Sequence {
         let entries = numbers.len();
         if entries == 0 {
@@ -403,12 +450,6 @@ mod tests {
     use crate::tests::init;
     use pretty_assertions::assert_eq;
 
-    impl PartialEq for Sequence {
-        fn eq(&self, other: &Self) -> bool {
-            self.entries == other.entries && self.bits_per_entry == other.bits_per_entry && self.data == other.data
-        }
-    }
-
     #[test]
     fn write_read() -> color_eyre::Result<()> {
         init();
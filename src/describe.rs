@@ -0,0 +1,248 @@
+//! Descriptions of resources and subgraph extraction, see [`Hdt::describe`] and [`Hdt::describe_ids`].
+//! The traversal runs in ID space, terms are only decoded to check whether a reached node is a blank node or a literal.
+use crate::hdt::TripleCache;
+use crate::triples::{Id, ObjectIter, SubjectIter, TripleId};
+use crate::{Hdt, IdKind};
+use log::error;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+/// Which triples around the seed resources [`Hdt::describe_ids`] collects.
+/// The default is the Concise Bounded Description (CBD).
+#[derive(Debug, Clone)]
+pub struct DescribeOptions {
+    /// Number of hops from the seeds: 1 only collects the triples of the seeds themselves,
+    /// 2 also those of their neighbors and so on. 0 collects nothing.
+    pub depth: usize,
+    /// Blank node neighbors are described as well, without counting towards the depth, like in a CBD.
+    pub follow_blank_nodes: bool,
+    /// Also collect incoming triples, whose subjects are neighbors, which results in a Symmetric CBD.
+    pub symmetric: bool,
+    /// Hops to neighbors beyond the seeds are only taken along these predicates, along all if empty.
+    /// Blank nodes are followed along any predicate.
+    pub predicates: Vec<String>,
+}
+
+impl Default for DescribeOptions {
+    fn default() -> Self {
+        Self { depth: 1, follow_blank_nodes: true, symmetric: false, predicates: Vec::new() }
+    }
+}
+
+/// A node of the traversal as a pair of its subject and object ID, where 0 means that it does not occur in that position.
+/// Shared terms have the same ID in both positions, so each term has exactly one such pair.
+type Node = (Id, Id);
+
+impl Hdt {
+    /// The Concise Bounded Description of a resource with the given depth, see [`DescribeOptions`] for the parameters.
+    /// Triples are returned in SPO order.
+    /// # Example
+    /// ```
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let cbd: Vec<_> = hdt.describe("http://www.snik.eu/ontology/meta/Top", 1, true).collect();
+    /// assert!(cbd.iter().all(|[s, _, _]| s.as_ref() == "http://www.snik.eu/ontology/meta/Top" || s.starts_with("_:")));
+    /// ```
+    pub fn describe(
+        &self, iri: &str, depth: usize, follow_blank_nodes: bool,
+    ) -> impl Iterator<Item = [Arc<str>; 3]> + '_ {
+        let options = DescribeOptions { depth, follow_blank_nodes, ..Default::default() };
+        self.translate_all(self.describe_ids(&[iri], &options))
+    }
+
+    /// Like [`Hdt::describe`] but also includes the incoming triples of the resource, found with [`ObjectIter`].
+    pub fn describe_symmetric(
+        &self, iri: &str, depth: usize, follow_blank_nodes: bool,
+    ) -> impl Iterator<Item = [Arc<str>; 3]> + '_ {
+        let options = DescribeOptions { depth, follow_blank_nodes, symmetric: true, ..Default::default() };
+        self.translate_all(self.describe_ids(&[iri], &options))
+    }
+
+    /// IDs of the distinct triples that describe the given seed terms, sorted in SPO order.
+    /// Seeds that are not in the dictionary are ignored.
+    /// The graph is traversed breadth-first, so each node is described once at its shortest distance from the seeds.
+    pub fn describe_ids(&self, seeds: &[&str], options: &DescribeOptions) -> Vec<TripleId> {
+        let shared = self.dict.shared.num_strings() as Id;
+        let predicates: Option<HashSet<Id>> = (!options.predicates.is_empty())
+            .then(|| options.predicates.iter().map(|p| self.dict.string_to_id(p, IdKind::Predicate)).collect());
+        // nodes with their number of hops, blank nodes are pushed to the front as they don't add a hop
+        let mut queue: VecDeque<(Node, usize)> = seeds
+            .iter()
+            .map(|s| (self.dict.string_to_id(s, IdKind::Subject), self.dict.string_to_id(s, IdKind::Object)))
+            .filter(|&node| node != (0, 0))
+            .map(|node| (node, 0))
+            .collect();
+        let mut described = HashSet::<Node>::new();
+        let mut triples = Vec::new();
+        while let Some((node @ (s, o), hops)) = queue.pop_front() {
+            if hops >= options.depth || !described.insert(node) {
+                continue;
+            }
+            let mut neighbor = |node: Node, kind: IdKind, p: Id| {
+                let id = if matches!(kind, IdKind::Subject) { node.0 } else { node.1 };
+                let term = match self.dict.id_to_string(id, kind) {
+                    Ok(term) => term,
+                    Err(e) => {
+                        error!("{e}");
+                        return;
+                    }
+                };
+                if options.follow_blank_nodes && term.starts_with("_:") {
+                    queue.push_front((node, hops));
+                } else if !term.starts_with('"') && predicates.as_ref().is_none_or(|ps| ps.contains(&p)) {
+                    queue.push_back((node, hops + 1));
+                }
+            };
+            if s != 0 {
+                for t @ [_, p, object] in SubjectIter::with_pattern(&self.triples, [s, 0, 0]) {
+                    triples.push(t);
+                    let node = (if object <= shared { object } else { 0 }, object);
+                    if !described.contains(&node) {
+                        neighbor(node, IdKind::Object, p);
+                    }
+                }
+            }
+            if options.symmetric && o != 0 {
+                for t @ [subject, p, _] in ObjectIter::new(&self.triples, o) {
+                    triples.push(t);
+                    let node = (subject, if subject <= shared { subject } else { 0 });
+                    if !described.contains(&node) {
+                        neighbor(node, IdKind::Subject, p);
+                    }
+                }
+            }
+        }
+        triples.sort_unstable();
+        triples.dedup();
+        triples
+    }
+
+    /// Extracts the subgraph around the seeds into a new HDT written to `write` and returns its number of triples.
+    /// The subgraph consists of the Concise Bounded Descriptions of all nodes that are reachable from the seeds
+    /// within `max_hops` steps along the given predicates, or along all predicates if none are given.
+    /// The base IRI denotes the new dataset in the header.
+    /// *This function is available only if HDT is built with the experimental `"nt"` feature.*
+    /// # Example
+    /// ```
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let seeds = ["http://www.snik.eu/ontology/meta/Top"];
+    /// let subclass_of = ["http://www.w3.org/2000/01/rdf-schema#subClassOf"];
+    /// let mut buffer = Vec::new();
+    /// let n = hdt.extract_subgraph(&seeds, &subclass_of, 2, "http://example.org/top", &mut buffer).unwrap();
+    /// assert_eq!(hdt::Hdt::read(buffer.as_slice()).unwrap().triples.len(), n);
+    /// ```
+    #[cfg(feature = "nt")]
+    pub fn extract_subgraph(
+        &self, seed_iris: &[&str], predicates_to_follow: &[&str], max_hops: usize, base_iri: &str,
+        write: &mut impl std::io::Write,
+    ) -> crate::hdt::Result<usize> {
+        let options = DescribeOptions {
+            depth: max_hops + 1,
+            predicates: predicates_to_follow.iter().map(|&p| p.to_owned()).collect(),
+            ..Default::default()
+        };
+        let ids = self.describe_ids(seed_iris, &options);
+        let n = ids.len();
+        Hdt::from_triples(self.translate_all(ids), base_iri)?.write(write)?;
+        Ok(n)
+    }
+
+    /// Translates triple IDs to string triples, skipping and logging those that cannot be translated.
+    pub(crate) fn translate_all(&self, ids: Vec<TripleId>) -> impl Iterator<Item = [Arc<str>; 3]> + '_ {
+        let mut cache = TripleCache::default();
+        ids.into_iter().filter_map(move |t| cache.translate(&self.dict, t).map_err(|e| error!("{e}")).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+    use crate::vocab::RDF_TYPE;
+    use color_eyre::Result;
+
+    const META: &str = "http://www.snik.eu/ontology/meta/";
+    const SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+
+    #[test]
+    fn describe() -> Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let top = format!("{META}Top");
+        let outgoing: Vec<_> = hdt.triples_with_pattern(Some(&top), None, None).collect();
+        let cbd: Vec<_> = hdt.describe(&top, 1, false).collect();
+        assert_eq!(cbd.len(), outgoing.len());
+        assert!(hdt.describe(&top, 0, true).next().is_none());
+        assert!(hdt.describe("http://example.org/missing", 3, true).next().is_none());
+        // blank nodes are described as well, the owl:Restrictions of snikmeta are blank nodes
+        let blank = hdt
+            .triples_with_pattern(None, Some(RDF_TYPE), Some("http://www.w3.org/2002/07/owl#Restriction"))
+            .map(|[s, _, _]| s)
+            .find(|s| s.starts_with("_:"))
+            .expect("blank node restriction in snikmeta");
+        let owner =
+            hdt.triples_with_pattern(None, None, Some(&blank)).next().expect("referenced restriction")[0].clone();
+        assert!(hdt.describe(&owner, 1, true).any(|[s, _, _]| s == blank));
+        assert!(!hdt.describe(&owner, 1, false).any(|[s, _, _]| s == blank));
+        // a depth of 2 includes the triples of the neighbors
+        let neighbors: usize = outgoing
+            .iter()
+            .filter(|[_, _, o]| !o.starts_with('"') && **o != *top)
+            .map(|[_, _, o]| o.clone())
+            .collect::<HashSet<_>>()
+            .iter()
+            .map(|o| hdt.triples_with_pattern(Some(o), None, None).count())
+            .sum();
+        assert_eq!(hdt.describe(&top, 2, false).count(), outgoing.len() + neighbors);
+        // the symmetric description adds the incoming triples
+        let incoming = hdt.triples_with_pattern(None, None, Some(&top)).count();
+        assert!(incoming > 0);
+        assert_eq!(hdt.describe_symmetric(&top, 1, false).count(), cbd.len() + incoming);
+        Ok(())
+    }
+
+    #[test]
+    fn describe_ids() -> Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let top = format!("{META}Top");
+        let options =
+            DescribeOptions { depth: 10, predicates: vec![SUB_CLASS_OF.to_owned()], ..Default::default() };
+        let ids = hdt.describe_ids(&[&top], &options);
+        assert!(ids.is_sorted());
+        let subjects: HashSet<Id> = ids.iter().map(|t| t[0]).collect();
+        // superclasses of Top are described as well
+        let sub_class_of = hdt.dict.string_to_id(SUB_CLASS_OF, IdKind::Predicate);
+        let top_id = hdt.dict.string_to_id(&top, IdKind::Subject);
+        for [_, _, o] in SubjectIter::with_pattern(&hdt.triples, [top_id, sub_class_of, 0]) {
+            assert!(o > hdt.dict.shared.num_strings() as Id || subjects.contains(&o));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn extract_subgraph() -> Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let top = format!("{META}Top");
+        let mut buffer = Vec::new();
+        let n = hdt.extract_subgraph(&[&top], &[SUB_CLASS_OF], 1, "http://example.org/top", &mut buffer)?;
+        let extracted = Hdt::read(buffer.as_slice())?;
+        assert_eq!(extracted.triples.len(), n);
+        assert!(n < hdt.triples.len());
+        // the descriptions of Top and of its direct superclasses
+        let sub_class_of: Vec<_> = hdt.triples_with_pattern(Some(&top), Some(SUB_CLASS_OF), None).collect();
+        assert!(!sub_class_of.is_empty());
+        for [_, _, class] in sub_class_of {
+            for [s, p, o] in hdt.describe(&class, 1, true) {
+                assert_eq!(extracted.triples_with_pattern(Some(&s), Some(&p), Some(&o)).count(), 1);
+            }
+        }
+        for [s, p, o] in hdt.describe(&top, 1, true) {
+            assert_eq!(extracted.triples_with_pattern(Some(&s), Some(&p), Some(&o)).count(), 1);
+        }
+        Ok(())
+    }
+}
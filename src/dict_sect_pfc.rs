@@ -2,9 +2,10 @@
 /// Dictionary section with plain front coding.
 /// See <https://www.rdfhdt.org/hdt-binary-format/#DictionarySectionPlainFrontCoding>.
 use crate::containers::vbyte::{decode_vbyte_delta, encode_vbyte, read_vbyte};
-use crate::containers::{Sequence, sequence};
+use crate::containers::{Sequence, Verify, sequence};
 use crate::triples::Id;
 use bytesize::ByteSize;
+use std::borrow::Cow;
 use std::cmp::{Ordering, min};
 use std::collections::BTreeSet;
 use std::io::{BufRead, Write};
@@ -17,11 +18,11 @@ use thiserror::Error;
 pub type Result<T> = core::result::Result<T, Error>;
 
 /// Type alias for the internal read result to reduce complexity
-type ReadInternalResult = (usize, usize, Sequence, Arc<[u8]>, [u8; 4]);
+type ReadInternalResult = (usize, usize, Sequence, Arc<[u8]>, Option<[u8; 4]>);
 
 /// Dictionary section with plain front coding.
 //#[derive(Clone)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(PartialEq, Eq)]
 pub struct DictSectPFC {
     /// total number of strings stored
     pub num_strings: usize,
@@ -46,6 +47,8 @@ pub enum Error {
     DictSectNotPfc(u8),
     #[error("sequence read error")]
     Sequence(#[from] sequence::Error),
+    #[error("dictionary section with {0} strings exceeds the maximum ID {max}", max = Id::MAX)]
+    TooManyStrings(usize),
 }
 
 impl fmt::Debug for DictSectPFC {
@@ -126,6 +129,29 @@ impl DictSectPFC {
         ((mid * self.block_size) + idblock + 1) as Id
     }
 
+    /// The ID of the first string that is not less than the given one, or the number of strings plus one if there is none.
+    pub fn lower_bound(&self, element: &str) -> Id {
+        let num_blocks = self.num_strings.div_ceil(self.block_size);
+        // first block whose first string is not less than the element
+        let (mut low, mut high) = (0, num_blocks);
+        while low < high {
+            let mid = usize::midpoint(low, high);
+            if self.index_str(mid) < element {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            return 1;
+        }
+        // all strings of the following block are not less, so the result is in the previous block or starts the following one
+        let start = (low - 1) * self.block_size;
+        let end = min(low * self.block_size, self.num_strings);
+        (start + 1..=end).find(|&i| self.extract(i as Id).is_ok_and(|s| s.as_str() >= element)).unwrap_or(end + 1)
+            as Id
+    }
+
     fn longest_common_prefix(a: &[u8], b: &[u8]) -> usize {
         let len = min(a.len(), b.len());
         let mut delta = 0;
@@ -184,6 +210,42 @@ impl DictSectPFC {
 
     /// extract the string with the given ID between 1 and self.num_strings (inclusive) from the dictionary
     pub fn extract(&self, id: Id) -> core::result::Result<String, ExtractError> {
+        let mut string = String::new();
+        self.extract_into(id, &mut string)?;
+        Ok(string)
+    }
+
+    /// Like [`DictSectPFC::extract`] but writes into the given buffer, whose previous content is replaced.
+    /// Reusing the buffer avoids allocating a new string for each ID.
+    pub fn extract_into(&self, id: Id, buf: &mut String) -> core::result::Result<(), ExtractError> {
+        let mut bytes = std::mem::take(buf).into_bytes();
+        self.decode(id, &mut bytes)?;
+        match String::from_utf8(bytes) {
+            Ok(string) => {
+                *buf = string;
+                Ok(())
+            }
+            Err(e) => Err(Self::utf8_error(e.utf8_error(), e.as_bytes())),
+        }
+    }
+
+    /// Like [`DictSectPFC::extract`] but borrows the first string of each block, which is stored verbatim.
+    /// The other strings are front coded and need to be decoded into a new string.
+    pub fn extract_cow(&self, id: Id) -> core::result::Result<Cow<'_, str>, ExtractError> {
+        if id as usize > self.num_strings {
+            return Err(ExtractError::IdOutOfBounds { id, len: self.num_strings });
+        }
+        let index = id.saturating_sub(1) as usize;
+        if index.is_multiple_of(self.block_size) {
+            let position = self.sequence.get(index / self.block_size);
+            let bytes = &self.packed_data[position..position + self.strlen(position)];
+            return str::from_utf8(bytes).map(Cow::Borrowed).map_err(|e| Self::utf8_error(e, bytes));
+        }
+        self.extract(id).map(Cow::Owned)
+    }
+
+    /// Decode the bytes of the string with the given ID into the buffer, which is cleared first.
+    fn decode(&self, id: Id, string: &mut Vec<u8>) -> core::result::Result<(), ExtractError> {
         if id as usize > self.num_strings {
             return Err(ExtractError::IdOutOfBounds { id, len: self.num_strings });
         }
@@ -191,7 +253,8 @@ impl DictSectPFC {
         let string_index = id.saturating_sub(1) as usize % self.block_size;
         let mut position = self.sequence.get(block_index);
         let mut slen = self.strlen(position);
-        let mut string: Vec<u8> = self.packed_data[position..position + slen].to_vec();
+        string.clear();
+        string.extend_from_slice(&self.packed_data[position..position + slen]);
         //println!("block_index={} string_index={}, string={}", block_index, string_index, str::from_utf8(&string).unwrap());
         // loop takes around nearly half the time of the function
         for _ in 0..string_index {
@@ -202,14 +265,43 @@ impl DictSectPFC {
             string.truncate(delta);
             string.extend_from_slice(&self.packed_data[position..position + slen]);
         }
+        Ok(())
+    }
+
+    /// extract all strings of the block with the given index, which is 0 for the block starting with ID 1
+    pub fn extract_block(&self, block_index: usize) -> core::result::Result<Vec<String>, ExtractError> {
+        let start = block_index * self.block_size;
+        if start >= self.num_strings {
+            return Err(ExtractError::IdOutOfBounds { id: (start + 1) as Id, len: self.num_strings });
+        }
+        let count = min(self.block_size, self.num_strings - start);
+        let mut strings = Vec::with_capacity(count);
+        let mut position = self.sequence.get(block_index);
+        let mut slen = self.strlen(position);
+        let mut string: Vec<u8> = self.packed_data[position..position + slen].to_vec();
+        strings.push(Self::decode_utf8(&string)?);
+        for _ in 1..count {
+            position += slen + 1;
+            let (delta, vbyte_bytes) = decode_vbyte_delta(&self.packed_data, position);
+            position += vbyte_bytes;
+            slen = self.strlen(position);
+            string.truncate(delta);
+            string.extend_from_slice(&self.packed_data[position..position + slen]);
+            strings.push(Self::decode_utf8(&string)?);
+        }
+        Ok(strings)
+    }
+
+    fn decode_utf8(string: &[u8]) -> core::result::Result<String, ExtractError> {
         // tried simdutf8::basic::from_utf8 but that didn't speed up extract that much
-        match str::from_utf8(&string) {
-            Ok(string) => Ok(String::from(string)),
-            Err(e) => Err(ExtractError::InvalidUtf8 {
-                source: e,
-                data: string.clone(),
-                recovered: String::from_utf8_lossy(&string).into_owned(),
-            }),
+        str::from_utf8(string).map(String::from).map_err(|e| Self::utf8_error(e, string))
+    }
+
+    fn utf8_error(source: str::Utf8Error, data: &[u8]) -> ExtractError {
+        ExtractError::InvalidUtf8 {
+            source,
+            data: data.to_vec(),
+            recovered: String::from_utf8_lossy(data).into_owned(),
         }
     }
 
@@ -229,9 +321,8 @@ impl DictSectPFC {
         self.num_strings
     }
 
-    /// Common parsing logic for reading a dictionary section.
-    /// Returns the parsed components and the CRC32 code to be verified.
-    fn read_internal<R: BufRead>(reader: &mut R) -> Result<ReadInternalResult> {
+    /// Reads and validates the section metadata, returning the number of strings, packed data length and block size.
+    fn read_meta<R: BufRead>(reader: &mut R, verify: Verify) -> Result<(usize, usize, usize)> {
         // https://www.rdfhdt.org/hdt-binary-format/#DictionarySection specifies "unsigned 32bit value preamble" but 8bit is used in practice
         let mut preamble = [0_u8];
         reader.read_exact(&mut preamble)?;
@@ -248,6 +339,9 @@ impl DictSectPFC {
         // https://www.rdfhdt.org/hdt-binary-format was inaccurate, it's 3 vbytes, not 2.
         let (num_strings, bytes_read) = read_vbyte(reader)?;
         digest8.update(&bytes_read);
+        if Id::try_from(num_strings).is_err() {
+            return Err(Error::TooManyStrings(num_strings));
+        }
         //println!("num strings {num_strings}");
         let (packed_length, bytes_read) = read_vbyte(reader)?;
         digest8.update(&bytes_read);
@@ -262,11 +356,30 @@ impl DictSectPFC {
         //println!("crc_code {crc_code8}");
 
         let crc_calculated8 = digest8.finalize();
-        if crc_calculated8 != crc_code8 {
+        if verify != Verify::None && crc_calculated8 != crc_code8 {
             return Err(Error::InvalidCrc8Checksum(crc_calculated8, crc_code8));
         }
+        Ok((num_strings, packed_length, block_size))
+    }
+
+    /// Skips over a dictionary section without allocating or validating its data.
+    pub fn skip<R: BufRead>(reader: &mut R) -> Result<()> {
+        let (_, packed_length, _) = Self::read_meta(reader, Verify::Headers)?;
+        Sequence::skip(reader)?;
+        // packed data followed by CRC32
+        let len = packed_length as u64 + 4;
+        if std::io::copy(&mut std::io::Read::take(reader, len), &mut std::io::sink())? < len {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(())
+    }
+
+    /// Common parsing logic for reading a dictionary section.
+    /// Returns the parsed components and the CRC32 code to be verified in full verification mode.
+    fn read_internal<R: BufRead>(reader: &mut R, verify: Verify) -> Result<ReadInternalResult> {
+        let (num_strings, packed_length, block_size) = Self::read_meta(reader, verify)?;
         // read sequence log array
-        let sequence = Sequence::read(reader)?;
+        let sequence = Sequence::read_with(reader, verify)?;
         //println!("read sequence of length {} {:?}", sequence.data.len(), sequence.data);
 
         // read packed data
@@ -278,14 +391,19 @@ impl DictSectPFC {
         // read packed data CRC32
         let mut crc_code = [0u8; 4];
         reader.read_exact(&mut crc_code)?;
+        let crc_code = (verify == Verify::Full).then_some(crc_code);
 
         Ok((num_strings, block_size, sequence, packed_data, crc_code))
     }
 
-    /// Verifies the CRC32 checksum and constructs the DictSectPFC.
+    /// Verifies the CRC32 checksum, if given, and constructs the DictSectPFC.
     fn verify_and_construct(
-        num_strings: usize, block_size: usize, sequence: Sequence, packed_data: Arc<[u8]>, crc_code: [u8; 4],
+        num_strings: usize, block_size: usize, sequence: Sequence, packed_data: Arc<[u8]>,
+        crc_code: Option<[u8; 4]>,
     ) -> Result<Self> {
+        let Some(crc_code) = crc_code else {
+            return Ok(DictSectPFC { num_strings, block_size, sequence, packed_data });
+        };
         let crc32 = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
         let mut digest32 = crc32.digest();
         digest32.update(&packed_data[..]);
@@ -300,14 +418,26 @@ impl DictSectPFC {
     /// Returns an unverified dictionary section together with a handle to verify the checksum.
     #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
     pub fn read<R: BufRead>(reader: &mut R) -> Result<JoinHandle<Result<Self>>> {
-        let (num_strings, block_size, sequence, packed_data, crc_code) = Self::read_internal(reader)?;
+        Self::read_with(reader, Verify::Full)
+    }
+
+    /// Returns an unverified dictionary section together with a handle to verify the given checksums.
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+    pub fn read_with<R: BufRead>(reader: &mut R, verify: Verify) -> Result<JoinHandle<Result<Self>>> {
+        let (num_strings, block_size, sequence, packed_data, crc_code) = Self::read_internal(reader, verify)?;
         Ok(spawn(move || Self::verify_and_construct(num_strings, block_size, sequence, packed_data, crc_code)))
     }
 
     /// WASM-specific version that returns result directly without threading
     #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
     pub fn read<R: BufRead>(reader: &mut R) -> Result<Self> {
-        let (num_strings, block_size, sequence, packed_data, crc_code) = Self::read_internal(reader)?;
+        Self::read_with(reader, Verify::Full)
+    }
+
+    /// WASM-specific version that returns result directly without threading
+    #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
+    pub fn read_with<R: BufRead>(reader: &mut R, verify: Verify) -> Result<Self> {
+        let (num_strings, block_size, sequence, packed_data, crc_code) = Self::read_internal(reader, verify)?;
         Self::verify_and_construct(num_strings, block_size, sequence, packed_data, crc_code)
     }
 
@@ -509,8 +639,9 @@ mod tests {
         let set: BTreeSet<&str> = BTreeSet::from(strings);
         let dict = DictSectPFC::compress(&set, BLOCK_SIZE);
         // could add this as DictSectPFC::items if required elsewhere
-        let sect_items =
-            |ds: &DictSectPFC| -> Vec<String> { (1..=ds.num_strings()).map(|i| ds.extract(i).unwrap()).collect() };
+        let sect_items = |ds: &DictSectPFC| -> Vec<String> {
+            (1..=ds.num_strings() as Id).map(|i| ds.extract(i).unwrap()).collect()
+        };
         //let items: Vec<String> = (1..dict.num_strings() + 1).map(|i| dict.extract(i).unwrap()).collect();
         let items = sect_items(&dict);
         assert_eq!(string_vec, items);
@@ -530,4 +661,17 @@ mod tests {
         assert_eq!(0, DictSectPFC::compress(&BTreeSet::new(), BLOCK_SIZE).num_strings);
         Ok(())
     }
+
+    #[test]
+    fn too_many_strings() {
+        init();
+        // section preamble with 2^32 strings, an empty packed data length and block size 16
+        let mut meta = vec![2u8];
+        meta.extend(encode_vbyte(1 << 32));
+        meta.extend(encode_vbyte(0));
+        meta.extend(encode_vbyte(16));
+        meta.push(crc::Crc::<u8>::new(&crc::CRC_8_SMBUS).checksum(&meta));
+        let result = DictSectPFC::read_meta(&mut std::io::Cursor::new(meta), Verify::Full);
+        assert!(matches!(result, Err(Error::TooManyStrings(n)) if n == 1 << 32), "{result:?}");
+    }
 }
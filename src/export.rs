@@ -0,0 +1,188 @@
+//! Streaming export to the RDF serialization formats of [oxrdfio](https://crates.io/crates/oxrdfio):
+//! N-Triples, N-Quads, Turtle, TriG, RDF/XML and JSON-LD.
+//! *This module is available only if HDT is built with the `"export"` feature.*
+//! # Example
+//! ```
+//! use hdt::export::{ExportOptions, RdfFormat};
+//! let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+//! let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+//! let options = ExportOptions { graph: Some("http://example.org/graph".to_owned()), ..Default::default() };
+//! let mut trig = Vec::new();
+//! hdt.write_rdf(&mut trig, RdfFormat::TriG, &options).unwrap();
+//! assert!(String::from_utf8(trig).unwrap().starts_with("@prefix"));
+//! ```
+use crate::Hdt;
+pub use crate::prefixes::DEFAULT_PREFIXES;
+use oxrdf::{BlankNode, GraphName, Literal, NamedNode, NamedOrBlankNode, Quad, Term};
+pub use oxrdfio::{JsonLdProfileSet, RdfFormat};
+use oxrdfio::{RdfSerializer, WriterQuadSerializer};
+use std::io::{self, Error, ErrorKind, Write};
+use std::str::FromStr;
+
+/// Options for [`Hdt::write_rdf`].
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// IRI of the graph that contains the triples in the dataset formats N-Quads, TriG and JSON-LD, the default graph if `None`.
+    pub graph: Option<String>,
+    /// Number of the most common namespaces of the dictionary that are declared as prefixes
+    /// in formats that support them, see [`Hdt::common_prefixes`].
+    pub prefixes: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self { graph: None, prefixes: DEFAULT_PREFIXES }
+    }
+}
+
+/// Converts a term in the HDT dictionary format to an [`oxrdf::Term`].
+/// Literals that are not valid N-Triples, for example because they contain unescaped line breaks,
+/// are split at the last quotation mark.
+pub fn hdt_str_to_term(s: &str) -> io::Result<Term> {
+    let invalid = |e: &dyn std::fmt::Display| Error::new(ErrorKind::InvalidData, format!("{e} in term {s}"));
+    if let Some(id) = s.strip_prefix("_:") {
+        return BlankNode::new(id).map(Term::from).map_err(|e| invalid(&e));
+    }
+    if !s.starts_with('"') {
+        return NamedNode::new(s).map(Term::from).map_err(|e| invalid(&e));
+    }
+    if let Ok(literal) = Literal::from_str(s) {
+        return Ok(literal.into());
+    }
+    let end = s.rfind('"').filter(|&end| end > 0).ok_or_else(|| invalid(&"missing right quotation mark"))?;
+    let (value, rest) = (&s[1..end], &s[end + 1..]);
+    if let Some(language) = rest.strip_prefix('@') {
+        Literal::new_language_tagged_literal(value, language).map(Term::from).map_err(|e| invalid(&e))
+    } else if let Some(datatype) = rest.strip_prefix("^^<").and_then(|dt| dt.strip_suffix('>')) {
+        let datatype = NamedNode::new(datatype).map_err(|e| invalid(&e))?;
+        Ok(Literal::new_typed_literal(value, datatype).into())
+    } else if rest.is_empty() {
+        Ok(Literal::new_simple_literal(value).into())
+    } else {
+        Err(invalid(&"invalid literal suffix"))
+    }
+}
+
+/// Streaming writer of string triples in the HDT dictionary format to any [`RdfFormat`].
+/// Consecutive triples with the same subject are grouped in formats that support it.
+pub struct RdfWriter<W: Write> {
+    serializer: WriterQuadSerializer<W>,
+    graph: GraphName,
+}
+
+impl<W: Write> RdfWriter<W> {
+    /// Creates a writer that declares the given (prefix name, namespace) pairs in formats that support prefixes
+    /// and puts the triples into the given graph in dataset formats, the graph is ignored for the other formats.
+    pub fn new(
+        write: W, format: RdfFormat, graph: Option<&str>, prefixes: &[(String, String)],
+    ) -> io::Result<Self> {
+        let invalid = |e| Error::new(ErrorKind::InvalidInput, e);
+        let graph = match graph {
+            Some(iri) if format.supports_datasets() => NamedNode::new(iri).map_err(invalid)?.into(),
+            _ => GraphName::DefaultGraph,
+        };
+        let mut serializer = RdfSerializer::from_format(format);
+        for (name, namespace) in prefixes {
+            serializer = serializer.with_prefix(name, namespace).map_err(invalid)?;
+        }
+        Ok(Self { serializer: serializer.for_writer(write), graph })
+    }
+
+    /// Writes a triple of terms in the HDT dictionary format.
+    pub fn write_triple(&mut self, [s, p, o]: [&str; 3]) -> io::Result<()> {
+        let subject = match hdt_str_to_term(s)? {
+            Term::NamedNode(n) => NamedOrBlankNode::NamedNode(n),
+            Term::BlankNode(b) => NamedOrBlankNode::BlankNode(b),
+            Term::Literal(_) => return Err(Error::new(ErrorKind::InvalidData, format!("literal subject {s}"))),
+        };
+        let predicate = NamedNode::new(p).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let object = hdt_str_to_term(o)?;
+        self.serializer.serialize_quad(&Quad::new(subject, predicate, object, self.graph.clone()))
+    }
+
+    /// Writes the end of the document, such as closing brackets, and returns the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.serializer.finish()
+    }
+}
+
+impl Hdt {
+    /// Writes all triples in the given format, streaming them in SPO order.
+    /// Use [`Hdt::par_write_nt`] for faster N-Triples output.
+    /// *This function is available only if HDT is built with the `"export"` feature.*
+    pub fn write_rdf(&self, write: impl Write, format: RdfFormat, options: &ExportOptions) -> io::Result<()> {
+        let prefixes = if options.prefixes > 0 { self.common_prefixes(options.prefixes) } else { Vec::new() };
+        let mut writer = RdfWriter::new(write, format, options.graph.as_deref(), &prefixes)?;
+        for [s, p, o] in self.triples_all() {
+            writer.write_triple([&s, &p, &o])?;
+        }
+        writer.finish()?.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+    use oxrdfio::{JsonLdProfileSet, RdfParser};
+
+    fn term_string(term: Term) -> String {
+        match term {
+            Term::NamedNode(n) => n.into_string(),
+            term => term.to_string(),
+        }
+    }
+
+    #[test]
+    fn formats() -> color_eyre::Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let mut expected: Vec<[String; 3]> =
+            hdt.triples_all().map(|[s, p, o]| [s, p, o].map(|t| t.to_string())).collect();
+        expected.sort_unstable();
+        let graph = "http://example.org/graph";
+        for format in [
+            RdfFormat::NTriples,
+            RdfFormat::NQuads,
+            RdfFormat::Turtle,
+            RdfFormat::TriG,
+            RdfFormat::RdfXml,
+            RdfFormat::JsonLd { profile: JsonLdProfileSet::empty() },
+        ] {
+            let mut buffer = Vec::new();
+            hdt.write_rdf(&mut buffer, format, &ExportOptions { graph: Some(graph.to_owned()), prefixes: 5 })?;
+            if format == RdfFormat::Turtle {
+                let turtle = String::from_utf8(buffer.clone())?;
+                assert!(turtle.contains("@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#>"), "{turtle}");
+                assert!(turtle.contains("rdfs:label"));
+            }
+            let mut actual = Vec::new();
+            for quad in RdfParser::from_format(format).for_reader(buffer.as_slice()) {
+                let quad = quad?;
+                assert_eq!(
+                    quad.graph_name.to_string() == format!("<{graph}>"),
+                    format.supports_datasets(),
+                    "{format}"
+                );
+                actual.push([
+                    term_string(quad.subject.into()),
+                    quad.predicate.into_string(),
+                    term_string(quad.object),
+                ]);
+            }
+            actual.sort_unstable();
+            assert_eq!(actual, expected, "{format}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn terms() -> color_eyre::Result<()> {
+        assert_eq!(hdt_str_to_term("\"a\nb\"@en")?, Literal::new_language_tagged_literal("a\nb", "en")?.into());
+        assert_eq!(hdt_str_to_term("\"a \\\" b\"")?, Literal::new_simple_literal("a \" b").into());
+        assert!(hdt_str_to_term("\"unterminated").is_err());
+        assert!(RdfWriter::new(Vec::new(), RdfFormat::NQuads, Some("not an iri"), &[]).is_err());
+        assert!(RdfWriter::new(Vec::new(), RdfFormat::NTriples, Some("not an iri"), &[]).is_ok());
+        Ok(())
+    }
+}
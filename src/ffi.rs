@@ -0,0 +1,317 @@
+//! C API for using HDT from other languages, such as C, C++ or Go via cgo.
+//! The declarations are in the header `include/hdt.h`, which is generated by the build script and checked by `tests/ffi.rs`.
+//!
+//! An HDT is opened with [`hdt_open`] or [`hdt_open_buffer`] and closed with [`hdt_close`].
+//! Queries return a cursor, which keeps the HDT alive until it is freed with [`hdt_cursor_free`].
+//! Strings returned by the library are freed with [`hdt_string_free`], except for those of [`HdtTriple`],
+//! which stay valid until the next call on the same cursor.
+//!
+//! Functions that fail return `NULL`, `0` or `false` and store a message that [`hdt_last_error`] returns.
+//! Each call except for the free functions resets the message of the calling thread.
+use crate::owned::OwnedTripleIds;
+use crate::{Hdt, IdKind};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::fmt::{Display, Write};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+use std::sync::Arc;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Position of a term in a triple.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdtPosition {
+    Subject,
+    Predicate,
+    Object,
+}
+
+impl From<HdtPosition> for IdKind {
+    fn from(position: HdtPosition) -> Self {
+        match position {
+            HdtPosition::Subject => IdKind::Subject,
+            HdtPosition::Predicate => IdKind::Predicate,
+            HdtPosition::Object => IdKind::Object,
+        }
+    }
+}
+
+/// A triple in the string format of the HDT dictionary.
+/// IRIs have no angle brackets, blank nodes start with "_:" and literals with a quotation mark.
+#[repr(C)]
+#[derive(Debug)]
+pub struct HdtTriple {
+    pub subject: *const c_char,
+    pub predicate: *const c_char,
+    pub object: *const c_char,
+}
+
+/// Iterator over the triples that fit a pattern.
+#[derive(Debug)]
+pub struct HdtCursor {
+    ids: OwnedTripleIds,
+    /// strings of the last triple returned by [`hdt_cursor_next`]
+    strings: [CString; 3],
+}
+
+fn set_error(message: impl Display) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Runs f after resetting the last error, stores its error or panic message and returns the fallback in that case.
+fn guard<T>(fallback: T, f: impl FnOnce() -> Result<T, String>) -> T {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(t)) => t,
+        Ok(Err(message)) => {
+            set_error(message);
+            fallback
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| panic.downcast_ref::<String>().cloned());
+            set_error(format!("panic: {}", message.unwrap_or_default()));
+            fallback
+        }
+    }
+}
+
+/// Converts a nullable C string, `NULL` stands for a variable.
+unsafe fn optional_str<'a>(s: *const c_char) -> Result<Option<&'a str>, String> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: the caller guarantees a valid zero terminated string
+    unsafe { CStr::from_ptr(s) }.to_str().map(Some).map_err(|e| format!("string is not valid UTF-8: {e}"))
+}
+
+unsafe fn required_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    unsafe { optional_str(s) }?.ok_or_else(|| format!("{name} must not be NULL"))
+}
+
+unsafe fn hdt_ref<'a>(hdt: *const Hdt) -> Result<&'a Hdt, String> {
+    // SAFETY: the caller guarantees a pointer returned by hdt_open or hdt_open_buffer that has not been closed
+    unsafe { hdt.as_ref() }.ok_or_else(|| "hdt must not be NULL".to_owned())
+}
+
+/// Increments the reference count of the HDT and returns a shared reference to it.
+unsafe fn hdt_arc(hdt: *const Hdt) -> Result<Arc<Hdt>, String> {
+    unsafe { hdt_ref(hdt) }?;
+    // SAFETY: hdt was created by Arc::into_raw
+    unsafe {
+        Arc::increment_strong_count(hdt);
+        Ok(Arc::from_raw(hdt))
+    }
+}
+
+fn into_c_string(s: String) -> Result<*mut c_char, String> {
+    CString::new(s).map(CString::into_raw).map_err(|e| e.to_string())
+}
+
+/// Opens the HDT file at the given path.
+/// Returns `NULL` on error.
+/// # Safety
+/// `path` must be a valid zero terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_open(path: *const c_char) -> *const Hdt {
+    guard(ptr::null(), || {
+        let path = unsafe { required_str(path, "path") }?;
+        let file = File::open(path).map_err(|e| format!("{path}: {e}"))?;
+        let hdt = Hdt::read(BufReader::new(file)).map_err(|e| format!("{e:#}"))?;
+        Ok(Arc::into_raw(Arc::new(hdt)))
+    })
+}
+
+/// Reads an HDT from the given bytes, which are not needed anymore afterwards.
+/// Returns `NULL` on error.
+/// # Safety
+/// `data` must point to at least `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_open_buffer(data: *const u8, len: usize) -> *const Hdt {
+    guard(ptr::null(), || {
+        if data.is_null() {
+            return Err("data must not be NULL".to_owned());
+        }
+        // SAFETY: guaranteed by the caller
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        let hdt = Hdt::read(Cursor::new(data)).map_err(|e| format!("{e:#}"))?;
+        Ok(Arc::into_raw(Arc::new(hdt)))
+    })
+}
+
+/// Closes an HDT, whose memory is released once all of its cursors are freed as well.
+/// Does nothing if `hdt` is `NULL`.
+/// # Safety
+/// `hdt` must have been returned by [`hdt_open`] or [`hdt_open_buffer`] and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_close(hdt: *const Hdt) {
+    guard((), || {
+        if !hdt.is_null() {
+            // SAFETY: hdt was created by Arc::into_raw
+            drop(unsafe { Arc::from_raw(hdt) });
+        }
+        Ok(())
+    });
+}
+
+/// Number of triples.
+/// # Safety
+/// `hdt` must be an open HDT.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_triple_count(hdt: *const Hdt) -> usize {
+    guard(0, || Ok(unsafe { hdt_ref(hdt) }?.triples.len()))
+}
+
+/// The triples of the header as N-Triples, to be freed with [`hdt_string_free`].
+/// Returns `NULL` on error.
+/// # Safety
+/// `hdt` must be an open HDT.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_header(hdt: *const Hdt) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let hdt = unsafe { hdt_ref(hdt) }?;
+        let mut nt = String::new();
+        for triple in &hdt.header.body {
+            writeln!(nt, "{triple}").map_err(|e| e.to_string())?;
+        }
+        into_c_string(nt)
+    })
+}
+
+/// The ID of the given term at the given position, or 0 if it is not in the dictionary.
+/// The term uses the string format of [`HdtTriple`].
+/// # Safety
+/// `hdt` must be an open HDT and `term` a valid zero terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_string_to_id(hdt: *const Hdt, term: *const c_char, position: HdtPosition) -> u32 {
+    guard(0, || {
+        let hdt = unsafe { hdt_ref(hdt) }?;
+        let term = unsafe { required_str(term, "term") }?;
+        Ok(hdt.dict.string_to_id(term, position.into()))
+    })
+}
+
+/// The term with the given ID at the given position, to be freed with [`hdt_string_free`].
+/// Returns `NULL` if the ID does not exist.
+/// # Safety
+/// `hdt` must be an open HDT.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_id_to_string(hdt: *const Hdt, id: u32, position: HdtPosition) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let hdt = unsafe { hdt_ref(hdt) }?;
+        into_c_string(hdt.dict.id_to_string(id, position.into()).map_err(|e| format!("{e:#}"))?)
+    })
+}
+
+/// Frees a string returned by this library. Does nothing if `s` is `NULL`.
+/// # Safety
+/// `s` must have been returned by [`hdt_header`] or [`hdt_id_to_string`] and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: s was created by CString::into_raw
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Queries the triples that fit the pattern, where `NULL` stands for a variable.
+/// The terms use the string format of [`HdtTriple`], constants that are not in the dictionary result in no triples.
+/// Returns a cursor to be freed with [`hdt_cursor_free`] or `NULL` on error.
+/// # Safety
+/// `hdt` must be an open HDT and `s`, `p` and `o` must be `NULL` or valid zero terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_query(
+    hdt: *const Hdt, s: *const c_char, p: *const c_char, o: *const c_char,
+) -> *mut HdtCursor {
+    guard(ptr::null_mut(), || {
+        let pattern = unsafe { [optional_str(s)?, optional_str(p)?, optional_str(o)?] };
+        let hdt = unsafe { hdt_arc(hdt) }?;
+        let ids = match hdt.id_pattern(pattern) {
+            Some(pattern) => OwnedTripleIds::new(hdt, pattern),
+            None => OwnedTripleIds::empty(hdt),
+        };
+        Ok(Box::into_raw(Box::new(HdtCursor { ids, strings: Default::default() })))
+    })
+}
+
+/// Queries the triples that fit the ID pattern, where 0 stands for a variable.
+/// Returns a cursor to be freed with [`hdt_cursor_free`] or `NULL` on error.
+/// # Safety
+/// `hdt` must be an open HDT.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_query_ids(hdt: *const Hdt, s: u32, p: u32, o: u32) -> *mut HdtCursor {
+    guard(ptr::null_mut(), || {
+        let hdt = unsafe { hdt_arc(hdt) }?;
+        let ids = OwnedTripleIds::new(hdt, [s, p, o]);
+        Ok(Box::into_raw(Box::new(HdtCursor { ids, strings: Default::default() })))
+    })
+}
+
+/// Writes the IDs of the next triple into `ids`, which must have room for three values.
+/// Returns false at the end or on error, in which case [`hdt_last_error`] is not `NULL`.
+/// # Safety
+/// `cursor` must be a cursor that has not been freed and `ids` must point to three writable values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_cursor_next_ids(cursor: *mut HdtCursor, ids: *mut u32) -> bool {
+    guard(false, || {
+        // SAFETY: guaranteed by the caller
+        let cursor = unsafe { cursor.as_mut() }.ok_or("cursor must not be NULL")?;
+        if ids.is_null() {
+            return Err("ids must not be NULL".to_owned());
+        }
+        let Some(triple) = cursor.ids.next() else { return Ok(false) };
+        // SAFETY: guaranteed by the caller
+        unsafe { ptr::copy_nonoverlapping(triple.as_ptr(), ids, 3) };
+        Ok(true)
+    })
+}
+
+/// Writes the next triple into `triple`, whose strings stay valid until the next call with this cursor.
+/// Returns false at the end or on error, in which case [`hdt_last_error`] is not `NULL`.
+/// # Safety
+/// `cursor` must be a cursor that has not been freed and `triple` must point to a writable [`HdtTriple`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_cursor_next(cursor: *mut HdtCursor, triple: *mut HdtTriple) -> bool {
+    guard(false, || {
+        // SAFETY: guaranteed by the caller
+        let cursor = unsafe { cursor.as_mut() }.ok_or("cursor must not be NULL")?;
+        // SAFETY: guaranteed by the caller
+        let triple = unsafe { triple.as_mut() }.ok_or("triple must not be NULL")?;
+        let Some(ids) = cursor.ids.next() else { return Ok(false) };
+        let dict = &cursor.ids.hdt().dict;
+        for ((string, id), kind) in cursor.strings.iter_mut().zip(ids).zip(IdKind::KINDS) {
+            let s = dict.id_to_string(id, kind).map_err(|e| format!("{e:#}"))?;
+            *string = CString::new(s).map_err(|e| e.to_string())?;
+        }
+        let [s, p, o] = &cursor.strings;
+        *triple = HdtTriple { subject: s.as_ptr(), predicate: p.as_ptr(), object: o.as_ptr() };
+        Ok(true)
+    })
+}
+
+/// Frees a cursor. Does nothing if `cursor` is `NULL`.
+/// # Safety
+/// `cursor` must have been returned by [`hdt_query`] or [`hdt_query_ids`] and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hdt_cursor_free(cursor: *mut HdtCursor) {
+    if !cursor.is_null() {
+        // SAFETY: cursor was created by Box::into_raw
+        drop(unsafe { Box::from_raw(cursor) });
+    }
+}
+
+/// The message of the last error on the calling thread or `NULL` if the last call succeeded.
+/// The string is valid until the next call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn hdt_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
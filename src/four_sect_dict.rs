@@ -1,10 +1,15 @@
 #![allow(missing_docs)]
 // temporary while we figure out what should be public in the end
 /// Four section dictionary.
+use crate::block_cache::{BlockCache, CacheStats};
+use crate::containers::Verify;
 use crate::dict_sect_pfc;
 use crate::triples::Id;
 use crate::{ControlInfo, DictSectPFC};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::io::BufRead;
+use std::sync::{Arc, OnceLock};
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
 use std::thread::JoinHandle;
 use thiserror::Error;
@@ -30,7 +35,7 @@ impl IdKind {
 /// Dictionary with shared, subject, predicate and object sections.
 /// Types specified as <http://purl.org/HDT/hdt#dictionaryFour>.
 /// See <https://www.rdfhdt.org/hdt-internals/#dictionary>.
-#[cfg_attr(test, derive(PartialEq))]
+/// Construct with [`FourSectDict::new`].
 #[derive(Debug)]
 pub struct FourSectDict {
     /// The shared section contains URIs that occur both in subject and object position. Its IDs start at one.
@@ -41,10 +46,24 @@ pub struct FourSectDict {
     pub predicates: DictSectPFC,
     /// URIs and literals that only occur as objects . Their IDs start at the last ID of the shared section + 1.
     pub objects: DictSectPFC,
+    /// Optional cache of decoded blocks, see [`FourSectDict::enable_cache`].
+    cache: Option<BlockCache>,
+    /// Object IDs of the literals, found on first use, see [`FourSectDict::literal_ids`].
+    literals: OnceLock<std::ops::Range<Id>>,
+}
+
+/// Compares the sections, the cache does not change the content.
+impl PartialEq for FourSectDict {
+    fn eq(&self, other: &Self) -> bool {
+        self.shared == other.shared
+            && self.subjects == other.subjects
+            && self.predicates == other.predicates
+            && self.objects == other.objects
+    }
 }
 
 /// Designates one of the four sections.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SectKind {
     /// section for terms that appear as both subject and object
     Shared,
@@ -81,6 +100,8 @@ pub enum Error {
     ControlInfo(#[from] crate::containers::control_info::Error),
     #[error("failed to read FourSectDict section")]
     DictSect(#[from] DictSectError),
+    #[error("{sect_kind:?} IDs up to {max_id} exceed the maximum ID {max}", max = Id::MAX)]
+    TooManyIds { sect_kind: SectKind, max_id: usize },
     #[error("failed to read FourSectDict: {0}")]
     Other(String),
 }
@@ -93,44 +114,215 @@ pub struct EncodedTripleId {
 }
 
 impl FourSectDict {
-    /// Get the string value of a given ID of a given type.
-    /// String representation of URIs, literals and blank nodes is defined in <https://www.w3.org/Submission/2011/SUBM-HDT-20110330/#dictionaryEncoding>>..
-    pub fn id_to_string(&self, id: Id, id_kind: IdKind) -> core::result::Result<String, ExtractError> {
+    /// Creates a dictionary from its sections without a cache.
+    pub const fn new(
+        shared: DictSectPFC, subjects: DictSectPFC, predicates: DictSectPFC, objects: DictSectPFC,
+    ) -> Self {
+        FourSectDict { shared, subjects, predicates, objects, cache: None, literals: OnceLock::new() }
+    }
+
+    /// Checks that the largest IDs of sections with the given numbers of strings fit into an [`Id`].
+    /// Subject and object IDs continue after the shared section, so they can overflow even if each section fits.
+    pub fn check_id_range(shared: usize, subjects: usize, predicates: usize, objects: usize) -> Result<()> {
         use SectKind::*;
+        for (sect_kind, max_id) in
+            [(Subject, shared + subjects), (Predicate, predicates), (Object, shared + objects)]
+        {
+            if Id::try_from(max_id).is_err() {
+                return Err(Error::TooManyIds { sect_kind, max_id });
+            }
+        }
+        Ok(())
+    }
+
+    /// Caches decoded blocks of up to the given total size in bytes for [`FourSectDict::id_to_string`],
+    /// replacing any previous cache.
+    pub fn enable_cache(&mut self, capacity: usize) {
+        self.cache = Some(BlockCache::new(capacity));
+    }
+
+    /// Removes the cache of decoded blocks.
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// The cache of decoded blocks, if enabled.
+    pub const fn cache(&self) -> Option<&BlockCache> {
+        self.cache.as_ref()
+    }
+
+    /// Hit and miss statistics of the cache of decoded blocks, if enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(BlockCache::stats)
+    }
+
+    /// Section and ID within that section of a given ID of a given type.
+    const fn locate(&self, id: Id, id_kind: IdKind) -> (SectKind, &DictSectPFC, Id) {
         let shared_size = self.shared.num_strings() as Id;
         let d = id.saturating_sub(shared_size);
         match id_kind {
-            IdKind::Subject => {
-                if id <= shared_size {
-                    self.shared.extract(id).map_err(|e| ExtractError { e, id, id_kind, sect_kind: Shared })
-                } else {
-                    self.subjects.extract(d).map_err(|e| ExtractError { e, id, id_kind, sect_kind: Subject })
-                }
+            IdKind::Predicate => (SectKind::Predicate, &self.predicates, id),
+            _ if id <= shared_size => (SectKind::Shared, &self.shared, id),
+            IdKind::Subject => (SectKind::Subject, &self.subjects, d),
+            IdKind::Object => (SectKind::Object, &self.objects, d),
+        }
+    }
+
+    /// Decoded block with the given index, from the cache if enabled.
+    fn block(
+        &self, sect_kind: SectKind, sect: &DictSectPFC, block_index: usize,
+    ) -> core::result::Result<Arc<[String]>, dict_sect_pfc::ExtractError> {
+        match &self.cache {
+            Some(cache) => {
+                cache.get_or_decode((sect_kind as usize, block_index), || sect.extract_block(block_index))
             }
-            IdKind::Predicate => {
-                self.predicates.extract(id).map_err(|e| ExtractError { e, id, id_kind, sect_kind: Predicate })
+            None => sect.extract_block(block_index).map(Arc::from),
+        }
+    }
+
+    /// Decoded block containing the given ID within the section and the index of the ID in that block.
+    fn cached(
+        &self, sect_kind: SectKind, sect: &DictSectPFC, d: Id,
+    ) -> core::result::Result<(Arc<[String]>, usize), dict_sect_pfc::ExtractError> {
+        if d as usize > sect.num_strings {
+            return Err(dict_sect_pfc::ExtractError::IdOutOfBounds { id: d, len: sect.num_strings });
+        }
+        let i = d.saturating_sub(1) as usize;
+        Ok((self.block(sect_kind, sect, i / sect.block_size)?, i % sect.block_size))
+    }
+
+    /// Get the string value of a given ID of a given type.
+    /// String representation of URIs, literals and blank nodes is defined in <https://www.w3.org/Submission/2011/SUBM-HDT-20110330/#dictionaryEncoding>>..
+    pub fn id_to_string(&self, id: Id, id_kind: IdKind) -> core::result::Result<String, ExtractError> {
+        let (sect_kind, sect, d) = self.locate(id, id_kind);
+        let result = match self.cache {
+            None => sect.extract(d),
+            Some(_) => self.cached(sect_kind, sect, d).map(|(block, i)| block[i].clone()),
+        };
+        result.map_err(|e| ExtractError { e, id, id_kind, sect_kind })
+    }
+
+    /// Like [`FourSectDict::id_to_string`] but writes into the given buffer, whose previous content is replaced.
+    /// Reusing the buffer avoids allocating a new string for each ID.
+    /// # Example
+    /// ```
+    /// use hdt::IdKind;
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let mut buf = String::new();
+    /// for [s, _, _] in hdt.triples.into_iter().take(10) {
+    ///     hdt.dict.id_to_str_into(s, IdKind::Subject, &mut buf).unwrap();
+    ///     assert!(!buf.is_empty());
+    /// }
+    /// ```
+    pub fn id_to_str_into(
+        &self, id: Id, id_kind: IdKind, buf: &mut String,
+    ) -> core::result::Result<(), ExtractError> {
+        let (sect_kind, sect, d) = self.locate(id, id_kind);
+        let result = match self.cache {
+            None => sect.extract_into(d, buf),
+            Some(_) => self.cached(sect_kind, sect, d).map(|(block, i)| {
+                buf.clear();
+                buf.push_str(&block[i]);
+            }),
+        };
+        result.map_err(|e| ExtractError { e, id, id_kind, sect_kind })
+    }
+
+    /// Like [`FourSectDict::id_to_string`] but borrows the string if it is the first one of its block,
+    /// which is stored verbatim in plain front coding.
+    pub fn id_to_str_cow(&self, id: Id, id_kind: IdKind) -> core::result::Result<Cow<'_, str>, ExtractError> {
+        let (sect_kind, sect, d) = self.locate(id, id_kind);
+        let result = match self.cache {
+            Some(_) if !(d.saturating_sub(1) as usize).is_multiple_of(sect.block_size) => {
+                self.cached(sect_kind, sect, d).map(|(block, i)| Cow::Owned(block[i].clone()))
             }
-            IdKind::Object => {
-                if id <= shared_size {
-                    self.shared.extract(id).map_err(|e| ExtractError { e, id, id_kind, sect_kind: Shared })
-                } else {
-                    self.objects.extract(d).map_err(|e| ExtractError { e, id, id_kind, sect_kind: Object })
-                }
+            _ => sect.extract_cow(d),
+        };
+        result.map_err(|e| ExtractError { e, id, id_kind, sect_kind })
+    }
+
+    /// Calls the visitor with the string value of a given ID of a given type without allocating,
+    /// except for the first call on each thread, which allocates a reusable buffer.
+    /// # Example
+    /// ```
+    /// use hdt::IdKind;
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let literals = hdt.triples.into_iter()
+    ///     .filter(|[_, _, o]| hdt.dict.with_str(*o, IdKind::Object, |s| s.starts_with('"')).unwrap())
+    ///     .count();
+    /// assert!(literals > 0);
+    /// ```
+    pub fn with_str<R>(
+        &self, id: Id, id_kind: IdKind, visitor: impl FnOnce(&str) -> R,
+    ) -> core::result::Result<R, ExtractError> {
+        thread_local! {
+            static BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+        }
+        let (sect_kind, sect, d) = self.locate(id, id_kind);
+        let result = if self.cache.is_some() {
+            self.cached(sect_kind, sect, d).map(|(block, i)| visitor(&block[i]))
+        } else if (d.saturating_sub(1) as usize).is_multiple_of(sect.block_size) {
+            sect.extract_cow(d).map(|s| visitor(&s))
+        } else {
+            BUFFER.with(|buf| match buf.try_borrow_mut() {
+                Ok(mut buf) => sect.extract_into(d, &mut buf).map(|()| visitor(&buf)),
+                // called from within a visitor
+                Err(_) => sect.extract(d).map(|s| visitor(&s)),
+            })
+        };
+        result.map_err(|e| ExtractError { e, id, id_kind, sect_kind })
+    }
+
+    /// Get the string values of the given IDs of a given type, which are sorted in place so that each block is decoded only once.
+    /// The n-th string belongs to the n-th ID after sorting.
+    /// # Example
+    /// ```
+    /// use hdt::IdKind;
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let mut ids = [3, 1, 2];
+    /// let strings = hdt.dict.ids_to_strings(&mut ids, IdKind::Predicate).unwrap();
+    /// assert_eq!(ids, [1, 2, 3]);
+    /// assert_eq!(strings[0], hdt.dict.id_to_string(1, IdKind::Predicate).unwrap());
+    /// ```
+    pub fn ids_to_strings(
+        &self, ids: &mut [Id], id_kind: IdKind,
+    ) -> core::result::Result<Vec<String>, ExtractError> {
+        ids.sort_unstable();
+        let mut strings = Vec::with_capacity(ids.len());
+        // the last decoded block and its section and block index
+        let mut current: Option<(SectKind, usize)> = None;
+        let mut block: Arc<[String]> = Arc::new([]);
+        for &id in ids.iter() {
+            let (sect_kind, sect, d) = self.locate(id, id_kind);
+            let to_error = |e| ExtractError { e, id, id_kind, sect_kind };
+            if d as usize > sect.num_strings {
+                return Err(to_error(dict_sect_pfc::ExtractError::IdOutOfBounds { id: d, len: sect.num_strings }));
+            }
+            let i = d.saturating_sub(1) as usize;
+            let key = (sect_kind, i / sect.block_size);
+            if current != Some(key) {
+                block = self.block(sect_kind, sect, key.1).map_err(to_error)?;
+                current = Some(key);
             }
+            strings.push(block[i % sect.block_size].clone());
         }
+        Ok(strings)
     }
 
     /// Get the ID for a given string or 0 if not found.
     /// String representation of URIs, literals and blank nodes is defined in <https://www.w3.org/Submission/2011/SUBM-HDT-20110330/#dictionaryEncoding>>..
     pub fn string_to_id(&self, s: &str, id_kind: IdKind) -> Id {
-        let shared_size = self.shared.num_strings();
+        let shared_size = self.shared.num_strings() as Id;
         match id_kind {
             IdKind::Subject => {
                 let mut id = self.shared.string_to_id(s);
                 if id == 0 {
                     id = self.subjects.string_to_id(s);
                     if id > 0 {
-                        id += shared_size as Id;
+                        id += shared_size;
                     }
                 }
                 id
@@ -141,7 +333,7 @@ impl FourSectDict {
                 if id == 0 {
                     id = self.objects.string_to_id(s);
                     if id > 0 {
-                        id += shared_size as Id;
+                        id += shared_size;
                     }
                 }
                 id
@@ -149,15 +341,91 @@ impl FourSectDict {
         }
     }
 
+    /// IDs and strings of the given kind that start with the given prefix in ascending order of the strings,
+    /// for example for autocompletion.
+    pub fn strings_with_prefix<'a>(
+        &'a self, prefix: &'a str, id_kind: IdKind,
+    ) -> impl Iterator<Item = (Id, String)> + 'a {
+        let sect_strings = move |sect: &'a DictSectPFC, offset: Id| {
+            (sect.lower_bound(prefix)..=sect.num_strings() as Id)
+                .map_while(move |id| sect.extract(id).ok().map(|s| (id + offset, s)))
+                .take_while(move |(_, s)| s.starts_with(prefix))
+        };
+        let (sect, offset) = match id_kind {
+            IdKind::Subject => (&self.subjects, self.shared.num_strings() as Id),
+            IdKind::Predicate => (&self.predicates, 0),
+            IdKind::Object => (&self.objects, self.shared.num_strings() as Id),
+        };
+        let with_shared = !matches!(id_kind, IdKind::Predicate);
+        let mut shared = with_shared.then(|| sect_strings(&self.shared, 0)).into_iter().flatten().peekable();
+        let mut own = sect_strings(sect, offset).peekable();
+        // both sections are sorted, merge them
+        std::iter::from_fn(move || match (shared.peek(), own.peek()) {
+            (Some(a), Some(b)) if a.1 > b.1 => own.next(),
+            (Some(_), _) => shared.next(),
+            (None, _) => own.next(),
+        })
+    }
+
+    /// Object IDs of all literals.
+    /// Literals start with a quotation mark, which sorts before all IRIs and blank nodes,
+    /// so they form a contiguous range at the beginning of the object section that is found using binary search.
+    /// The range is only searched on the first call.
+    pub fn literal_ids(&self) -> std::ops::Range<Id> {
+        self.literals
+            .get_or_init(|| {
+                let (mut low, mut high) = (0, self.objects.num_strings());
+                while low < high {
+                    let mid = usize::midpoint(low, high);
+                    if self.objects.extract(mid as Id + 1).is_ok_and(|s| s.starts_with('"')) {
+                        low = mid + 1;
+                    } else {
+                        high = mid;
+                    }
+                }
+                let shared_size = self.shared.num_strings() as Id;
+                shared_size + 1..shared_size + 1 + low as Id
+            })
+            .clone()
+    }
+
     /// read the whole dictionary section including control information
     pub fn read<R: BufRead>(reader: &mut R) -> Result<UnvalidatedFourSectDict> {
+        Self::read_with(reader, Verify::Full)
+    }
+
+    /// read the whole dictionary section including control information, verifying the given checksums
+    pub fn read_with<R: BufRead>(reader: &mut R, verify: Verify) -> Result<UnvalidatedFourSectDict> {
         use SectKind::*;
+        Self::read_control_info(reader)?;
+        let mut f = |sect_kind| Self::read_sect(reader, verify, sect_kind);
+        Ok(UnvalidatedFourSectDict([f(Shared)?, f(Subject)?, f(Predicate)?, f(Object)?]))
+    }
+
+    /// Reads the control information at the start of the dictionary section and checks its format.
+    pub(crate) fn read_control_info<R: BufRead>(reader: &mut R) -> Result<()> {
         let dict_ci = ControlInfo::read(reader)?;
         if dict_ci.format != "<http://purl.org/HDT/hdt#dictionaryFour>" {
             return Err(Error::Other("Implementation only supports four section dictionaries".to_owned()));
         }
-        let mut f = |sect_kind| DictSectPFC::read(reader).map_err(|e| DictSectError { e, sect_kind });
-        Ok(UnvalidatedFourSectDict([f(Shared)?, f(Subject)?, f(Predicate)?, f(Object)?]))
+        Ok(())
+    }
+
+    /// Reads one of the four sections of the dictionary, whose checksum is validated later.
+    pub(crate) fn read_sect<R: BufRead>(
+        reader: &mut R, verify: Verify, sect_kind: SectKind,
+    ) -> Result<UnvalidatedDictSect> {
+        Ok(DictSectPFC::read_with(reader, verify).map_err(|e| DictSectError { e, sect_kind })?)
+    }
+
+    /// Skips over the whole dictionary section including control information without decoding or validating it.
+    pub fn skip<R: BufRead>(reader: &mut R) -> Result<()> {
+        use SectKind::*;
+        Self::read_control_info(reader)?;
+        for sect_kind in [Shared, Subject, Predicate, Object] {
+            DictSectPFC::skip(reader).map_err(|e| DictSectError { e, sect_kind })?;
+        }
+        Ok(())
     }
 
     /// write the whole Dictionary including control info and all sections
@@ -171,24 +439,33 @@ impl FourSectDict {
         Ok(())
     }
 
-    /// size in bytes of the in memory four section dictionary
+    /// size in bytes of the in memory four section dictionary including the cache of decoded blocks
     pub fn size_in_bytes(&self) -> usize {
         self.shared.size_in_bytes()
             + self.subjects.size_in_bytes()
             + self.predicates.size_in_bytes()
             + self.objects.size_in_bytes()
+            + self.cache_stats().map_or(0, |s| s.bytes)
     }
 }
 
-/// A wrapper to ensure prevent using FourSectDict before its checksums have been validated
+/// A dictionary section whose checksum is validated in a separate thread.
 #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
-pub struct UnvalidatedFourSectDict([JoinHandle<dict_sect_pfc::Result<DictSectPFC>>; 4]);
+pub(crate) type UnvalidatedDictSect = JoinHandle<dict_sect_pfc::Result<DictSectPFC>>;
 
-/// WASM version without JoinHandle
+/// WASM version without JoinHandle, the checksum is already validated during read.
 #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
-pub struct UnvalidatedFourSectDict([DictSectPFC; 4]);
+pub(crate) type UnvalidatedDictSect = DictSectPFC;
+
+/// A wrapper to ensure prevent using FourSectDict before its checksums have been validated
+pub struct UnvalidatedFourSectDict([UnvalidatedDictSect; 4]);
 
 impl UnvalidatedFourSectDict {
+    /// Combines the shared, subject, predicate and object sections in that order.
+    pub(crate) const fn new(sections: [UnvalidatedDictSect; 4]) -> Self {
+        Self(sections)
+    }
+
     /// Validates the checksums of all dictionary sections in parallel.
     /// Dict validation takes around 1200 ms on a single thread with an 1.5 GB HDT file on an i9-12900k.
     #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
@@ -200,14 +477,20 @@ impl UnvalidatedFourSectDict {
             .map(|(sect_kind, handle)| handle.join().unwrap().map_err(|e| DictSectError { e, sect_kind }))
             .collect::<std::result::Result<Vec<DictSectPFC>, DictSectError>>()?;
         let [shared, subjects, predicates, objects]: [DictSectPFC; 4] = r.try_into().unwrap();
-        Ok(FourSectDict { shared, subjects, predicates, objects })
+        FourSectDict::check_id_range(
+            shared.num_strings, subjects.num_strings, predicates.num_strings, objects.num_strings,
+        )?;
+        Ok(FourSectDict::new(shared, subjects, predicates, objects))
     }
 
     /// WASM version - sections are already validated during read
     #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
     pub fn validate(self) -> Result<FourSectDict> {
         let [shared, subjects, predicates, objects] = self.0;
-        Ok(FourSectDict { shared, subjects, predicates, objects })
+        FourSectDict::check_id_range(
+            shared.num_strings, subjects.num_strings, predicates.num_strings, objects.num_strings,
+        )?;
+        Ok(FourSectDict::new(shared, subjects, predicates, objects))
     }
 }
 
@@ -220,6 +503,37 @@ mod tests {
     use pretty_assertions::assert_eq;
     use std::io::BufReader;
 
+    #[test]
+    fn strings_with_prefix() -> color_eyre::Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let dict = &hdt.dict;
+        for kind in IdKind::KINDS {
+            let max = match kind {
+                IdKind::Subject => dict.shared.num_strings() + dict.subjects.num_strings(),
+                IdKind::Predicate => dict.predicates.num_strings(),
+                IdKind::Object => dict.shared.num_strings() + dict.objects.num_strings(),
+            };
+            for prefix in
+                ["http://www.snik.eu/ontology/meta/", "http://www.w3.org/2000/01/rdf-schema#", "\"", "_:", ""]
+            {
+                let mut expected: Vec<_> = (1..=max as Id)
+                    .map(|id| (id, dict.id_to_string(id, kind).unwrap()))
+                    .filter(|(_, s)| s.starts_with(prefix))
+                    .collect();
+                expected.sort_by(|a, b| a.1.cmp(&b.1));
+                assert_eq!(
+                    dict.strings_with_prefix(prefix, kind).collect::<Vec<_>>(),
+                    expected,
+                    "{kind:?} {prefix}"
+                );
+            }
+        }
+        assert_eq!(dict.strings_with_prefix("doesnotexist", IdKind::Object).count(), 0);
+        assert_eq!(dict.objects.lower_bound("~"), dict.objects.num_strings() as Id + 1);
+        Ok(())
+    }
+
     #[test]
     fn read_write_dict() -> color_eyre::Result<()> {
         init();
@@ -237,7 +551,7 @@ mod tests {
         assert_eq!("http://www.snik.eu/ontology/meta/uses", dict.id_to_string(43, IdKind::Subject)?);
         assert_eq!("http://www.snik.eu/ontology/meta/Chapter", dict.id_to_string(3, IdKind::Subject)?);
         assert_eq!("http://www.snik.eu/ontology/meta/DataSetType", dict.id_to_string(5, IdKind::Subject)?);
-        for id in 1..dict.shared.num_strings() {
+        for id in 1..dict.shared.num_strings() as Id {
             let s = dict.id_to_string(id, IdKind::Subject)?;
             let back = dict.string_to_id(&s, IdKind::Subject);
             assert_eq!(id, back, "shared id {} -> subject {} -> id {}", id, s, back);
@@ -247,11 +561,11 @@ mod tests {
             assert_eq!(id, back, "shared id {} -> object {} -> id {}", id, s, back);
         }
         for (sect, kind, name, offset) in [
-            (&dict.subjects, IdKind::Subject, "subject", dict.shared.num_strings()),
-            (&dict.objects, IdKind::Object, "object", dict.shared.num_strings()),
+            (&dict.subjects, IdKind::Subject, "subject", dict.shared.num_strings() as Id),
+            (&dict.objects, IdKind::Object, "object", dict.shared.num_strings() as Id),
             (&dict.predicates, IdKind::Predicate, "predicate", 0),
         ] {
-            for id in offset + 1..offset + sect.num_strings() {
+            for id in offset + 1..offset + sect.num_strings() as Id {
                 let s = dict.id_to_string(id, kind)?;
                 let back = dict.string_to_id(&s, kind);
                 assert_eq!(id, back, "{} id {} -> {} {} -> id {}", name, id, name, s, back);
@@ -263,4 +577,75 @@ mod tests {
         assert_eq!(dict, dict2);
         Ok(())
     }
+
+    #[test]
+    fn zero_copy() -> color_eyre::Result<()> {
+        init();
+        let mut dict = crate::hdt::tests::snikmeta()?.dict;
+        let mut buf = String::new();
+        for cache in [false, true] {
+            if cache {
+                dict.enable_cache(1 << 20);
+            }
+            for (kind, n) in [
+                (IdKind::Subject, dict.shared.num_strings() + dict.subjects.num_strings()),
+                (IdKind::Predicate, dict.predicates.num_strings()),
+                (IdKind::Object, dict.shared.num_strings() + dict.objects.num_strings()),
+            ] {
+                let n = n as Id;
+                for id in 1..=n {
+                    let expected = dict.id_to_string(id, kind)?;
+                    dict.id_to_str_into(id, kind, &mut buf)?;
+                    assert_eq!(buf, expected);
+                    let cow = dict.id_to_str_cow(id, kind)?;
+                    assert_eq!(cow, expected);
+                    let (_, sect, d) = dict.locate(id, kind);
+                    let head = ((d - 1) as usize).is_multiple_of(sect.block_size);
+                    assert_eq!(matches!(cow, Cow::Borrowed(_)), head, "{kind:?} {id}");
+                    // nested visitors cannot share the thread local buffer
+                    let nested = dict.with_str(id, kind, |s| {
+                        dict.with_str(n + 1 - id, kind, |t| format!("{s} {t}")).unwrap()
+                    })?;
+                    assert_eq!(nested, format!("{expected} {}", dict.id_to_string(n + 1 - id, kind)?));
+                }
+                assert!(dict.id_to_str_into(n + 1, kind, &mut buf).is_err());
+                assert!(dict.with_str(n + 1, kind, |_| ()).is_err());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ids_to_strings() -> color_eyre::Result<()> {
+        init();
+        let dict = crate::hdt::tests::snikmeta()?.dict;
+        for (kind, n) in [
+            (IdKind::Subject, dict.shared.num_strings() + dict.subjects.num_strings()),
+            (IdKind::Predicate, dict.predicates.num_strings()),
+            (IdKind::Object, dict.shared.num_strings() + dict.objects.num_strings()),
+        ] {
+            let n = n as Id;
+            // reversed with duplicates
+            let mut ids: Vec<Id> = (1..=n).rev().chain(1..=n).collect();
+            let strings = dict.ids_to_strings(&mut ids, kind)?;
+            assert!(ids.is_sorted());
+            for (id, s) in ids.iter().zip(&strings) {
+                assert_eq!(&dict.id_to_string(*id, kind)?, s);
+            }
+            assert!(dict.ids_to_strings(&mut [n + 1], kind).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn id_range() {
+        init();
+        let max = Id::MAX as usize;
+        assert!(FourSectDict::check_id_range(1, max - 1, max, 0).is_ok());
+        // each section fits but the object IDs continue after the shared section
+        let result = FourSectDict::check_id_range(1, 0, 0, max);
+        assert!(
+            matches!(result, Err(Error::TooManyIds { sect_kind: SectKind::Object, max_id }) if max_id == max + 1)
+        );
+    }
 }
@@ -1,6 +1,11 @@
+#[cfg(feature = "cache")]
+use crate::cache;
+pub use crate::containers::Verify;
 use crate::containers::{ControlInfo, control_info};
 use crate::four_sect_dict::{self, IdKind};
 use crate::header::Header;
+use crate::owned::{OwnedSubjects, OwnedTripleIds, OwnedTriples};
+use crate::progress::{self, Cancelled, Phase, Progress, ProgressRead, ProgressWrite};
 use crate::triples::{Id, ObjectIter, PredicateIter, PredicateObjectIter, SubjectIter, TripleId, TriplesBitmap};
 use crate::{FourSectDict, header};
 use bytesize::ByteSize;
@@ -18,7 +23,7 @@ use std::sync::Arc;
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[cfg(feature = "cache")]
-pub const CACHE_EXT: &str = "index.v1-rust-cache";
+pub use crate::cache::CACHE_EXT;
 #[cfg(feature = "nt")]
 #[path = "concurrent_interner.rs"]
 mod concurrent_interner;
@@ -42,6 +47,37 @@ pub struct Hdt {
 
 type StringTriple = [Arc<str>; 3];
 
+/// Options for [`Hdt::read_with_options`].
+#[derive(Default, Clone, Copy)]
+pub struct ReadOptions<'a> {
+    /// Which checksums are verified, by default all of them.
+    pub verify: Verify,
+    /// Observer that is notified of the loading progress and can cancel it.
+    pub progress: Option<&'a dyn Progress>,
+    /// Directory of the cache files used by [`Hdt::read_from_path_with_options`], by default that of the HDT file.
+    #[cfg(feature = "cache")]
+    pub cache_dir: Option<&'a Path>,
+}
+
+impl std::fmt::Debug for ReadOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("ReadOptions");
+        d.field("verify", &self.verify).field("progress", &self.progress.map(|_| "dyn Progress"));
+        #[cfg(feature = "cache")]
+        d.field("cache_dir", &self.cache_dir);
+        d.finish()
+    }
+}
+
+/// Direction of a traversal along the edges of the graph, see [`Hdt::reachable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From subject to object.
+    Forward,
+    /// From object to subject, like an inverse property path `^p`.
+    Backward,
+}
+
 /// The error type for the `translate_id` method.
 #[derive(thiserror::Error, Debug)]
 #[error("cannot translate triple ID {t:?} to string triple: {e}")]
@@ -64,6 +100,21 @@ pub enum Error {
     Triples(#[from] crate::triples::Error),
     #[error("IO Error")]
     Io(#[from] std::io::Error),
+    #[error("the {0} section was not loaded and there is no file to load it from")]
+    NotLoaded(&'static str),
+    #[cfg(feature = "cache")]
+    #[error("failed to write cache")]
+    Cache(#[from] cache::Error),
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+}
+
+/// Triples errors caused by cancellation are reported as such.
+fn triples_error(e: crate::triples::Error) -> Error {
+    match e {
+        crate::triples::Error::Cancelled(c) => Error::Cancelled(c),
+        e => Error::Triples(e),
+    }
 }
 
 impl Hdt {
@@ -88,10 +139,30 @@ impl Hdt {
     /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").expect("error opening file");
     /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
     /// ```
-    pub fn read<R: BufRead>(mut reader: R) -> Result<Self> {
-        let header = Self::read_header(&mut reader)?;
-        let unvalidated_dict = FourSectDict::read(&mut reader)?;
-        let triples = TriplesBitmap::read_sect(&mut reader)?;
+    pub fn read<R: BufRead>(reader: R) -> Result<Self> {
+        Self::read_with_options(reader, ReadOptions::default())
+    }
+
+    /// Like [`Hdt::read`] with the given options.
+    /// # Example
+    /// ```
+    /// use hdt::hdt::{ReadOptions, Verify};
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").expect("error opening file");
+    /// // skip the CRC32 checksums of the section data for a trusted file
+    /// let options = ReadOptions { verify: Verify::Headers, ..Default::default() };
+    /// let hdt = hdt::Hdt::read_with_options(std::io::BufReader::new(file), options).unwrap();
+    /// ```
+    pub fn read_with_options<R: BufRead>(reader: R, options: ReadOptions) -> Result<Self> {
+        let mut reader = ProgressRead::new(reader, options.progress, None);
+        reader.phase(Phase::Header)?;
+        let header = Self::read_header(&mut reader).map_err(|e| reader.error(e))?;
+        reader.phase(Phase::Dictionary)?;
+        let unvalidated_dict =
+            FourSectDict::read_with(&mut reader, options.verify).map_err(|e| reader.error(e))?;
+        reader.phase(Phase::Triples)?;
+        let triples =
+            TriplesBitmap::read_sect_lazy(&mut reader, options).map_err(|e| reader.error(triples_error(e)))?;
+        triples.build_op_index_with(options.progress)?;
         let dict = unvalidated_dict.validate()?;
         let hdt = Hdt { header, dict, triples };
         debug!("HDT size in memory {}, details:", ByteSize(hdt.size_in_bytes() as u64));
@@ -120,28 +191,39 @@ impl Hdt {
     /// ```
     #[cfg(feature = "cache")]
     pub fn read_from_path(f: impl AsRef<Path>) -> Result<Self> {
+        Self::read_from_path_with_options(f, ReadOptions::default())
+    }
+
+    /// Like [`Hdt::read_from_path`] with the given options.
+    /// The verification and progress options only apply to the HDT file, not to the cache.
+    /// A cache that was written by a different version of this library, on a platform with a different pointer width
+    /// or for a different triples section is rejected and overwritten, see [`crate::cache`].
+    #[cfg(feature = "cache")]
+    pub fn read_from_path_with_options(f: impl AsRef<Path>, options: ReadOptions) -> Result<Self> {
         let f = f.as_ref();
         let source = File::open(f)?;
-        let mut reader = std::io::BufReader::new(source);
-        ControlInfo::read(&mut reader)?;
-        let header = Header::read(&mut reader)?;
-        let unvalidated_dict = FourSectDict::read(&mut reader)?;
-        let mut abs_path = std::fs::canonicalize(f)?;
-        let _ = abs_path.pop();
-        let index_file_name = format!("{}.{CACHE_EXT}", f.file_name().unwrap().to_str().unwrap());
-        let index_file_path = abs_path.join(index_file_name);
-        let triples = if index_file_path.exists() {
+        let total = source.metadata()?.len();
+        let mut reader = ProgressRead::new(std::io::BufReader::new(source), options.progress, Some(total));
+        reader.phase(Phase::Header)?;
+        let header = Self::read_header(&mut reader).map_err(|e| reader.error(e))?;
+        reader.phase(Phase::Dictionary)?;
+        let unvalidated_dict =
+            FourSectDict::read_with(&mut reader, options.verify).map_err(|e| reader.error(e))?;
+        reader.phase(Phase::Triples)?;
+        let cache_path = cache::cache_path(f, options.cache_dir)?;
+        let triples = if cache_path.exists() {
             let pos = reader.stream_position()?;
-            match Self::load_with_cache(&mut reader, &index_file_path, header.length) {
+            debug!("hdt file cache detected, loading from {}", cache_path.display());
+            match cache::load(&mut reader, &cache_path) {
                 Ok(triples) => triples,
                 Err(e) => {
-                    log::warn!("error loading cache, overwriting: {e}");
+                    log::warn!("rejected cache {}, overwriting: {e}", cache_path.display());
                     reader.seek(SeekFrom::Start(pos))?;
-                    Self::load_without_cache(&mut reader, &index_file_path, header.length)?
+                    Self::load_without_cache(&mut reader, &cache_path, options).map_err(|e| reader.error(e))?
                 }
             }
         } else {
-            Self::load_without_cache(&mut reader, &index_file_path, header.length)?
+            Self::load_without_cache(&mut reader, &cache_path, options).map_err(|e| reader.error(e))?
         };
 
         let dict = unvalidated_dict.validate()?;
@@ -151,55 +233,62 @@ impl Hdt {
         Ok(hdt)
     }
 
+    /// Reads the triples section, builds the object index and tries to save both to the cache file.
     #[cfg(feature = "cache")]
-    fn load_without_cache<R: BufRead>(
-        mut reader: R, index_file_path: &PathBuf, header_length: usize,
+    fn load_without_cache<R: BufRead + Seek>(
+        mut reader: R, cache_path: &Path, options: ReadOptions,
     ) -> Result<TriplesBitmap> {
-        use log::warn;
-
-        debug!("no cache detected, generating index");
-        let triples = TriplesBitmap::read_sect(&mut reader)?;
-        debug!("index generated, saving cache to {}", index_file_path.display());
-        if let Err(e) = Self::write_cache(index_file_path, &triples, header_length) {
-            warn!("error trying to save cache to file: {e}");
+        debug!("no valid cache detected, generating index");
+        let pos = reader.stream_position()?;
+        let (_, triples_hash) = cache::triples_hash(&mut reader)?;
+        reader.seek(SeekFrom::Start(pos))?;
+        let triples = TriplesBitmap::read_sect_lazy(&mut reader, options).map_err(triples_error)?;
+        triples.build_op_index_with(options.progress)?;
+        debug!("index generated, saving cache to {}", cache_path.display());
+        if let Err(e) = Self::write_cache(cache_path, &triples, triples_hash) {
+            log::warn!("error trying to save cache to file: {e}");
         }
         Ok(triples)
     }
 
+    /// Creates or overwrites the cache file of the given HDT file ahead of time, so that the first
+    /// [`Hdt::read_from_path`] is already fast, and returns its path.
+    /// # Example
+    /// ```
+    /// let dir = std::path::Path::new("tests/resources/generated/cache-build-doc");
+    /// let cache = hdt::Hdt::build_cache_in("tests/resources/snikmeta.hdt", Some(dir)).unwrap();
+    /// assert!(cache.exists());
+    /// ```
     #[cfg(feature = "cache")]
-    fn load_with_cache<R: BufRead>(
-        mut reader: R, index_file_path: &PathBuf, header_length: usize,
-    ) -> core::result::Result<TriplesBitmap, Box<dyn std::error::Error>> {
-        use std::io::Read;
-        // load cached index
-        debug!("hdt file cache detected, loading from {}", index_file_path.display());
-        let index_source = File::open(index_file_path)?;
-        let mut index_reader = std::io::BufReader::new(index_source);
-        let triples_ci = ControlInfo::read(&mut reader)?;
-        // we cannot rely on the numTriples property being present, see https://github.com/rdfhdt/hdt-cpp/issues/289
-        // let num_triples = triples_ci.get("numTriples").expect("numTriples key missing in triples CI");
-        // thus we use the number of bytes of the header data
-        let mut buf = [0u8; size_of::<usize>()];
-        index_reader.read_exact(&mut buf)?;
-        if header_length != usize::from_le_bytes(buf) {
-            return Err("failed index validation".into());
-        }
-        let triples = TriplesBitmap::load_cache(&mut index_reader, &triples_ci)?;
-        Ok(triples)
+    pub fn build_cache(f: impl AsRef<Path>) -> Result<PathBuf> {
+        Self::build_cache_in(f, None)
+    }
+
+    /// Like [`Hdt::build_cache`] but places the cache file into the given directory, which is created if necessary.
+    /// Use the same directory as [`ReadOptions::cache_dir`] when reading.
+    #[cfg(feature = "cache")]
+    pub fn build_cache_in(f: impl AsRef<Path>, cache_dir: Option<&Path>) -> Result<PathBuf> {
+        let f = f.as_ref();
+        let mut reader = std::io::BufReader::new(File::open(f)?);
+        Self::read_header(&mut reader)?;
+        FourSectDict::skip(&mut reader)?;
+        let pos = reader.stream_position()?;
+        let (_, triples_hash) = cache::triples_hash(&mut reader)?;
+        reader.seek(SeekFrom::Start(pos))?;
+        let triples = TriplesBitmap::read_sect(&mut reader).map_err(triples_error)?;
+        let cache_path = cache::cache_path(f, cache_dir)?;
+        Self::write_cache(&cache_path, &triples, triples_hash)?;
+        Ok(cache_path)
     }
 
     #[cfg(feature = "cache")]
     /// Writes a custom cache file to improve load times. This cache file is usuable only by
-    /// this library and is not intended to be used with hdt-cpp or hdt-java versions of the HDT tooling
+    /// this library and is not intended to be used with hdt-cpp or hdt-java versions of the HDT tooling.
+    /// The hash identifies the triples section of the HDT file, see [`cache::CacheHeader::triples_hash`].
     pub fn write_cache(
-        index_file_path: &PathBuf, triples: &TriplesBitmap, header_length: usize,
-    ) -> core::result::Result<(), Box<dyn std::error::Error>> {
-        let new_index_file = File::create(index_file_path)?;
-        let mut writer = std::io::BufWriter::new(new_index_file);
-        writer.write_all(&header_length.to_le_bytes())?;
-        bincode::serde::encode_into_std_write(triples, &mut writer, bincode::config::standard())?;
-        writer.flush()?;
-        Ok(())
+        cache_path: &Path, triples: &TriplesBitmap, triples_hash: u64,
+    ) -> core::result::Result<(), cache::Error> {
+        cache::write(cache_path, triples, triples_hash)
     }
 
     pub fn write(&self, write: &mut impl Write) -> Result<()> {
@@ -211,6 +300,14 @@ impl Hdt {
         Ok(())
     }
 
+    /// Like [`Hdt::write`] while reporting the number of bytes written to the observer, which can cancel writing.
+    /// After cancellation, the output is incomplete.
+    pub fn write_with_progress(&self, write: &mut impl Write, progress: &dyn Progress) -> Result<()> {
+        progress::report(Some(progress), Phase::Write, 0, None)?;
+        let mut write = ProgressWrite::new(write, progress);
+        self.write(&mut write).map_err(|e| write.cancelled.map_or(e, Error::Cancelled))
+    }
+
     /// Recursive size in bytes on the heap.
     pub fn size_in_bytes(&self) -> usize {
         self.dict.size_in_bytes() + self.triples.size_in_bytes()
@@ -227,8 +324,8 @@ impl Hdt {
     /// }
     /// ```
     pub fn triples_all(&self) -> impl Iterator<Item = StringTriple> + '_ {
-        let mut triple_cache = TripleCache::new(self);
-        self.triples.into_iter().map(move |ids| triple_cache.translate(ids).unwrap())
+        let mut triple_cache = TripleCache::default();
+        self.triples.into_iter().map(move |ids| triple_cache.translate(&self.dict, ids).unwrap())
     }
 
     /// Get all subjects with the given property and object (?PO pattern).
@@ -262,6 +359,11 @@ impl Hdt {
         )
     }
 
+    /// Like [`Hdt::subjects_with_po`] but returns an owned iterator that is `Send + 'static`, see [`crate::owned`].
+    pub fn subjects_with_po_owned(self: &Arc<Self>, p: &str, o: &str) -> OwnedSubjects {
+        OwnedSubjects::new(self.clone(), p, o)
+    }
+
     /// Get all triples that fit the given triple patterns, where `None` stands for a variable.
     /// For example, `triples_with_pattern(Some(s), Some(p), None)` answers an SP? pattern.
     /// # Example
@@ -276,14 +378,15 @@ impl Hdt {
     pub fn triples_with_pattern<'a>(
         &'a self, sp: Option<&'a str>, pp: Option<&'a str>, op: Option<&'a str>,
     ) -> Box<dyn Iterator<Item = StringTriple> + 'a> {
-        let pattern: [Option<(Arc<str>, usize)>; 3] = [(0, sp), (1, pp), (2, op)]
+        let pattern: [Option<(Arc<str>, Id)>; 3] = [(0, sp), (1, pp), (2, op)]
             .map(|(i, x)| x.map(|x| (Arc::from(x), self.dict.string_to_id(x, IdKind::KINDS[i]))));
         // at least one term does not exist in the graph
         if pattern.iter().flatten().any(|x| x.1 == 0) {
             return Box::new(iter::empty());
         }
         // TODO: improve error handling
-        let mut cache = TripleCache::new(self);
+        let mut cache = TripleCache::default();
+        let dict = &self.dict;
         match pattern {
             [Some(s), Some(p), Some(o)] => {
                 if SubjectIter::with_pattern(&self.triples, [s.1, p.1, o.1]).next().is_some() {
@@ -302,41 +405,55 @@ impl Hdt {
                     [s.0.clone(), Arc::from(self.dict.id_to_string(t[1], IdKind::Predicate).unwrap()), o.0.clone()]
                 }))
             }
-            [Some(s), None, None] => Box::new(
-                SubjectIter::with_pattern(&self.triples, [s.1, 0, 0])
-                    .map(move |t| [s.0.clone(), cache.get(1, t[1]).unwrap(), cache.get(2, t[2]).unwrap()]),
-            ),
+            [Some(s), None, None] => {
+                Box::new(SubjectIter::with_pattern(&self.triples, [s.1, 0, 0]).map(move |t| {
+                    [s.0.clone(), cache.get(dict, 1, t[1]).unwrap(), cache.get(dict, 2, t[2]).unwrap()]
+                }))
+            }
             [None, Some(p), Some(o)] => {
                 Box::new(PredicateObjectIter::new(&self.triples, p.1, o.1).map(move |sid| {
                     [Arc::from(self.dict.id_to_string(sid, IdKind::Subject).unwrap()), p.0.clone(), o.0.clone()]
                 }))
             }
-            [None, Some(p), None] => Box::new(
-                PredicateIter::new(&self.triples, p.1)
-                    .map(move |t| [cache.get(0, t[0]).unwrap(), p.0.clone(), cache.get(2, t[2]).unwrap()]),
-            ),
-            [None, None, Some(o)] => Box::new(
-                ObjectIter::new(&self.triples, o.1)
-                    .map(move |t| [cache.get(0, t[0]).unwrap(), cache.get(1, t[1]).unwrap(), o.0.clone()]),
-            ),
+            [None, Some(p), None] => Box::new(PredicateIter::new(&self.triples, p.1).map(move |t| {
+                [cache.get(dict, 0, t[0]).unwrap(), p.0.clone(), cache.get(dict, 2, t[2]).unwrap()]
+            })),
+            [None, None, Some(o)] => Box::new(ObjectIter::new(&self.triples, o.1).map(move |t| {
+                [cache.get(dict, 0, t[0]).unwrap(), cache.get(dict, 1, t[1]).unwrap(), o.0.clone()]
+            })),
             [None, None, None] => Box::new(self.triples_all()),
         }
     }
 
+    /// Like [`Hdt::triples_with_pattern`] but returns an owned iterator that is `Send + 'static`, see [`crate::owned`].
+    pub fn triples_with_pattern_owned(
+        self: &Arc<Self>, sp: Option<&str>, pp: Option<&str>, op: Option<&str>,
+    ) -> OwnedTriples {
+        OwnedTriples::new(self.clone(), sp, pp, op)
+    }
+
     /// Get all internal triple IDs that fit the given triple patterns, where `None` stands for a variable.
     /// Used for specific optimizations, call triples_with_pattern instead to get actual string results.
     pub fn triple_ids_with_pattern<'a>(
         &'a self, sp: Option<&'a str>, pp: Option<&'a str>, op: Option<&'a str>,
     ) -> Box<dyn Iterator<Item = TripleId> + 'a> {
-        let pattern: [Option<usize>; 3] =
-            [(0, sp), (1, pp), (2, op)].map(|(i, x)| x.map(|x| self.dict.string_to_id(x, IdKind::KINDS[i])));
+        match self.id_pattern([sp, pp, op]) {
+            Some(pattern) => self.triple_ids_with_id_pattern(pattern),
+            None => Box::new(iter::empty()),
+        }
+    }
+
+    /// Translates a pattern of optional strings into a triple ID pattern, where 0 stands for a variable.
+    /// Returns `None` if a constant is not in the dictionary.
+    pub(crate) fn id_pattern(&self, pattern: [Option<&str>; 3]) -> Option<TripleId> {
+        let pattern: [Option<Id>; 3] =
+            [0, 1, 2].map(|i| pattern[i].map(|x| self.dict.string_to_id(x, IdKind::KINDS[i])));
         // fail fast if any constant is 0, meaning missing from the dict
         if pattern.contains(&Some(0)) {
-            return Box::new(iter::empty());
+            return None;
         }
         // we did not fail, so all constants are >0 so we transform None values into 0 to signify variables
-        let pattern: TripleId = pattern.map(|x| x.unwrap_or(0));
-        self.triple_ids_with_id_pattern(pattern)
+        Some(pattern.map(|x| x.unwrap_or(0)))
     }
 
     /// Get all internal triple IDs that fit the given triple patterns, where 0 stands for a variable.
@@ -353,39 +470,121 @@ impl Hdt {
             (0, 0, 0) => Box::new(self.triples.into_iter()),
         }
     }
+
+    /// Like [`Hdt::triple_ids_with_id_pattern`] but returns an owned iterator that is `Send + 'static`, see [`crate::owned`].
+    pub fn triple_ids_with_id_pattern_owned(self: &Arc<Self>, pattern: TripleId) -> OwnedTripleIds {
+        OwnedTripleIds::new(self.clone(), pattern)
+    }
+
+    /// All nodes reachable from the start node via paths of the given predicates
+    /// whose length is between `min_depth` and `max_depth` (unbounded if `None`), which is traversed breadth-first in ID space.
+    /// For example, `rdfs:subClassOf+` corresponds to a minimum depth of 1 and no maximum depth.
+    /// Forward steps go from a subject ID to object IDs using [`SubjectIter`] and backward steps go from an object ID to subject IDs
+    /// using the object index, so for [`Direction::Forward`] the start is a subject ID and the result consists of object IDs and vice versa.
+    /// Only shared terms, which have the same subject and object ID, can continue a path, as the others do not occur in the other position.
+    /// A minimum depth of 0 includes the start node itself only if it also occurs in the other position.
+    /// Each node is returned once, in the order it was first reached.
+    /// # Example
+    /// All superclasses of meta:Chapter:
+    /// ```
+    /// use hdt::IdKind;
+    /// use hdt::hdt::Direction;
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let chapter = hdt.dict.string_to_id("http://www.snik.eu/ontology/meta/Chapter", IdKind::Subject);
+    /// let sub_class_of = hdt.dict.string_to_id("http://www.w3.org/2000/01/rdf-schema#subClassOf", IdKind::Predicate);
+    /// for id in hdt.reachable(chapter, &[sub_class_of], Direction::Forward, 1, None) {
+    ///     println!("{}", hdt.dict.id_to_string(id, IdKind::Object).unwrap());
+    /// }
+    /// ```
+    pub fn reachable(
+        &self, start: Id, predicates: &[Id], direction: Direction, min_depth: usize, max_depth: Option<usize>,
+    ) -> Vec<Id> {
+        let shared = self.dict.shared.num_strings() as Id;
+        let [num_sources, num_targets] = match direction {
+            Direction::Forward => [self.dict.subjects.num_strings(), self.dict.objects.num_strings()],
+            Direction::Backward => [self.dict.objects.num_strings(), self.dict.subjects.num_strings()],
+        }
+        .map(|n| n as Id + shared);
+        let num_predicates = self.dict.predicates.num_strings() as Id;
+        let predicates: Vec<Id> = predicates.iter().copied().filter(|&p| p != 0 && p <= num_predicates).collect();
+        let mut reached = Vec::new();
+        if start == 0 || start > num_sources {
+            return reached;
+        }
+        // visited bitset over the target IDs, only used from the minimum depth on
+        let mut visited = vec![0u64; (num_targets as usize + 1).div_ceil(64)];
+        let mut visit = |id: Id| {
+            let (word, bit) = (id as usize / 64, 1 << (id % 64));
+            let new = visited[word] & bit == 0;
+            visited[word] |= bit;
+            new
+        };
+        if min_depth == 0 && start <= shared && visit(start) {
+            reached.push(start);
+        }
+        let mut frontier = vec![start];
+        let mut depth = 0;
+        while !frontier.is_empty() && max_depth.is_none_or(|max| depth < max) {
+            depth += 1;
+            let mut next = Vec::new();
+            for &node in &frontier {
+                for &p in &predicates {
+                    let neighbors: Box<dyn Iterator<Item = Id>> = match direction {
+                        Direction::Forward => {
+                            Box::new(SubjectIter::with_pattern(&self.triples, [node, p, 0]).map(|t| t[2]))
+                        }
+                        Direction::Backward => Box::new(PredicateObjectIter::new(&self.triples, p, node)),
+                    };
+                    for n in neighbors {
+                        if depth < min_depth {
+                            next.push(n);
+                        } else if visit(n) {
+                            reached.push(n);
+                            next.push(n);
+                        }
+                    }
+                }
+            }
+            // before reaching the minimum depth, nodes may be revisited on longer paths
+            next.sort_unstable();
+            next.dedup();
+            next.retain(|&n| n <= shared);
+            frontier = next;
+        }
+        reached
+    }
 }
 
 /// A TripleCache stores the `Arc<str>` of the last returned triple
-#[derive(Clone, Debug)]
-struct TripleCache<'a> {
-    hdt: &'a Hdt,
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TripleCache {
     tid: TripleId,
     arc: [Option<Arc<str>>; 3],
 }
 
-impl<'a> TripleCache<'a> {
-    /// Build a new [`TripleCache`] for the given [`Hdt`]
-    const fn new(hdt: &'a super::Hdt) -> Self {
-        TripleCache { hdt, tid: [0; 3], arc: [None, None, None] }
-    }
-
+impl TripleCache {
     /// Translate a triple of indexes into a triple of strings.
-    fn translate(&mut self, t: TripleId) -> core::result::Result<StringTriple, TranslateError> {
+    pub(crate) fn translate(
+        &mut self, dict: &FourSectDict, t: TripleId,
+    ) -> core::result::Result<StringTriple, TranslateError> {
         // refactor when try_map for arrays becomes stable
         Ok([
-            self.get(0, t[0]).map_err(|e| TranslateError { e, t })?,
-            self.get(1, t[1]).map_err(|e| TranslateError { e, t })?,
-            self.get(2, t[2]).map_err(|e| TranslateError { e, t })?,
+            self.get(dict, 0, t[0]).map_err(|e| TranslateError { e, t })?,
+            self.get(dict, 1, t[1]).map_err(|e| TranslateError { e, t })?,
+            self.get(dict, 2, t[2]).map_err(|e| TranslateError { e, t })?,
         ])
     }
 
-    fn get(&mut self, pos: usize, id: Id) -> core::result::Result<Arc<str>, four_sect_dict::ExtractError> {
+    fn get(
+        &mut self, dict: &FourSectDict, pos: usize, id: Id,
+    ) -> core::result::Result<Arc<str>, four_sect_dict::ExtractError> {
         debug_assert!(id != 0);
         debug_assert!(pos < 3);
         if self.tid[pos] == id {
             Ok(self.arc[pos].as_ref().unwrap().clone())
         } else {
-            let ret: Arc<str> = self.hdt.dict.id_to_string(id, IdKind::KINDS[pos])?.into();
+            let ret: Arc<str> = dict.id_to_string(id, IdKind::KINDS[pos])?.into();
             self.arc[pos] = Some(ret.clone());
             self.tid[pos] = id;
             Ok(ret)
@@ -420,6 +619,27 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn verify() -> Result<()> {
+        init();
+        let mut buf = std::fs::read("tests/resources/snikmeta.hdt")?;
+        // corrupt the CRC32 of the object sequence at the end of the file
+        *buf.last_mut().unwrap() ^= 1;
+        let read = |verify| {
+            Hdt::read_with_options(std::io::Cursor::new(&buf), ReadOptions { verify, ..Default::default() })
+        };
+        assert!(matches!(
+            read(Verify::Full),
+            Err(Error::Triples(crate::triples::Error::Sequence(
+                _,
+                crate::containers::sequence::Error::InvalidCrc32Checksum(..)
+            )))
+        ));
+        snikmeta_check(&read(Verify::Headers)?)?;
+        snikmeta_check(&read(Verify::None)?)?;
+        Ok(())
+    }
+
     #[test]
     fn modify_header() -> Result<()> {
         use crate::containers::rdf::{Id as RdfId, Term as RdfTerm, Triple as RdfTriple};
@@ -1,42 +1,48 @@
 // //! *This module is available only if HDT is built with the `"sophia"` feature, included by default.*
 use crate::four_sect_dict::IdKind;
 use crate::hdt::Hdt;
-use crate::triples::{Id, ObjectIter, PredicateIter, PredicateObjectIter, SubjectIter};
+use crate::rdfs::Rdfs;
+use crate::triples::Id;
 use log::debug;
+use sophia::api::MownStr;
 use sophia::api::graph::Graph;
-use sophia::api::term::matcher::TermMatcher;
-use sophia::api::term::{BnodeId, IriRef, LanguageTag, Term};
+use sophia::api::ns::xsd;
+use sophia::api::term::matcher::{Any, TermMatcher};
+use sophia::api::term::{BnodeId, IriRef, LanguageTag, SimpleTerm, Term};
 use std::convert::Infallible;
 use std::io::{self, Error, ErrorKind};
 use std::iter;
 use std::sync::Arc;
 
+mod dataset;
+mod id_ranges;
 mod term;
+pub use dataset::HdtDataset;
+use id_ranges::{IdRanges, scan_size};
 pub use term::HdtTerm;
 
 /// HdtGraph does not support all of the Sophia TermMatcher functionality.
 enum HdtMatcher {
-    Constant((HdtTerm, Id)),
+    Constant(Id),
     Other,
 }
 
-fn id_term(hdt: &Hdt, id: Id, kind: IdKind) -> HdtTerm {
-    auto_term(&hdt.dict.id_to_string(id, kind).unwrap()).unwrap()
-    // TODO: optimize by excluding cases depending on the id kind
-    //IriRef::new_unchecked(MownStr::from(s)).into_term()
-}
-
 /// Transforms a Sophia TermMatcher to a constant HdtTerm and Id if possible.
 /// Returns none if it matches a constant term that cannot be found.
 fn unpack_matcher<T: TermMatcher>(hdt: &Hdt, tm: &T, kind: IdKind) -> Option<HdtMatcher> {
+    unpack_matcher_with(|s| hdt.dict.string_to_id(s, kind), tm)
+}
+
+/// Like [`unpack_matcher`] but with a custom string to ID translation.
+fn unpack_matcher_with<T: TermMatcher>(string_to_id: impl Fn(&str) -> Id, tm: &T) -> Option<HdtMatcher> {
     match tm.constant() {
         Some(t) => match HdtTerm::try_from(t.borrow_term()) {
             Some(t) => {
-                let id = hdt.dict.string_to_id(&term_string(&t), kind);
+                let id = string_to_id(&term_string(&t));
                 if id == 0 {
                     return None;
                 }
-                Some(HdtMatcher::Constant((t, id)))
+                Some(HdtMatcher::Constant(id))
             }
             None => None,
         },
@@ -46,7 +52,12 @@ fn unpack_matcher<T: TermMatcher>(hdt: &Hdt, tm: &T, kind: IdKind) -> Option<Hdt
 
 /// Create the correct Sophia term for a given resource string.
 /// Slow, use the appropriate method if you know which type (Literal, URI, or blank node) the string has.
-fn auto_term(s: &str) -> io::Result<HdtTerm> {
+pub(crate) fn auto_term(s: &str) -> io::Result<HdtTerm<'static>> {
+    str_term(s).map(hdt_term)
+}
+
+/// Like [`auto_term`] but borrows from the given string instead of allocating.
+pub(crate) fn str_term(s: &str) -> io::Result<SimpleTerm<'_>> {
     match s.chars().next() {
         None => Err(Error::new(ErrorKind::InvalidData, "empty input")),
         Some('"') => match s.rfind('"') {
@@ -55,16 +66,16 @@ fn auto_term(s: &str) -> io::Result<HdtTerm> {
                 format!("missing right quotation mark in literal string {s}"),
             )),
             Some(index) => {
-                let lex = Arc::from(&s[1..index]);
+                let lex = MownStr::from_ref(&s[1..index]);
                 let rest = &s[index + 1..];
                 // literal with no language tag and no datatype
                 if rest.is_empty() {
-                    return Ok(HdtTerm::LiteralDatatype(lex, term::XSD_STRING.clone()));
+                    return Ok(SimpleTerm::LiteralDatatype(lex, xsd::string.iri().unwrap()));
                 }
                 // either language tag or datatype
                 if let Some(tag_index) = rest.find('@') {
-                    let tag = LanguageTag::new_unchecked(Arc::from(&rest[tag_index + 1..]));
-                    return Ok(HdtTerm::LiteralLanguage(lex, tag));
+                    let tag = LanguageTag::new_unchecked(MownStr::from_ref(&rest[tag_index + 1..]));
+                    return Ok(SimpleTerm::LiteralLanguage(lex, tag, None));
                 }
                 // datatype
                 let mut dt_split = rest.split("^^");
@@ -73,21 +84,63 @@ fn auto_term(s: &str) -> io::Result<HdtTerm> {
                     || Err(Error::new(ErrorKind::InvalidData, format!("empty datatype in {s}"))),
                     |dt| {
                         let unquoted = &dt[1..dt.len() - 1];
-                        let dt = IriRef::new_unchecked(Arc::from(unquoted));
-                        Ok(HdtTerm::LiteralDatatype(lex, dt))
+                        let dt = IriRef::new_unchecked(MownStr::from_ref(unquoted));
+                        Ok(SimpleTerm::LiteralDatatype(lex, dt))
                     },
                 )
             }
         },
-        Some('_') => Ok(HdtTerm::BlankNode(BnodeId::new_unchecked(Arc::from(&s[2..])))),
-        _ => Ok(HdtTerm::Iri(IriRef::new_unchecked(Arc::from(s)))),
+        Some('_') => Ok(SimpleTerm::BlankNode(BnodeId::new_unchecked(MownStr::from_ref(&s[2..])))),
+        _ => Ok(SimpleTerm::Iri(IriRef::new_unchecked(MownStr::from_ref(s)))),
+    }
+}
+
+/// Copies a term created by [`str_term`] into an [`HdtTerm`].
+fn hdt_term(t: SimpleTerm<'_>) -> HdtTerm<'static> {
+    match t {
+        // share the most common datatype instead of allocating it
+        SimpleTerm::LiteralDatatype(lex, dt) if dt.as_str() == term::XSD_STRING.as_str() => {
+            HdtTerm::LiteralDatatype(Arc::from(lex.as_ref()), term::XSD_STRING.clone())
+        }
+        t => HdtTerm::try_from(t).expect("str_term only creates IRIs, blank nodes and literals"),
+    }
+}
+
+/// Filters the ID backed terms of IDs with a matcher, which only decodes them if it needs their contents.
+/// Remembers the result for the last ID, which repeats for example for the subjects of consecutive triples.
+struct TermFilter<'a, T> {
+    hdt: &'a Hdt,
+    kind: IdKind,
+    matcher: T,
+    /// whether all IDs are already known to match
+    resolved: bool,
+    last: Option<(Id, bool)>,
+}
+
+impl<'a, T: TermMatcher> TermFilter<'a, T> {
+    const fn new(hdt: &'a Hdt, kind: IdKind, matcher: T, resolved: bool) -> Self {
+        TermFilter { hdt, kind, matcher, resolved, last: None }
+    }
+
+    /// The term of the given ID if it matches.
+    fn term(&mut self, id: Id) -> Option<HdtTerm<'a>> {
+        let term = HdtTerm::Id(self.hdt, id, self.kind);
+        if self.resolved {
+            return Some(term);
+        }
+        let matches = match self.last {
+            Some((last, matches)) if last == id => matches,
+            _ => self.matcher.matches(&term),
+        };
+        self.last = Some((id, matches));
+        matches.then_some(term)
     }
 }
 
 // Convert a SimpleTerm into the HDT String format.
 // Sophia doesn't include the _: prefix for blank node strings but HDT expects it
 // not needed for property terms, as they can't be blank nodes
-fn term_string(t: &HdtTerm) -> String {
+fn term_string(t: &HdtTerm<'_>) -> String {
     match t {
         HdtTerm::BlankNode(b) => "_:".to_owned() + b.as_str(),
         HdtTerm::Iri(i) => i.as_str().to_owned(),
@@ -99,11 +152,12 @@ fn term_string(t: &HdtTerm) -> String {
             let dts = dt.as_str();
             if dts == xsd_string { format!("\"{l}\"") } else { format!("\"{l}\"^^<{dts}>") }
         }
+        HdtTerm::Id(hdt, id, kind) => hdt.dict.id_to_string(*id, *kind).unwrap(),
     }
 }
 
 impl Graph for Hdt {
-    type Triple<'a> = [HdtTerm; 3];
+    type Triple<'a> = [HdtTerm<'a>; 3];
     type Error = Infallible; // infallible for now, figure out what to put here later
 
     /// # Example
@@ -115,13 +169,18 @@ impl Graph for Hdt {
     /// ```
     fn triples(&self) -> impl Iterator<Item = Result<Self::Triple<'_>, Self::Error>> {
         debug!("Iterating through ALL triples in the HDT Graph. This can be inefficient for large graphs.");
-        self.triples_all().map(move |[s, p, o]| {
-            Ok([auto_term(&s).unwrap(), HdtTerm::Iri(IriRef::new_unchecked(p)), auto_term(&o).unwrap()])
+        self.triples.into_iter().map(move |[s, p, o]| {
+            Ok([
+                HdtTerm::Id(self, s, IdKind::Subject),
+                HdtTerm::Id(self, p, IdKind::Predicate),
+                HdtTerm::Id(self, o, IdKind::Object),
+            ])
         })
     }
 
-    /// Only supports constant and "any" matchers.
-    /// Non-constant matchers are supposed to be "any" matchers.
+    /// Constant matchers are answered using the indexes, other matchers are applied as filters.
+    /// Use [`Hdt::triples_matching_any`] for finite sets of terms or for literal objects,
+    /// which are answered using the indexes as well.
     /// # Example
     /// Who was born in Leipzig?
     /// ```
@@ -144,62 +203,168 @@ impl Graph for Hdt {
         P: TermMatcher + 't,
         O: TermMatcher + 't,
     {
-        use HdtMatcher::{Constant, Other};
         let Some(xso) = unpack_matcher(self, &sm, IdKind::Subject) else {
             return Box::new(iter::empty()) as Box<dyn Iterator<Item = _>>;
         };
         let Some(xpo) = unpack_matcher(self, &pm, IdKind::Predicate) else { return Box::new(iter::empty()) };
         let Some(xoo) = unpack_matcher(self, &om, IdKind::Object) else { return Box::new(iter::empty()) };
-        // TODO: improve error handling
-        match (xso, xpo, xoo) {
-            //if SubjectIter::with_pattern(&self.triples, [s.1, p.1, o.1]).next().is_some() { // always true
-            (Constant(s), Constant(p), Constant(o)) => Box::new(iter::once(Ok([s.0, p.0, o.0]))),
-            (Constant(s), Constant(p), Other) => Box::new(
-                SubjectIter::with_pattern(&self.triples, [s.1, p.1, 0])
-                    .map(|tid| auto_term(&self.dict.id_to_string(tid[2], IdKind::Object).unwrap()).unwrap())
-                    .filter(move |term| om.matches(term))
-                    .map(move |term| Ok([s.0.clone(), p.0.clone(), term])),
-            ),
-            (Constant(s), Other, Constant(o)) => Box::new(
-                SubjectIter::with_pattern(&self.triples, [s.1, 0, o.1])
-                    .map(|t| id_term(self, t[1], IdKind::Predicate))
-                    .filter(move |term| pm.matches(term))
-                    .map(move |term| Ok([s.0.clone(), term, o.0.clone()])),
-            ),
-            (Constant(s), Other, Other) => Box::new(
-                SubjectIter::with_pattern(&self.triples, [s.1, 0, 0])
-                    .map(move |t| [id_term(self, t[1], IdKind::Predicate), id_term(self, t[2], IdKind::Object)])
-                    .filter(move |[pt, ot]| pm.matches(pt) && om.matches(ot))
-                    .map(move |[pt, ot]| Ok([s.0.clone(), pt, ot])),
-            ),
-            (Other, Constant(p), Constant(o)) => Box::new(
-                PredicateObjectIter::new(&self.triples, p.1, o.1)
-                    .map(|sid| id_term(self, sid, IdKind::Subject))
-                    .filter(move |term| sm.matches(term))
-                    .map(move |term| Ok([term, p.0.clone(), o.0.clone()])),
-            ),
-            (Other, Constant(p), Other) => Box::new(
-                PredicateIter::new(&self.triples, p.1)
-                    .map(move |t| [id_term(self, t[0], IdKind::Subject), id_term(self, t[2], IdKind::Object)])
-                    .filter(move |[st, ot]| sm.matches(st) && om.matches(ot))
-                    .map(move |[st, ot]| Ok([st, p.0.clone(), ot])),
-            ),
-            (Other, Other, Constant(o)) => Box::new(ObjectIter::new(&self.triples, o.1).map(move |t| {
-                Ok([
-                    auto_term(&Arc::from(self.dict.id_to_string(t[0], IdKind::Subject).unwrap())).unwrap(),
-                    id_term(self, t[1], IdKind::Predicate),
-                    o.0.clone(),
-                ])
+        let pattern = [xso, xpo, xoo].map(|m| match m {
+            HdtMatcher::Constant(id) => id,
+            HdtMatcher::Other => 0,
+        });
+        // matchers of constant positions don't need to be tested again
+        let mut sf = TermFilter::new(self, IdKind::Subject, sm, pattern[0] != 0);
+        let mut pf = TermFilter::new(self, IdKind::Predicate, pm, pattern[1] != 0);
+        let mut of = TermFilter::new(self, IdKind::Object, om, pattern[2] != 0);
+        Box::new(
+            self.triple_ids_with_id_pattern(pattern)
+                .filter_map(move |t| Some(Ok([sf.term(t[0])?, pf.term(t[1])?, of.term(t[2])?]))),
+        )
+    }
+}
+
+/// A set of terms for one position of a triple pattern in [`Hdt::triples_matching_any`].
+/// Unlike a Sophia [`TermMatcher`], its members can be looked up in the dictionary.
+#[derive(Debug)]
+pub enum TermSet<'a, T> {
+    /// All terms.
+    Any,
+    /// The given terms, which are looked up individually. Terms that are not in the dictionary are ignored.
+    Terms(&'a [T]),
+    /// All literals, found by binary search in the object section. Empty for subjects and predicates.
+    Literals,
+}
+
+// derived implementations would require T: Copy
+impl<T> Clone for TermSet<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TermSet<'_, T> {}
+
+impl Hdt {
+    /// Triples whose subject, predicate and object are in the respective set.
+    /// The sets are resolved to IDs without scanning the dictionary,
+    /// and the pattern is answered as a union of indexed lookups for the set with the fewest triples,
+    /// if they are fewer than those of the remaining pattern; the other sets are checked by their IDs.
+    /// The order of the results depends on which set is looked up.
+    /// # Example
+    /// Labels of two classes:
+    /// ```
+    /// use hdt::hdt_graph::TermSet;
+    /// use hdt::sophia::api::term::IriRef;
+    ///
+    /// let hdt = hdt::Hdt::read_from_path(std::path::Path::new("tests/resources/snikmeta.hdt")).unwrap();
+    /// let classes = ["Chapter", "Method"].map(|c| IriRef::new_unchecked(format!("http://www.snik.eu/ontology/meta/{c}")));
+    /// let label = [IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#label".to_owned())];
+    /// let labels = hdt.triples_matching_any(TermSet::Terms(&classes), TermSet::Terms(&label), TermSet::Literals);
+    /// assert_eq!(labels.count(), 4);
+    /// ```
+    pub fn triples_matching_any<T: Term>(
+        &self, s: TermSet<'_, T>, p: TermSet<'_, T>, o: TermSet<'_, T>,
+    ) -> impl Iterator<Item = [HdtTerm<'_>; 3]> + '_ {
+        let mut sets = [
+            IdRanges::resolve(self, IdKind::Subject, s),
+            IdRanges::resolve(self, IdKind::Predicate, p),
+            IdRanges::resolve(self, IdKind::Object, o),
+        ];
+        if sets.iter().flatten().any(IdRanges::is_empty) {
+            return Box::new(iter::empty()) as Box<dyn Iterator<Item = _>>;
+        }
+        // single IDs are constants of the pattern
+        let mut pattern = [0; 3];
+        for i in 0..3 {
+            if let Some(id) = sets[i].as_ref().and_then(IdRanges::single) {
+                pattern[i] = id;
+                sets[i] = None;
+            }
+        }
+        let scan = scan_size(self, pattern);
+        // answer as union of indexed lookups for the ID set with the least triples, if they are less than the scan
+        let driver = (0..3)
+            .filter_map(|i| Some((i, sets[i].as_ref()?.scan_size(self, pattern, i))))
+            .min_by_key(|(_, size)| *size)
+            .filter(|(_, size)| *size < scan)
+            .map(|(i, _)| (i, sets[i].take().unwrap()));
+        let ids = match driver {
+            Some((i, set)) => Box::new(set.into_ids().flat_map(move |id| {
+                let mut p = pattern;
+                p[i] = id;
+                self.triple_ids_with_id_pattern(p)
             })),
-            (Other, Other, Other) => Box::new(
-                self.triples_all()
-                    .map(move |[s, p, o]| {
-                        [auto_term(&s).unwrap(), HdtTerm::Iri(IriRef::new_unchecked(p)), auto_term(&o).unwrap()]
-                    })
-                    .filter(move |[st, pt, ot]| sm.matches(st) && pm.matches(pt) && om.matches(ot))
-                    .map(Result::Ok),
-            ),
+            None => self.triple_ids_with_id_pattern(pattern),
+        };
+        Box::new(
+            ids.filter(move |t| {
+                sets.iter().zip(t).all(|(set, &id)| set.as_ref().is_none_or(|set| set.contains(id)))
+            })
+            .map(move |[s, p, o]| {
+                [
+                    HdtTerm::Id(self, s, IdKind::Subject),
+                    HdtTerm::Id(self, p, IdKind::Predicate),
+                    HdtTerm::Id(self, o, IdKind::Object),
+                ]
+            }),
+        )
+    }
+}
+
+impl Graph for Rdfs<'_> {
+    type Triple<'a>
+        = [HdtTerm<'a>; 3]
+    where
+        Self: 'a;
+    type Error = Infallible;
+
+    fn triples(&self) -> impl Iterator<Item = Result<Self::Triple<'_>, Self::Error>> {
+        debug!("Iterating through ALL entailed triples. This can be inefficient for large graphs.");
+        self.triples_matching(Any, Any, Any)
+    }
+
+    /// Includes triples entailed by RDFS, see [`Rdfs`].
+    /// Only supports constant and "any" matchers, other matchers are filtered.
+    /// # Example
+    /// All instances of meta:EntityType including those of its subclasses:
+    /// ```
+    /// use hdt::sophia::api::graph::Graph;
+    /// use hdt::sophia::api::ns::rdf;
+    /// use hdt::sophia::api::term::{IriRef, SimpleTerm, matcher::Any};
+    ///
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let rdfs = hdt::rdfs::Rdfs::new(&hdt).unwrap();
+    /// let entity_type = SimpleTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta/EntityType".into()));
+    /// let instances = rdfs.triples_matching(Any, [rdf::type_], [entity_type]);
+    /// ```
+    fn triples_matching<'s, 't, S, P, O>(
+        &'s self, sm: S, pm: P, om: O,
+    ) -> impl Iterator<Item = Result<Self::Triple<'s>, Self::Error>> + 't
+    where
+        's: 't,
+        S: TermMatcher + 't,
+        P: TermMatcher + 't,
+        O: TermMatcher + 't,
+    {
+        let matchers = [
+            unpack_matcher_with(|s| self.string_to_id(s, IdKind::Subject), &sm),
+            unpack_matcher_with(|s| self.string_to_id(s, IdKind::Predicate), &pm),
+            unpack_matcher_with(|s| self.string_to_id(s, IdKind::Object), &om),
+        ];
+        if matchers.iter().any(Option::is_none) {
+            return Box::new(iter::empty()) as Box<dyn Iterator<Item = _>>;
         }
+        let pattern = matchers.map(|m| match m {
+            Some(HdtMatcher::Constant(id)) => id,
+            _ => 0,
+        });
+        Box::new(self.triple_ids_with_id_pattern(pattern).filter_map(move |t| {
+            let [s, p, o] = [0, 1, 2].map(|i| self.id_to_string(t[i], IdKind::KINDS[i]).unwrap());
+            let [st, pt, ot] = [&s, &p, &o].map(|x| str_term(x).unwrap());
+            (sm.matches(&st) && pm.matches(&pt) && om.matches(&ot))
+                .then(|| Ok([hdt_term(st), hdt_term(pt), hdt_term(ot)]))
+        }))
     }
 }
 
@@ -209,7 +374,6 @@ mod tests {
     use crate::tests::init;
     use fs_err::File;
     use sophia::api::prelude::Triple;
-    use sophia::api::term::matcher::Any;
 
     #[test]
     fn test_graph() -> color_eyre::Result<()> {
@@ -274,6 +438,10 @@ mod tests {
                 .map(Result::unwrap)
                 .collect::<Vec<_>>()
         );
+        // fully constant patterns only match triples that are contained in the graph
+        assert_eq!(1, graph.triples_matching([&s], [&label], [&o]).count());
+        let meta = HdtTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta".into()));
+        assert_eq!(0, graph.triples_matching([&meta], [&label], [&o]).count());
         assert_eq!(1, graph.triples_matching(Any, Any, ["22.10"]).count());
         let date = HdtTerm::LiteralDatatype(
             "2022-10-20".into(),
@@ -281,7 +449,6 @@ mod tests {
         );
         assert_eq!(1, graph.triples_matching(Any, Any, Some(&date)).count());
         // *** matchers other than constant and Any ********************************************
-        let meta = HdtTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta".into()));
         let modified = HdtTerm::Iri(IriRef::new_unchecked("http://purl.org/dc/terms/modified".into()));
         // SPO
         assert_eq!(2, graph.triples_matching([&meta, &s], [&label, &modified], [&date, &o]).count());
@@ -328,4 +495,118 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn non_constant_matchers() -> color_eyre::Result<()> {
+        use sophia::api::term::TermKind;
+        // resolved matchers may change the order of the results
+        fn sorted(mut v: Vec<[HdtTerm<'_>; 3]>) -> Vec<[HdtTerm<'_>; 3]> {
+            v.sort_by_cached_key(|t| format!("{t:?}"));
+            v
+        }
+        fn collect<'a>(it: impl Iterator<Item = Result<[HdtTerm<'a>; 3], Infallible>>) -> Vec<[HdtTerm<'a>; 3]> {
+            sorted(it.map(Result::unwrap).collect())
+        }
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let all: Vec<[HdtTerm; 3]> = hdt.triples().map(Result::unwrap).collect();
+        let top = HdtTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta/Top".into()));
+        let entity = HdtTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta/Entity".into()));
+        let label = HdtTerm::Iri(IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#label".into()));
+        let subjects = [top.clone(), entity.clone()];
+        let expected = |f: &dyn Fn(&[HdtTerm; 3]) -> bool| -> Vec<_> {
+            sorted(all.iter().filter(|t| f(t)).cloned().collect())
+        };
+        // two subjects are not a constant, a term kind matcher is not either, both are applied as filters
+        assert_eq!(
+            collect(Box::new(hdt.triples_matching(subjects.clone(), Any, TermKind::Literal))),
+            expected(&|t| subjects.contains(&t[0]) && t[2].kind() == TermKind::Literal)
+        );
+        assert_eq!(
+            collect(Box::new(hdt.triples_matching(TermKind::Iri, [&label, &top], Any))),
+            expected(&|t| t[1] == label)
+        );
+        // constant sets and literals are looked up
+        let (any, literals) = (TermSet::<HdtTerm>::Any, TermSet::<HdtTerm>::Literals);
+        assert_eq!(
+            sorted(hdt.triples_matching_any(TermSet::Terms(&subjects), any, literals).collect()),
+            expected(&|t| subjects.contains(&t[0]) && t[2].kind() == TermKind::Literal)
+        );
+        assert_eq!(
+            sorted(hdt.triples_matching_any(any, any, literals).collect()),
+            expected(&|t| t[2].kind() == TermKind::Literal)
+        );
+        assert_eq!(
+            sorted(
+                hdt.triples_matching_any(
+                    TermSet::Terms(&subjects),
+                    TermSet::Terms(std::slice::from_ref(&label)),
+                    any
+                )
+                .collect()
+            ),
+            expected(&|t| subjects.contains(&t[0]) && t[1] == label)
+        );
+        let literal = all.iter().find(|t| t[0] == top && t[1] == label).unwrap()[2].clone();
+        assert_eq!(
+            sorted(
+                hdt.triples_matching_any(
+                    any,
+                    TermSet::Terms(&[label.clone(), top.clone()]),
+                    TermSet::Terms(std::slice::from_ref(&literal))
+                )
+                .collect()
+            ),
+            expected(&|t| t[1] == label && t[2] == literal)
+        );
+        assert_eq!(hdt.triples_matching_any(literals, any, any).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn id_terms() -> color_eyre::Result<()> {
+        use sophia::api::term::TermKind;
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let other = crate::hdt::tests::snikmeta()?;
+        let shared = hdt.dict.shared.num_strings() as Id;
+        let id = |id, kind| HdtTerm::Id(&hdt, id, kind);
+        // subject and object IDs stand for the same term only in the shared section
+        assert_eq!(id(shared, IdKind::Subject), id(shared, IdKind::Object));
+        assert_ne!(id(shared + 1, IdKind::Subject), id(shared + 1, IdKind::Object));
+        // terms of different HDTs and eager terms are compared by their contents
+        assert_eq!(id(1, IdKind::Subject), HdtTerm::Id(&other, 1, IdKind::Object));
+        assert_eq!(id(1, IdKind::Subject), id(1, IdKind::Subject).into_owned());
+        let label = HdtTerm::Iri(IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#label".into()));
+        let label_id = hdt.dict.string_to_id(label.iri().unwrap().as_str(), IdKind::Predicate);
+        assert_eq!(id(label_id, IdKind::Predicate), label);
+        assert_eq!(id(label_id, IdKind::Predicate).kind(), TermKind::Iri);
+        let literal = id(hdt.dict.literal_ids().start, IdKind::Object);
+        assert_eq!(literal.kind(), TermKind::Literal);
+        assert!(literal.lexical_form().is_some() && literal.iri().is_none());
+        assert_eq!(literal.clone().into_owned(), literal);
+        assert!(literal.datatype().is_some() && id(label_id, IdKind::Predicate).datatype().is_none());
+        // the kinds of subjects and objects are found without decoding them into terms
+        let blank = hdt.dict.string_to_id("_:b1", IdKind::Subject);
+        assert_eq!(id(blank, IdKind::Subject).kind(), TermKind::BlankNode);
+        assert_eq!(id(blank, IdKind::Subject).bnode_id().unwrap().as_str(), "b1");
+        assert!(id(blank, IdKind::Subject).iri().is_none() && id(blank, IdKind::Subject).lexical_form().is_none());
+        assert_eq!(id(1, IdKind::Subject).kind(), id(1, IdKind::Subject).into_owned().kind());
+        Ok(())
+    }
+
+    #[test]
+    fn rdfs_graph() -> color_eyre::Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let rdfs = Rdfs::new(&hdt)?;
+        assert_eq!(rdfs.triples().count(), rdfs.triple_ids_with_id_pattern([0, 0, 0]).count());
+        let sub_class_of =
+            HdtTerm::Iri(IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#subClassOf".into()));
+        let thing = HdtTerm::Iri(IriRef::new_unchecked("http://www.w3.org/2002/07/owl#Thing".into()));
+        let explicit = hdt.triples_matching(Any, [&sub_class_of], [&thing]).count();
+        let entailed = rdfs.triples_matching(Any, [&sub_class_of], [&thing]).count();
+        assert!(entailed > explicit, "{entailed} entailed subclasses of owl:Thing, {explicit} explicit");
+        Ok(())
+    }
 }
@@ -0,0 +1,225 @@
+//! I define [`HdtDataset`], an implementation of [`sophia::api::dataset::Dataset`] over several HDT files.
+use super::{HdtTerm, term_string};
+use crate::four_sect_dict::IdKind;
+use crate::hdt::Hdt;
+use crate::triples::{Id, SubjectIter};
+use sophia::api::dataset::{DResult, Dataset};
+use sophia::api::graph::Graph;
+use sophia::api::quad::Spog;
+use sophia::api::term::matcher::{Any, GraphNameMatcher, TermMatcher};
+use sophia::api::term::{Term, TermKind};
+use std::convert::Infallible;
+use std::io::{self, Error, ErrorKind};
+use std::sync::Arc;
+
+/// Several HDT files exposed as the named graphs of a Sophia dataset.
+/// The default graph is the union of all named graphs, triples contained in several files occur only once in it.
+/// # Example
+/// ```
+/// use hdt::hdt_graph::HdtDataset;
+/// use hdt::sophia::api::dataset::Dataset;
+/// use hdt::sophia::api::term::IriRef;
+///
+/// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+/// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+/// let mut dataset = HdtDataset::new();
+/// dataset.insert(IriRef::new_unchecked("http://www.snik.eu/ontology/meta"), hdt).unwrap();
+/// assert_eq!(dataset.quads().count(), 2 * 328); // once in the named graph and once in the default graph
+/// ```
+#[derive(Debug, Default)]
+pub struct HdtDataset {
+    graphs: Vec<(HdtTerm<'static>, Hdt)>,
+}
+
+impl HdtDataset {
+    /// Creates a dataset without any graphs.
+    pub const fn new() -> Self {
+        Self { graphs: Vec::new() }
+    }
+
+    /// Adds the given HDT as the graph with the given name, which must be an IRI or a blank node.
+    /// Returns the HDT that was previously stored under that name, if any.
+    pub fn insert<T: Term>(&mut self, name: T, hdt: Hdt) -> io::Result<Option<Hdt>> {
+        if !matches!(name.kind(), TermKind::Iri | TermKind::BlankNode) {
+            return Err(Error::new(ErrorKind::InvalidInput, "graph name must be an IRI or a blank node"));
+        }
+        let name = HdtTerm::try_from(name).expect("IRIs and blank nodes are supported");
+        if let Some(i) = self.graphs.iter().position(|(n, _)| *n == name) {
+            return Ok(Some(std::mem::replace(&mut self.graphs[i].1, hdt)));
+        }
+        self.graphs.push((name, hdt));
+        Ok(None)
+    }
+
+    /// The HDT stored as the graph with the given name, if any.
+    pub fn graph<T: Term>(&self, name: T) -> Option<&Hdt> {
+        let name = HdtTerm::try_from(name)?;
+        self.graphs.iter().find(|(n, _)| *n == name).map(|(_, hdt)| hdt)
+    }
+
+    /// The names of all graphs in insertion order.
+    pub fn graph_names(&self) -> impl Iterator<Item = &HdtTerm<'static>> {
+        self.graphs.iter().map(|(name, _)| name)
+    }
+
+    /// The number of named graphs.
+    pub const fn len(&self) -> usize {
+        self.graphs.len()
+    }
+
+    /// Whether there are no named graphs.
+    pub const fn is_empty(&self) -> bool {
+        self.graphs.is_empty()
+    }
+}
+
+/// Allows the same matcher to be passed to the triple iterators of several graphs.
+struct SharedMatcher<M>(Arc<M>);
+
+impl<M> Clone for SharedMatcher<M> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<M: TermMatcher> TermMatcher for SharedMatcher<M> {
+    type Term = M::Term;
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        self.0.matches(term)
+    }
+
+    fn constant(&self) -> Option<&Self::Term> {
+        self.0.constant()
+    }
+}
+
+/// Looks up triples of other graphs in an HDT.
+/// Terms of the same HDT are looked up by their IDs, the translation of other ID backed terms is remembered for each position,
+/// as it repeats for example for the subjects of consecutive triples.
+struct Lookup<'a> {
+    hdt: &'a Hdt,
+    last: [Option<(Id, Id)>; 3],
+}
+
+impl<'a> Lookup<'a> {
+    const fn new(hdt: &'a Hdt) -> Self {
+        Self { hdt, last: [None; 3] }
+    }
+
+    /// The ID of the term in the given position of a triple, 0 if it is not in the dictionary.
+    fn id(&mut self, i: usize, term: &HdtTerm<'_>) -> Id {
+        let kind = IdKind::KINDS[i];
+        match term {
+            HdtTerm::Id(hdt, id, _) if std::ptr::eq(*hdt, self.hdt) => *id,
+            HdtTerm::Id(hdt, id, term_kind) => match self.last[i] {
+                Some((from, to)) if from == *id => to,
+                _ => {
+                    let to =
+                        hdt.dict.with_str(*id, *term_kind, |s| self.hdt.dict.string_to_id(s, kind)).unwrap_or(0);
+                    self.last[i] = Some((*id, to));
+                    to
+                }
+            },
+            _ => self.hdt.dict.string_to_id(&term_string(term), kind),
+        }
+    }
+
+    /// Whether the HDT contains the given triple.
+    fn contains(&mut self, triple: &[HdtTerm<'_>; 3]) -> bool {
+        let mut pattern = [0; 3];
+        for (i, term) in triple.iter().enumerate() {
+            pattern[i] = self.id(i, term);
+            if pattern[i] == 0 {
+                return false;
+            }
+        }
+        SubjectIter::with_pattern(&self.hdt.triples, pattern).next().is_some()
+    }
+}
+
+impl Dataset for HdtDataset {
+    type Quad<'a> = Spog<HdtTerm<'a>>;
+    type Error = Infallible;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.quads_matching(Any, Any, Any, Any)
+    }
+
+    /// Dispatches the triple pattern to the ID based iterators of each graph matched by `gm`, see [`Hdt::triples_matching`].
+    /// Quads of the default graph are skipped if their triple is contained in a previous graph.
+    /// # Example
+    /// Where is meta:Top labelled?
+    /// ```
+    /// use hdt::hdt_graph::HdtDataset;
+    /// use hdt::sophia::api::dataset::Dataset;
+    /// use hdt::sophia::api::ns::rdfs;
+    /// use hdt::sophia::api::term::{IriRef, matcher::Any};
+    ///
+    /// fn query(dataset: &HdtDataset) {
+    ///     let top = IriRef::new_unchecked("http://www.snik.eu/ontology/meta/Top");
+    ///     let labels = dataset.quads_matching([top], [rdfs::label], Any, Any);
+    /// }
+    /// ```
+    fn quads_matching<'s, 't, S, P, O, G>(
+        &'s self, sm: S, pm: P, om: O, gm: G,
+    ) -> impl Iterator<Item = DResult<Self, Self::Quad<'s>>> + 't
+    where
+        's: 't,
+        S: TermMatcher + 't,
+        P: TermMatcher + 't,
+        O: TermMatcher + 't,
+        G: GraphNameMatcher + 't,
+    {
+        let (sm, pm, om) = (SharedMatcher(Arc::new(sm)), SharedMatcher(Arc::new(pm)), SharedMatcher(Arc::new(om)));
+        let (dsm, dpm, dom) = (sm.clone(), pm.clone(), om.clone());
+        let named: Vec<_> = self.graphs.iter().filter(|(name, _)| gm.matches(Some(name))).collect();
+        let union = if gm.matches(None::<&HdtTerm>) { &self.graphs[..] } else { &[] };
+        let named_quads = named.into_iter().flat_map(move |(name, hdt)| {
+            hdt.triples_matching(sm.clone(), pm.clone(), om.clone())
+                .map(move |t| t.map(|t| (t, Some(name.clone()))))
+        });
+        let default_quads = union.iter().enumerate().flat_map(move |(i, (_, hdt))| {
+            let mut previous: Vec<_> = union[..i].iter().map(|(_, h)| Lookup::new(h)).collect();
+            hdt.triples_matching(dsm.clone(), dpm.clone(), dom.clone())
+                .filter(move |t| t.as_ref().is_ok_and(|t| !previous.iter_mut().any(|l| l.contains(t))))
+                .map(|t| t.map(|t| (t, None)))
+        });
+        named_quads.chain(default_quads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+    use sophia::api::ns::rdfs;
+    use sophia::api::term::{BnodeId, IriRef};
+
+    #[test]
+    fn dataset() -> color_eyre::Result<()> {
+        init();
+        let a = HdtTerm::Iri(IriRef::new_unchecked("http://example.org/a".into()));
+        let b = HdtTerm::BlankNode(BnodeId::new_unchecked("b".into()));
+        let mut dataset = HdtDataset::new();
+        assert!(dataset.insert(&a, crate::hdt::tests::snikmeta()?)?.is_none());
+        assert!(dataset.insert(&b, crate::hdt::tests::snikmeta()?)?.is_none());
+        assert!(dataset.insert(&a, crate::hdt::tests::snikmeta()?)?.is_some());
+        assert!(dataset.insert("literal", crate::hdt::tests::snikmeta()?).is_err());
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.graph_names().collect::<Vec<_>>(), [&a, &b]);
+        assert!(dataset.graph(&b).is_some());
+        // each triple is in both named graphs but only once in the default graph
+        assert_eq!(dataset.quads().count(), 3 * 328);
+        assert_eq!(dataset.quads_matching(Any, Any, Any, [Some(&a)]).count(), 328);
+        assert_eq!(dataset.quads_matching(Any, Any, Any, [None::<&HdtTerm>]).count(), 328);
+        let top = HdtTerm::Iri(IriRef::new_unchecked("http://www.snik.eu/ontology/meta/Top".into()));
+        let labels: Vec<_> = dataset.quads_matching([&top], [rdfs::label], Any, Any).map(Result::unwrap).collect();
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels.iter().filter(|(_, g)| g.is_none()).count(), 1);
+        let label = labels[0].0[2].clone();
+        assert!(dataset.contains(&top, rdfs::label, &label, Some(&b))?);
+        assert!(!dataset.contains(&top, rdfs::label, &top, None::<&HdtTerm>)?);
+        Ok(())
+    }
+}
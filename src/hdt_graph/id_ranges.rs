@@ -0,0 +1,141 @@
+//! Resolution of sets of terms to the dictionary IDs they contain, without scanning the dictionary.
+use super::{TermSet, term_string};
+use crate::four_sect_dict::IdKind;
+use crate::hdt::Hdt;
+use crate::triples::{Id, TripleId};
+use qwt::RankUnsigned;
+use sophia::api::term::Term;
+use std::ops::Range;
+
+/// Sorted, disjoint ranges of IDs of a dictionary section.
+/// Finite sets of terms result in one range per term, all literals in a single range of the object section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct IdRanges(Vec<Range<Id>>);
+
+impl IdRanges {
+    /// The IDs of the terms in the set, found by dictionary lookups and by binary search for literals.
+    /// Returns `None` for [`TermSet::Any`].
+    pub(super) fn resolve<T: Term>(hdt: &Hdt, kind: IdKind, set: TermSet<'_, T>) -> Option<Self> {
+        match set {
+            TermSet::Any => None,
+            TermSet::Terms(terms) => {
+                let mut ids: Vec<Id> = terms
+                    .iter()
+                    .filter_map(|t| super::HdtTerm::try_from(t.borrow_term()))
+                    .map(|t| hdt.dict.string_to_id(&term_string(&t), kind))
+                    .filter(|&id| id != 0)
+                    .collect();
+                ids.sort_unstable();
+                ids.dedup();
+                Some(Self(ids.into_iter().map(|id| id..id + 1).collect()))
+            }
+            TermSet::Literals => match kind {
+                IdKind::Object => {
+                    Some(Self(vec![hdt.dict.literal_ids()].into_iter().filter(|r| !r.is_empty()).collect()))
+                }
+                // literals are neither subjects nor predicates
+                IdKind::Subject | IdKind::Predicate => Some(Self(Vec::new())),
+            },
+        }
+    }
+
+    /// Estimated number of triples that the indexed lookups of all IDs at the given position of the pattern iterate through.
+    /// Computed per range for subjects and objects, as their triples are contiguous in the respective index.
+    pub(super) fn scan_size(&self, hdt: &Hdt, pattern: TripleId, i: usize) -> usize {
+        let t = &hdt.triples;
+        self.0
+            .iter()
+            .map(|r| match (i, pattern) {
+                (0, _) => t.adjlist_z.find(t.last_y(r.end - 2) + 1) - t.adjlist_z.find(t.find_y(r.start - 1)),
+                (2, [0, _, _]) => t.op_index().last(r.end - 1) + 1 - t.op_index().find(r.start),
+                // an object under a constant subject is looked up by scanning the triples of the subject
+                (2, _) => scan_size(hdt, pattern) * r.len(),
+                _ => r
+                    .clone()
+                    .map(|id| {
+                        let mut p = pattern;
+                        p[i] = id;
+                        scan_size(hdt, p)
+                    })
+                    .sum(),
+            })
+            .sum()
+    }
+
+    /// Whether there are no IDs.
+    pub(super) const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The ID if there is exactly one.
+    pub(super) fn single(&self) -> Option<Id> {
+        match self.0.as_slice() {
+            [r] if r.len() == 1 => Some(r.start),
+            _ => None,
+        }
+    }
+
+    /// Whether the given ID is in one of the ranges.
+    pub(super) fn contains(&self, id: Id) -> bool {
+        let i = self.0.partition_point(|r| r.end <= id);
+        self.0.get(i).is_some_and(|r| r.start <= id)
+    }
+
+    /// All IDs in ascending order.
+    pub(super) fn into_ids(self) -> impl Iterator<Item = Id> {
+        self.0.into_iter().flatten()
+    }
+}
+
+/// Estimated number of triples that [`Hdt::triple_ids_with_id_pattern`] iterates through for the given pattern.
+/// Exact for single constants except for predicates, where the number of subject-predicate pairs is returned.
+pub(super) fn scan_size(hdt: &Hdt, pattern: TripleId) -> usize {
+    let t = &hdt.triples;
+    let [s, p, o] = pattern;
+    match (s, p, o) {
+        (1.., _, _) => t.adjlist_z.find(t.last_y(s - 1) + 1) - t.adjlist_z.find(t.find_y(s - 1)),
+        (0, _, 1..) => t.op_index().last(o) + 1 - t.op_index().find(o),
+        (0, 1.., 0) => t.wavelet_y.rank(p as usize, t.wavelet_y.len()).unwrap(),
+        (0, 0, 0) => t.adjlist_z.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdt_graph::HdtTerm;
+    use crate::tests::init;
+    use sophia::api::term::IriRef;
+
+    #[test]
+    fn resolve() -> color_eyre::Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        assert_eq!(IdRanges::resolve::<HdtTerm>(&hdt, IdKind::Object, TermSet::Any), None);
+        let literals = IdRanges::resolve::<HdtTerm>(&hdt, IdKind::Object, TermSet::Literals).unwrap();
+        assert_eq!(literals, IdRanges(vec![hdt.dict.literal_ids()]));
+        assert!(!literals.contains(1));
+        assert!(IdRanges::resolve::<HdtTerm>(&hdt, IdKind::Subject, TermSet::Literals).unwrap().is_empty());
+        let top = "http://www.snik.eu/ontology/meta/Top";
+        let meta = "http://www.snik.eu/ontology/meta";
+        let iris = [top, meta, "http://example.org/doesnotexist"].map(IriRef::new_unchecked);
+        let subjects = IdRanges::resolve(&hdt, IdKind::Subject, TermSet::Terms(&iris)).unwrap();
+        let ids: Vec<_> = subjects.clone().into_ids().collect();
+        assert!(ids.iter().all(|id| subjects.contains(*id)));
+        let mut expected = [top, meta].map(|s| hdt.dict.string_to_id(s, IdKind::Subject));
+        expected.sort_unstable();
+        assert_eq!(ids, expected);
+        assert_eq!(scan_size(&hdt, [0, 0, 0]), 328);
+        assert_eq!(scan_size(&hdt, [ids[0], 0, 0]), hdt.triple_ids_with_id_pattern([ids[0], 0, 0]).count());
+        let o = literals.clone().into_ids().next().unwrap();
+        assert_eq!(scan_size(&hdt, [0, 0, o]), hdt.triple_ids_with_id_pattern([0, 0, o]).count());
+        let literal_triples: usize =
+            literals.clone().into_ids().map(|o| hdt.triple_ids_with_id_pattern([0, 0, o]).count()).sum();
+        assert_eq!(literals.scan_size(&hdt, [0, 0, 0], 2), literal_triples);
+        assert_eq!(
+            subjects.scan_size(&hdt, [0, 0, 0], 0),
+            ids.iter().map(|&s| hdt.triple_ids_with_id_pattern([s, 0, 0]).count()).sum::<usize>()
+        );
+        Ok(())
+    }
+}
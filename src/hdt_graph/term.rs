@@ -1,16 +1,21 @@
 //! I define [`HdtTerm`], an implementation of [`sophia::api::term::Term`].
+use super::{auto_term, str_term};
+use crate::four_sect_dict::IdKind;
+use crate::hdt::Hdt;
+use crate::triples::Id;
 use sophia::api::MownStr;
 use sophia::api::ns::{rdf, xsd};
-use sophia::api::term::{BaseDirection, BnodeId, LanguageTag, Term, TermKind};
+use sophia::api::term::{BaseDirection, BnodeId, LanguageTag, SimpleTerm, Term, TermKind};
 use sophia::iri::IriRef;
+use std::fmt;
 use std::sync::{Arc, LazyLock};
 
 pub static XSD_STRING: LazyLock<IriRef<Arc<str>>> =
     LazyLock::new(|| xsd::string.iri().unwrap().map_unchecked(|m| Arc::from(m.as_ref())));
 
 /// An implementation of [`sophia::api::term::Term`] for HDT.
-#[derive(Clone, Debug)]
-pub enum HdtTerm {
+#[derive(Clone)]
+pub enum HdtTerm<'a> {
     /// This HdtTerm is an IRI
     Iri(IriRef<Arc<str>>),
     /// This HdtTerm is a blank node
@@ -19,12 +24,15 @@ pub enum HdtTerm {
     LiteralDatatype(Arc<str>, IriRef<Arc<str>>),
     /// This HdtTerm is a language string literal
     LiteralLanguage(Arc<str>, LanguageTag<Arc<str>>),
+    /// This HdtTerm is the dictionary entry with the given ID and kind,
+    /// which is only decoded when its kind or its contents are accessed.
+    Id(&'a Hdt, Id, IdKind),
 }
 
-impl HdtTerm {
+impl HdtTerm<'_> {
     /// Convert t into an HdtTerm if it is a supported kind of term.
     #[allow(clippy::needless_pass_by_value)]
-    pub fn try_from<T: Term>(t: T) -> Option<HdtTerm> {
+    pub fn try_from<T: Term>(t: T) -> Option<Self> {
         match t.kind() {
             TermKind::Iri => t.iri().map(|iri| HdtTerm::Iri(iri.map_unchecked(mown2arc))),
             TermKind::BlankNode => t.bnode_id().map(|bnid| HdtTerm::BlankNode(bnid.map_unchecked(mown2arc))),
@@ -41,9 +49,47 @@ impl HdtTerm {
             _ => None,
         }
     }
+
+    /// Decodes an ID backed term, so that it does not borrow the HDT anymore.
+    pub fn into_owned(self) -> HdtTerm<'static> {
+        match self {
+            HdtTerm::Iri(iri) => HdtTerm::Iri(iri),
+            HdtTerm::BlankNode(bnid) => HdtTerm::BlankNode(bnid),
+            HdtTerm::LiteralDatatype(lex, dt) => HdtTerm::LiteralDatatype(lex, dt),
+            HdtTerm::LiteralLanguage(lex, tag) => HdtTerm::LiteralLanguage(lex, tag),
+            HdtTerm::Id(hdt, id, kind) => hdt.dict.with_str(id, kind, |s| auto_term(s).unwrap()).unwrap(),
+        }
+    }
+
+    /// The kind of an ID backed term, found without decoding for predicates and literals,
+    /// which form a contiguous range of object IDs, and else by checking the decoded string for a blank node prefix.
+    fn id_kind(hdt: &Hdt, id: Id, kind: IdKind) -> TermKind {
+        match kind {
+            IdKind::Predicate => TermKind::Iri,
+            IdKind::Object if hdt.dict.literal_ids().contains(&id) => TermKind::Literal,
+            _ if hdt.dict.with_str(id, kind, |s| s.starts_with("_:")).unwrap() => TermKind::BlankNode,
+            _ => TermKind::Iri,
+        }
+    }
+
+    /// Applies f to the decoded term of an ID backed term.
+    fn with_simple<R>(hdt: &Hdt, id: Id, kind: IdKind, f: impl FnOnce(SimpleTerm<'_>) -> R) -> R {
+        hdt.dict.with_str(id, kind, |s| f(str_term(s).unwrap())).unwrap()
+    }
+}
+
+/// Whether the given IDs of the same HDT stand for the same term.
+/// Subject and object IDs are only equal in the shared section, predicate IDs cannot be compared with them.
+const fn id_eq(hdt: &Hdt, (a, a_kind): (Id, IdKind), (b, b_kind): (Id, IdKind)) -> Option<bool> {
+    use IdKind::{Object, Predicate, Subject};
+    match (a_kind, b_kind) {
+        (Subject, Subject) | (Predicate, Predicate) | (Object, Object) => Some(a == b),
+        (Subject, Object) | (Object, Subject) => Some(a == b && a as usize <= hdt.dict.shared.num_strings()),
+        _ => None,
+    }
 }
 
-impl Term for HdtTerm {
+impl Term for HdtTerm<'_> {
     type BorrowTerm<'x>
         = &'x Self
     where
@@ -54,6 +100,7 @@ impl Term for HdtTerm {
             HdtTerm::Iri(_) => TermKind::Iri,
             HdtTerm::BlankNode(_) => TermKind::BlankNode,
             HdtTerm::LiteralDatatype(..) | HdtTerm::LiteralLanguage(..) => TermKind::Literal,
+            HdtTerm::Id(hdt, id, kind) => Self::id_kind(hdt, *id, *kind),
         }
     }
 
@@ -64,6 +111,9 @@ impl Term for HdtTerm {
     fn iri(&self) -> Option<sophia::api::term::IriRef<mownstr::MownStr<'_>>> {
         match self {
             HdtTerm::Iri(iri) => Some(iri.as_ref().map_unchecked(MownStr::from_ref)),
+            HdtTerm::Id(hdt, id, kind) if Self::id_kind(hdt, *id, *kind) == TermKind::Iri => {
+                hdt.dict.with_str(*id, *kind, |s| IriRef::new_unchecked(MownStr::from(s.to_owned()))).ok()
+            }
             _ => None,
         }
     }
@@ -71,6 +121,9 @@ impl Term for HdtTerm {
     fn bnode_id(&self) -> Option<BnodeId<mownstr::MownStr<'_>>> {
         match self {
             HdtTerm::BlankNode(bnid) => Some(bnid.as_ref().map_unchecked(MownStr::from_ref)),
+            HdtTerm::Id(hdt, id, kind) if Self::id_kind(hdt, *id, *kind) == TermKind::BlankNode => {
+                hdt.dict.with_str(*id, *kind, |s| BnodeId::new_unchecked(MownStr::from(s[2..].to_owned()))).ok()
+            }
             _ => None,
         }
     }
@@ -78,6 +131,9 @@ impl Term for HdtTerm {
     fn lexical_form(&self) -> Option<mownstr::MownStr<'_>> {
         match self {
             HdtTerm::LiteralDatatype(lex, _) | HdtTerm::LiteralLanguage(lex, _) => Some(lex.as_ref().into()),
+            HdtTerm::Id(hdt, id, IdKind::Object) if hdt.dict.literal_ids().contains(id) => {
+                Self::with_simple(hdt, *id, IdKind::Object, |t| t.lexical_form().map(own))
+            }
             _ => None,
         }
     }
@@ -86,13 +142,19 @@ impl Term for HdtTerm {
         match self {
             HdtTerm::LiteralDatatype(_, datatype) => Some(datatype.as_ref().map_unchecked(MownStr::from_ref)),
             HdtTerm::LiteralLanguage(..) => rdf::langString.iri(),
-            _ => None,
+            HdtTerm::Id(hdt, id, IdKind::Object) if hdt.dict.literal_ids().contains(id) => {
+                Self::with_simple(hdt, *id, IdKind::Object, |t| t.datatype().map(|dt| dt.map_unchecked(own)))
+            }
+            HdtTerm::Iri(_) | HdtTerm::BlankNode(_) | HdtTerm::Id(..) => None,
         }
     }
 
     fn language_tag(&self) -> Option<LanguageTag<mownstr::MownStr<'_>>> {
         match self {
             HdtTerm::LiteralLanguage(_, tag) => Some(tag.as_ref().map_unchecked(MownStr::from_ref)),
+            HdtTerm::Id(hdt, id, IdKind::Object) if hdt.dict.literal_ids().contains(id) => {
+                Self::with_simple(hdt, *id, IdKind::Object, |t| t.language_tag().map(|tag| tag.map_unchecked(own)))
+            }
             _ => None,
         }
     }
@@ -103,14 +165,43 @@ impl Term for HdtTerm {
     }
 }
 
-impl PartialEq for HdtTerm {
+impl PartialEq for HdtTerm<'_> {
+    /// Compares ID backed terms of the same HDT by their IDs, all other terms by their contents.
     fn eq(&self, other: &Self) -> bool {
+        if let (HdtTerm::Id(hdt, a, a_kind), HdtTerm::Id(other_hdt, b, b_kind)) = (self, other) {
+            if std::ptr::eq(*hdt, *other_hdt) {
+                if let Some(eq) = id_eq(hdt, (*a, *a_kind), (*b, *b_kind)) {
+                    return eq;
+                }
+            }
+        }
         Term::eq(self, other)
     }
 }
 
-impl Eq for HdtTerm {}
+impl Eq for HdtTerm<'_> {}
+
+impl fmt::Debug for HdtTerm<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HdtTerm::Iri(iri) => f.debug_tuple("Iri").field(iri).finish(),
+            HdtTerm::BlankNode(bnid) => f.debug_tuple("BlankNode").field(bnid).finish(),
+            HdtTerm::LiteralDatatype(lex, dt) => f.debug_tuple("LiteralDatatype").field(lex).field(dt).finish(),
+            HdtTerm::LiteralLanguage(lex, tag) => f.debug_tuple("LiteralLanguage").field(lex).field(tag).finish(),
+            // the HDT itself is too large to print, show the decoded string instead
+            HdtTerm::Id(hdt, id, kind) => {
+                let s = hdt.dict.id_to_string(*id, *kind).map_err(|_| fmt::Error)?;
+                f.debug_tuple("Id").field(id).field(kind).field(&s).finish()
+            }
+        }
+    }
+}
 
 fn mown2arc(m: MownStr) -> Arc<str> {
     Box::<str>::from(m).into()
 }
+
+/// Detaches a string from the buffer it was decoded into.
+fn own(m: MownStr) -> MownStr<'static> {
+    MownStr::from(String::from(m))
+}
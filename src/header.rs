@@ -83,6 +83,11 @@ impl Header {
         Ok(Header { format: header_ci.format, length, body })
     }
 
+    /// Size in bytes on the heap, without the overhead of the set of triples.
+    pub fn size_in_bytes(&self) -> usize {
+        self.format.capacity() + self.body.iter().map(Triple::size_in_bytes).sum::<usize>()
+    }
+
     pub fn write(&self, write: &mut impl Write) -> Result<()> {
         let mut body = Vec::<u8>::new();
         for triple in &self.body {
@@ -106,6 +111,7 @@ mod tests {
         assert_eq!(header.format, "ntriples");
         assert_eq!(header.length, 1891);
         assert_eq!(header.body.len(), 22);
+        assert!(header.size_in_bytes() > header.length);
         Ok(())
     }
 }
@@ -89,10 +89,23 @@ fn query(hdt: Hdt)
 #![allow(clippy::unsafe_derive_deserialize)]
 // multiple versions of syn crate in transitive dependencies
 #![allow(clippy::multiple_crate_versions)]
+/// Cache of decoded dictionary blocks.
+pub mod block_cache;
+#[cfg(feature = "cache")]
+/// Versioned cache files of the triples section.
+pub mod cache;
 /// Types for storing and reading data.
 pub mod containers;
+/// Resource descriptions and subgraph extraction.
+pub mod describe;
 /// Types for representing dictionaries.
 pub mod dict_sect_pfc;
+#[cfg(feature = "export")]
+/// Export to other RDF serialization formats.
+pub mod export;
+#[cfg(feature = "ffi")]
+/// C API.
+pub mod ffi;
 /// Types for representing a four section dictionary
 pub mod four_sect_dict;
 /// Types for representing triple sections.
@@ -104,14 +117,38 @@ pub use sophia;
 pub mod hdt_graph;
 /// Types for representing the header.
 pub mod header;
+/// Incremental loading from chunks of data.
+pub mod loader;
+/// Owned query iterators that can be moved between threads.
+pub mod owned;
+/// Parallel iteration over triples with rayon.
+pub mod par;
+/// Loading selected sections of HDT files.
+pub mod partial;
+/// Prefixes derived from the most common namespaces of the dictionary.
+pub mod prefixes;
+/// Progress reporting and cancellation.
+pub mod progress;
+#[cfg(feature = "python")]
+/// Python bindings.
+pub mod python;
+/// RDFS entailment.
+pub mod rdfs;
 #[cfg(feature = "sparql")]
 /// SPARQL queries.
 pub mod sparql;
+/// Dataset statistics.
+pub mod statistics;
 /// Types for representing and querying triples.
 pub mod triples;
+/// Streaming Turtle serialization.
+pub mod turtle;
 /// Constants for triple terms
 pub mod vocab;
+/// VoID and SPARQL service descriptions.
+pub mod void;
 #[cfg(target_arch = "wasm32")]
+/// WebAssembly bindings for JavaScript.
 pub mod wasm;
 
 pub use crate::hdt::Hdt;
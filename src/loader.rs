@@ -0,0 +1,278 @@
+//! Building an [`Hdt`] from chunks of data as they arrive, see [`HdtLoader`].
+use crate::Hdt;
+use crate::containers::{Bitmap, ControlInfo, Sequence, Verify};
+use crate::four_sect_dict::{FourSectDict, SectKind, UnvalidatedDictSect, UnvalidatedFourSectDict};
+use crate::hdt::{Error, ReadOptions, Result};
+use crate::header::Header;
+use crate::triples::{self, Level, Order, TriplesBitmap};
+use std::io::{self, BufRead, Read};
+
+/// Parsing is only retried after an incomplete attempt once the buffer has grown by this many bytes or doubled,
+/// so that small chunks do not cause the same section to be parsed over and over.
+const MIN_RETRY: usize = 64 * 1024;
+
+/// The containers of an HDT file in the order in which they are stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    #[default]
+    Header,
+    DictInfo,
+    Dict(SectKind),
+    TriplesInfo,
+    Bitmap(Level),
+    Sequence(Level),
+    Done,
+}
+
+impl Step {
+    const fn next(self) -> Self {
+        use SectKind::*;
+        match self {
+            Step::Header => Step::DictInfo,
+            Step::DictInfo => Step::Dict(Shared),
+            Step::Dict(Shared) => Step::Dict(Subject),
+            Step::Dict(Subject) => Step::Dict(Predicate),
+            Step::Dict(Predicate) => Step::Dict(Object),
+            Step::Dict(Object) => Step::TriplesInfo,
+            Step::TriplesInfo => Step::Bitmap(Level::Y),
+            Step::Bitmap(Level::Y) => Step::Bitmap(Level::Z),
+            Step::Bitmap(Level::Z) => Step::Sequence(Level::Y),
+            Step::Sequence(Level::Y) => Step::Sequence(Level::Z),
+            Step::Sequence(Level::Z) | Step::Done => Step::Done,
+        }
+    }
+}
+
+/// Reader over the received data that remembers whether the parser wanted to read past its end.
+struct Probe<'a> {
+    data: &'a [u8],
+    pos: usize,
+    starved: bool,
+}
+
+impl Read for Probe<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for Probe<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.starved |= self.pos == self.data.len();
+        Ok(&self.data[self.pos..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos += amount;
+    }
+}
+
+/// Builds an [`Hdt`] from chunks of an HDT file, for example from a network stream.
+/// Each container of the file is parsed as soon as all of its bytes have been received and its bytes are then released,
+/// so in contrast to [`Hdt::read`] over an in-memory copy of the file, the whole file is never held in memory.
+/// The received but not yet parsed data is less than twice the size of the largest container,
+/// which is usually the sequence of objects in the triples section, plus 64 KiB and the size of the last chunk.
+/// The buffer holding it grows by doubling, so its allocation can be up to twice as large.
+/// # Example
+/// ```
+/// use hdt::loader::HdtLoader;
+/// let data = std::fs::read("tests/resources/snikmeta.hdt").unwrap();
+/// let mut loader = HdtLoader::new();
+/// for chunk in data.chunks(1000) {
+///     loader.push(chunk).unwrap();
+/// }
+/// let hdt = loader.finish().unwrap();
+/// assert_eq!(hdt.triples.len(), 328);
+/// ```
+#[derive(Default)]
+pub struct HdtLoader {
+    verify: Verify,
+    /// received data that has not been parsed yet
+    buffer: Vec<u8>,
+    consumed: usize,
+    /// buffer length before which parsing of the current step is not retried
+    retry_at: usize,
+    step: Step,
+    header: Option<Header>,
+    dict: Vec<UnvalidatedDictSect>,
+    order: Order,
+    bitmaps: Vec<Bitmap>,
+    sequences: Vec<(Sequence, Option<u32>)>,
+}
+
+impl std::fmt::Debug for HdtLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HdtLoader")
+            .field("verify", &self.verify)
+            .field("buffered", &self.buffered())
+            .field("consumed", &self.consumed)
+            .field("step", &self.step)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HdtLoader {
+    /// Creates a loader that verifies all checksums.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a loader that verifies the given checksums, see [`ReadOptions::verify`].
+    pub fn with_verify(verify: Verify) -> Self {
+        Self { verify, ..Self::default() }
+    }
+
+    /// Number of bytes that have been received but not parsed yet.
+    pub const fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of bytes that have been parsed.
+    pub const fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Whether all sections have been parsed, in which case further data is ignored.
+    pub fn is_complete(&self) -> bool {
+        self.step == Step::Done
+    }
+
+    /// Appends the next chunk of the file and parses all containers that are complete now.
+    /// Returns an error if the data received so far is invalid, in which case the loader should be discarded.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<()> {
+        self.push_with(chunk.len(), |buf| buf.copy_from_slice(chunk))
+    }
+
+    /// Like [`HdtLoader::push`] but lets `fill` write the chunk of length `len` directly into the buffer,
+    /// which avoids an intermediate copy when the data comes from outside of Rust memory.
+    pub fn push_with(&mut self, len: usize, fill: impl FnOnce(&mut [u8])) -> Result<()> {
+        if self.is_complete() {
+            return Ok(());
+        }
+        let start = self.buffer.len();
+        self.buffer.resize(start + len, 0);
+        fill(&mut self.buffer[start..]);
+        if self.buffer.len() >= self.retry_at {
+            self.parse(false)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the remaining data and builds the HDT after the last chunk has been pushed.
+    /// Returns an error if the data ends before the triples section is complete.
+    pub fn finish(mut self) -> Result<Hdt> {
+        self.parse(true)?;
+        let options = ReadOptions { verify: self.verify, ..Default::default() };
+        let header = self.header.take().expect("header is parsed first");
+        let dict: [UnvalidatedDictSect; 4] = self.dict.try_into().expect("all dictionary sections are parsed");
+        let dict = UnvalidatedFourSectDict::new(dict).validate()?;
+        let [bitmap_y, bitmap_z]: [Bitmap; 2] = self.bitmaps.try_into().expect("both bitmaps are parsed");
+        let [sequence_y, sequence_z]: [_; 2] = self.sequences.try_into().expect("both sequences are parsed");
+        let triples = TriplesBitmap::from_parts(self.order, bitmap_y, bitmap_z, sequence_y, sequence_z, options)
+            .map_err(Error::Triples)?;
+        triples.build_op_index_with(None)?;
+        Ok(Hdt { header, dict, triples })
+    }
+
+    /// Parses as many steps as possible from the buffer.
+    /// Unless at the end of the data, running out of data is not an error but the step is retried on a later push.
+    fn parse(&mut self, eof: bool) -> Result<()> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let mut probe = Probe { data: &buffer, pos: 0, starved: false };
+        let result = loop {
+            if self.is_complete() {
+                break Ok(());
+            }
+            let start = probe.pos;
+            probe.starved = false;
+            match self.parse_step(&mut probe) {
+                Ok(()) => self.step = self.step.next(),
+                Err(_) if probe.starved && !eof => {
+                    probe.pos = start;
+                    let remaining = buffer.len() - start;
+                    self.retry_at = remaining + remaining.max(MIN_RETRY);
+                    break Ok(());
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        let pos = probe.pos;
+        self.consumed += pos;
+        if !self.is_complete() {
+            buffer.drain(..pos);
+            self.buffer = buffer;
+        }
+        result
+    }
+
+    fn parse_step(&mut self, reader: &mut Probe) -> Result<()> {
+        let verify = self.verify;
+        match self.step {
+            Step::Header => self.header = Some(Hdt::read_header(reader)?),
+            Step::DictInfo => FourSectDict::read_control_info(reader)?,
+            Step::Dict(sect_kind) => self.dict.push(FourSectDict::read_sect(reader, verify, sect_kind)?),
+            Step::TriplesInfo => {
+                let triples_ci = ControlInfo::read(reader)?;
+                TriplesBitmap::check_format(&triples_ci)?;
+                self.order = TriplesBitmap::read_order(&triples_ci)?;
+            }
+            Step::Bitmap(level) => {
+                let bitmap = Bitmap::read_with(reader, verify).map_err(|e| triples::Error::Bitmap(level, e))?;
+                self.bitmaps.push(bitmap);
+            }
+            Step::Sequence(level) => {
+                let sequence =
+                    Sequence::read_unverified(reader, verify).map_err(|e| triples::Error::Sequence(level, e))?;
+                self.sequences.push(sequence);
+            }
+            Step::Done => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+
+    #[test]
+    fn chunks() -> color_eyre::Result<()> {
+        init();
+        let data = std::fs::read("tests/resources/snikmeta.hdt")?;
+        let expected = crate::hdt::tests::snikmeta()?;
+        for size in [1, 7, 4096, data.len()] {
+            let mut loader = HdtLoader::new();
+            for chunk in data.chunks(size) {
+                loader.push(chunk)?;
+                assert!(loader.buffered() <= 2 * MIN_RETRY + size);
+            }
+            // parsing may be deferred until the end of small files
+            assert_eq!(loader.consumed() + loader.buffered(), data.len());
+            assert_eq!(loader.is_complete(), size == data.len());
+            let hdt = loader.finish()?;
+            assert_eq!(
+                hdt.triples.into_iter().collect::<Vec<_>>(),
+                expected.triples.into_iter().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                hdt.dict.id_to_string(1, crate::IdKind::Object)?,
+                expected.dict.id_to_string(1, crate::IdKind::Object)?
+            );
+            assert_eq!(hdt.header.body, expected.header.body);
+        }
+        // truncated data
+        let mut loader = HdtLoader::new();
+        loader.push(&data[..data.len() - 10])?;
+        assert!(!loader.is_complete());
+        assert!(loader.finish().is_err());
+        // invalid data is reported before the end
+        let mut loader = HdtLoader::new();
+        assert!(loader.push(&[b'x'; 100]).is_err());
+        Ok(())
+    }
+}
@@ -1,26 +1,29 @@
 /// *This module is available only if HDT is built with the `"cli"` feature.*
 /// Under development, parameters may change.
 use bytesize::ByteSize;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::config::HookBuilder;
-use color_eyre::eyre::{Report, WrapErr};
+use color_eyre::eyre::{Report, WrapErr, bail};
 //use log::info;
 use fs_err::{File, metadata};
 use hdt::Hdt;
 use hdt::containers::ControlInfo;
+use hdt::export::{DEFAULT_PREFIXES, ExportOptions, JsonLdProfileSet, RdfFormat};
 use hdt::header::Header;
+use hdt::sparql;
+use hdt::turtle::TurtleOptions;
 use sophia::api::graph::Graph;
-use sophia::api::prelude::{TripleSerializer, TripleSource};
+use sophia::api::prelude::TripleSource;
 //use sophia::api::prelude::Stringifier;
 use sophia::inmem::graph::LightGraph;
 use sophia::turtle::parser::{nt, turtle};
-use sophia::turtle::serializer::nt::NTriplesSerializer;
-use sophia::turtle::serializer::turtle::{TurtleConfig, TurtleSerializer};
+use sparesults::{QueryResultsFormat, QueryResultsSerializer};
+use spareval::{QueryResults, QuerySolutionIter};
+use spargebra::term::Variable;
 use std::ffi::OsStr;
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, BufWriter, Write, stdin, stdout};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
-//use std::io::{BufReader, stdin};
 
 /*enum Format {
     NTriples,
@@ -39,29 +42,367 @@ struct Args {
 
     // /// verbose output
     //verbose: bool,
-    // disable std reading for now because of usability downside for new users when started with no parameter // the HDT file to load from, if not given it is read from stdin
 }
 
 #[derive(Subcommand)]
 enum Command {
+    /// print the header of an HDT file or count the triples of an RDF file
     Info {
+        /// the HDT, N-Triples or Turtle file to read, "-" for standard input
         input_path: PathBuf,
+        /// input format, required for standard input, determined by the extension otherwise
+        #[arg(long, value_enum)]
+        from: Option<InputFormat>,
     },
+    /// convert between HDT and N-Triples or export HDT to other RDF formats
     Convert {
-        // #[arg(short, long)]
-        // /// export as RDF Turtle, default is N-Triples
-        // turtle: bool,
-        /*
+        /// the HDT or N-Triples file to load from, "-" for standard input
+        input_path: PathBuf,
+        /// the file to create, "-" for standard output
+        output_path: PathBuf,
+        /// input format, required for standard input, determined by the extension otherwise
+        #[arg(long, value_enum)]
+        from: Option<InputFormat>,
+        /// output format, required for standard output, determined by the extension otherwise
+        #[arg(short, long, value_enum, visible_alias = "format")]
+        to: Option<ConvertFormat>,
+        /// IRI of the named graph for N-Quads, TriG and JSON-LD output, the default graph if not given
         #[arg(short, long)]
-        /// Count triples only, do not print them
-        count: bool,
-        */
-        /// the HDT file to load from
+        graph: Option<String>,
+        /// number of the most common namespaces that are declared as prefixes in Turtle, TriG and RDF/XML output
+        #[arg(short, long, default_value_t = DEFAULT_PREFIXES)]
+        prefixes: usize,
+        /// write blank nodes that occur only once as an object in square brackets in Turtle output
+        #[arg(long)]
+        inline_blank_nodes: bool,
+    },
+    /// extract the Concise Bounded Descriptions of the seeds and of the resources within a number of hops into a new HDT file
+    Extract {
+        /// the HDT file to extract from, "-" for standard input
         input_path: PathBuf,
-        // /// the RDF file to create, if not given it is written to stdout
-        // rdf_output_path: Option<String>,
+        /// the HDT file to create, "-" for standard output
         output_path: PathBuf,
+        /// IRI of a resource to start from, can be given multiple times
+        #[arg(short, long = "seed", required = true)]
+        seeds: Vec<String>,
+        /// IRI of a predicate to follow, can be given multiple times, all predicates are followed if not given
+        #[arg(short, long = "predicate")]
+        predicates: Vec<String>,
+        /// maximum number of hops from the seeds
+        #[arg(short = 'n', long, default_value_t = 1)]
+        max_hops: usize,
+        /// IRI of the extracted dataset in the header, the absolute output path as a file IRI if not given, required for standard output
+        #[arg(short, long)]
+        base: Option<String>,
+    },
+    /// run a SPARQL query over one or more HDT files
+    Sparql {
+        /// the HDT files to query ("-" for standard input), which are combined into a union default graph, followed by the query unless --query-file is given
+        #[arg(required = true, num_args = 1..)]
+        args: Vec<String>,
+        /// read the query from a file instead of the last argument
+        #[arg(short, long)]
+        query_file: Option<PathBuf>,
+        /// output format, table for SELECT and ASK and N-Triples for CONSTRUCT and DESCRIBE queries if not given
+        #[arg(short, long, value_enum)]
+        format: Option<ResultFormat>,
+        /// write the results to a file instead of standard output ("-"), CONSTRUCT and DESCRIBE results are converted to HDT if it ends in .hdt
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
+    /// print statistics such as term counts, component sizes and the most frequent predicates and classes
+    Stats {
+        /// the HDT file to profile, "-" for standard input
+        input_path: PathBuf,
+        /// number of predicates and classes to list
+        #[arg(short, long, default_value_t = 10)]
+        top: usize,
+        /// output JSON instead of human readable text
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// print a VoID description of the dataset as N-Triples or add it to the header of a new HDT file
+    Void {
+        /// the HDT file to describe, "-" for standard input
+        input_path: PathBuf,
+        /// IRI of the void:Dataset
+        #[arg(short, long)]
+        base: String,
+        /// also describe the SPARQL endpoint with the given IRI using the SPARQL 1.1 Service Description vocabulary
+        #[arg(short, long)]
+        endpoint: Option<String>,
+        /// write a copy of the input HDT file with the description added to its header instead of printing it, "-" for standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Input format of the info and convert commands.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// HDT
+    Hdt,
+    /// N-Triples
+    Nt,
+    /// RDF Turtle, only for the info command
+    Ttl,
+}
+
+impl InputFormat {
+    /// The given format or the one of the extension of the input file.
+    fn of(path: &Path, from: Option<Self>) -> Result<Self, Report> {
+        if let Some(from) = from {
+            return Ok(from);
+        }
+        if is_stdio(path) {
+            bail!("the format of standard input must be given with --from");
+        }
+        match path.extension().and_then(OsStr::to_str) {
+            Some("hdt") => Ok(InputFormat::Hdt),
+            Some("nt") => Ok(InputFormat::Nt),
+            Some("ttl") => Ok(InputFormat::Ttl),
+            _ => bail!("RDF format of {path:?} cannot be determined from its extension, use --from"),
+        }
+    }
+}
+
+/// Output format of the convert command.
+#[derive(Clone, Copy, ValueEnum)]
+enum ConvertFormat {
+    /// HDT
+    Hdt,
+    /// N-Triples
+    Nt,
+    /// N-Quads
+    Nq,
+    /// RDF Turtle
+    Ttl,
+    /// TriG
+    Trig,
+    /// RDF/XML
+    Rdfxml,
+    /// JSON-LD
+    Jsonld,
+}
+
+impl ConvertFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(OsStr::to_str)? {
+            "hdt" => Some(ConvertFormat::Hdt),
+            "nt" => Some(ConvertFormat::Nt),
+            "nq" => Some(ConvertFormat::Nq),
+            "ttl" => Some(ConvertFormat::Ttl),
+            "trig" => Some(ConvertFormat::Trig),
+            "rdf" | "owl" | "xml" => Some(ConvertFormat::Rdfxml),
+            "jsonld" | "json" => Some(ConvertFormat::Jsonld),
+            _ => None,
+        }
+    }
+
+    const fn rdf_format(self) -> Option<RdfFormat> {
+        match self {
+            ConvertFormat::Hdt | ConvertFormat::Nt => None,
+            ConvertFormat::Nq => Some(RdfFormat::NQuads),
+            ConvertFormat::Ttl => Some(RdfFormat::Turtle),
+            ConvertFormat::Trig => Some(RdfFormat::TriG),
+            ConvertFormat::Rdfxml => Some(RdfFormat::RdfXml),
+            ConvertFormat::Jsonld => Some(RdfFormat::JsonLd { profile: JsonLdProfileSet::empty() }),
+        }
+    }
+}
+
+/// Output format of the sparql command.
+#[derive(Clone, Copy, ValueEnum)]
+enum ResultFormat {
+    /// human readable table, only for SELECT and ASK queries
+    Table,
+    /// SPARQL Query Results JSON Format, only for SELECT and ASK queries
+    Json,
+    /// SPARQL Query Results XML Format, only for SELECT and ASK queries
+    Xml,
+    /// SPARQL Query Results CSV Format, only for SELECT and ASK queries
+    Csv,
+    /// SPARQL Query Results TSV Format, only for SELECT and ASK queries
+    Tsv,
+    /// N-Triples, only for CONSTRUCT and DESCRIBE queries
+    Nt,
+    /// RDF Turtle, only for CONSTRUCT and DESCRIBE queries
+    Ttl,
+}
+
+impl ResultFormat {
+    const fn results_format(self) -> Option<QueryResultsFormat> {
+        match self {
+            ResultFormat::Json => Some(QueryResultsFormat::Json),
+            ResultFormat::Xml => Some(QueryResultsFormat::Xml),
+            ResultFormat::Csv => Some(QueryResultsFormat::Csv),
+            ResultFormat::Tsv => Some(QueryResultsFormat::Tsv),
+            ResultFormat::Table | ResultFormat::Nt | ResultFormat::Ttl => None,
+        }
+    }
+}
+
+/// Base IRI of datasets converted from standard input.
+const STDIN_BASE: &str = "file:///dev/stdin";
+
+/// Whether the path is "-", which stands for standard input or output.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// The `file:` IRI of the absolute path, used as the default base IRI of new HDT files.
+/// Standard output has no such IRI, so a base IRI has to be given in that case.
+fn file_iri(path: &Path) -> Result<String, Report> {
+    if is_stdio(path) {
+        bail!("a base IRI is required when writing HDT to standard output");
+    }
+    let path = std::path::absolute(path).with_context(|| format!("Error resolving output path {path:?}"))?;
+    let mut iri = String::from("file://");
+    if !path.starts_with("/") {
+        iri.push('/');
+    }
+    for b in path.to_string_lossy().replace('\\', "/").bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                iri.push(b as char)
+            }
+            _ => iri.push_str(&format!("%{b:02X}")),
+        }
+    }
+    Ok(iri)
+}
+
+/// Buffered reader of the file or of standard input for "-".
+fn open_input(path: &Path) -> Result<Box<dyn BufRead>, Report> {
+    if is_stdio(path) {
+        return Ok(Box::new(stdin().lock()));
+    }
+    let file = File::open(path).with_context(|| format!("Error opening input file {path:?}"))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Buffered writer to a new file or to standard output for "-".
+fn create_output(path: &Path) -> Result<Box<dyn Write>, Report> {
+    if is_stdio(path) {
+        return Ok(Box::new(BufWriter::new(stdout().lock())));
+    }
+    let file = File::create(path).with_context(|| format!("Error creating output file {path:?}"))?;
+    Ok(Box::new(BufWriter::new(file)))
+}
+
+fn load_hdt(path: &Path) -> Result<Hdt, Report> {
+    Hdt::read(open_input(path)?).with_context(|| format!("Error loading input HDT from {path:?}"))
+}
+
+/// Prints the solutions of a SELECT query as a table with aligned columns.
+fn write_table(w: &mut impl Write, variables: &[Variable], solutions: QuerySolutionIter) -> Result<(), Report> {
+    let header: Vec<String> = variables.iter().map(ToString::to_string).collect();
+    let mut rows = Vec::new();
+    for solution in solutions {
+        let solution = solution?;
+        rows.push(
+            variables
+                .iter()
+                .map(|v| solution.get(v).map(ToString::to_string).unwrap_or_default())
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells.iter().zip(&widths).map(|(c, w)| format!("{c:<w$}")).collect();
+        format!("| {} |", padded.join(" | "))
+    };
+    writeln!(w, "{}", line(&header))?;
+    writeln!(w, "|{}|", widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>().join("|"))?;
+    for row in &rows {
+        writeln!(w, "{}", line(row))?;
+    }
+    writeln!(w, "{} results", rows.len())?;
+    Ok(())
+}
+
+fn sparql(
+    mut args: Vec<String>, query_file: Option<PathBuf>, format: Option<ResultFormat>, output: Option<PathBuf>,
+) -> Result<(), Report> {
+    let query = match query_file {
+        Some(f) => fs_err::read_to_string(f)?,
+        None if args.len() < 2 => bail!("expected at least one HDT file followed by a SPARQL query"),
+        None => args.pop().unwrap(),
+    };
+    let hdts = args.iter().map(|p| load_hdt(Path::new(p))).collect::<Result<Vec<_>, _>>()?;
+    let results = sparql::query_union(&query, &hdts).wrap_err("Error evaluating SPARQL query")?;
+    if let Some(path) = output.as_ref().filter(|p| p.extension().and_then(OsStr::to_str) == Some("hdt")) {
+        let QueryResults::Graph(triples) = results else {
+            bail!("HDT output is only supported for CONSTRUCT and DESCRIBE queries")
+        };
+        let mut string_triples = Vec::new();
+        for t in triples {
+            let t = t?;
+            string_triples.push([
+                sparql::term_to_hdt_bgp_str(t.subject.into()),
+                t.predicate.into_string(),
+                sparql::term_to_hdt_bgp_str(t.object),
+            ]);
+        }
+        let hdt = Hdt::from_triples(string_triples, &file_iri(path)?)?;
+        let mut writer = create_output(path)?;
+        hdt.write(&mut writer).with_context(|| format!("Error writing HDT file {path:?}"))?;
+        writer.flush()?;
+        return Ok(());
+    }
+    let mut writer = create_output(output.as_deref().unwrap_or(Path::new("-")))?;
+    match results {
+        QueryResults::Solutions(solutions) => {
+            let variables = solutions.variables().to_vec();
+            match format.unwrap_or(ResultFormat::Table) {
+                ResultFormat::Table => write_table(&mut writer, &variables, solutions)?,
+                f => {
+                    let Some(rf) = f.results_format() else {
+                        bail!("RDF output formats are only supported for CONSTRUCT and DESCRIBE queries")
+                    };
+                    let mut serializer = QueryResultsSerializer::from_format(rf)
+                        .serialize_solutions_to_writer(&mut writer, variables)?;
+                    for solution in solutions {
+                        serializer.serialize(solution?.iter())?;
+                    }
+                    serializer.finish()?;
+                }
+            }
+        }
+        QueryResults::Boolean(b) => match format.unwrap_or(ResultFormat::Table) {
+            ResultFormat::Table => writeln!(writer, "{b}")?,
+            f => {
+                let Some(rf) = f.results_format() else {
+                    bail!("RDF output formats are only supported for CONSTRUCT and DESCRIBE queries")
+                };
+                QueryResultsSerializer::from_format(rf).serialize_boolean_to_writer(&mut writer, b)?;
+            }
+        },
+        QueryResults::Graph(triples) => match format.unwrap_or(ResultFormat::Nt) {
+            ResultFormat::Nt => {
+                let mut serializer = oxttl::NTriplesSerializer::new().for_writer(&mut writer);
+                for t in triples {
+                    serializer.serialize_triple(&t?)?;
+                }
+                serializer.finish();
+            }
+            ResultFormat::Ttl => {
+                let mut serializer = oxttl::TurtleSerializer::new().for_writer(&mut writer);
+                for t in triples {
+                    serializer.serialize_triple(&t?)?;
+                }
+                serializer.finish()?;
+            }
+            _ => bail!("CONSTRUCT and DESCRIBE queries only support the nt and ttl output formats"),
+        },
+    }
+    writer.flush()?;
+    Ok(())
 }
 
 fn main() -> Result<(), Report> {
@@ -69,109 +410,129 @@ fn main() -> Result<(), Report> {
     //env_logger::init();
     //env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     let args = Args::parse();
-    /*let hdt = match args.input_path {
-        Some(filename) => {
-            let file =
-                File::open(filename.clone()).wrap_err_with(|| format!("Error opening input file {}", filename))?;
-            Hdt::read(BufReader::new(file))
-                .wrap_err_with(|| format!("Error loading HDT from {}", filename))?
-            //info!("Loaded from file {filename} {hdt:?}");
-        }
-        None => {
-            let reader = BufReader::new(stdin());
-            Hdt::read(reader).wrap_err("Error loading HDT from standard input")?
-            //info!("Loaded from stdin {hdt:?}");
-        }
-    };*/
-    match args.command {
-        Command::Info { input_path } => {
-            let file = File::open(input_path.clone())
-                .with_context(|| format!("Error opening input HDT file {input_path:?}"))?;
-            let mut reader = BufReader::new(file);
-            match input_path.extension().and_then(OsStr::to_str) {
-                Some("nt") => {
+    match run(args.command) {
+        // a closed pipe, such as when piping to `head`, is not an error
+        Err(e) if e.chain().any(|c| c.downcast_ref::<std::io::Error>().is_some_and(is_broken_pipe)) => Ok(()),
+        result => result,
+    }
+}
+
+fn is_broken_pipe(e: &std::io::Error) -> bool {
+    e.kind() == std::io::ErrorKind::BrokenPipe
+}
+
+fn run(command: Command) -> Result<(), Report> {
+    match command {
+        Command::Info { input_path, from } => {
+            let mut reader = open_input(&input_path)?;
+            match InputFormat::of(&input_path, from)? {
+                InputFormat::Nt => {
                     let triples = nt::parse_bufread(reader).collect_triples();
                     let g: LightGraph = triples.unwrap();
                     println!("RDF N-Triples File with ~{} triples", g.triples().size_hint().0);
                 }
-                Some("ttl") => {
+                InputFormat::Ttl => {
                     let triples = turtle::parse_bufread(reader).collect_triples();
                     let g: LightGraph = triples.unwrap();
                     println!("RDF Turtle with ~{} triples", g.triples().size_hint().0);
                 }
-                Some("hdt") => {
+                InputFormat::Hdt => {
                     ControlInfo::read(&mut reader)?;
                     let header = Header::read(&mut reader)?;
                     //println!("{}",ByteSize(hdt.size_in_bytes() as u64).to_string());
                     println!("HDT File: {:#?}", header.body);
                 }
-                Some(x) => {
-                    eprintln!("Unknown RDF extension {x:?}, aborting.");
-                }
-                None => {
-                    println!("File has no extension, RDF format cannot be determined, aborting.");
-                }
             }
         }
-        Command::Convert { input_path, output_path /* turtle*/ } => {
+        Command::Convert { input_path, output_path, from, to, graph, prefixes, inline_blank_nodes } => {
             let t = Instant::now();
-            let file = File::open(input_path.clone())
-                .with_context(|| format!("Error opening input HDT file {input_path:?}"))?;
-            let reader = BufReader::new(file);
-
-            let hdt = match input_path.extension().and_then(OsStr::to_str) {
-                Some("hdt") => {
-                    Hdt::read(reader).with_context(|| format!("Error loading input HDT from {input_path:?}"))?
+            let Some(format) = to.or_else(|| ConvertFormat::from_path(&output_path)) else {
+                if is_stdio(&output_path) {
+                    bail!("the format of standard output must be given with --to");
                 }
-                Some("nt") => Hdt::read_nt(&input_path)
+                bail!("Output format of {output_path:?} cannot be determined from its extension, use --to");
+            };
+            let hdt = match (InputFormat::of(&input_path, from)?, is_stdio(&input_path)) {
+                (InputFormat::Hdt, _) => load_hdt(&input_path)?,
+                // sequential parsing, as standard input cannot be split for parallel parsing
+                (InputFormat::Nt, true) => Hdt::read_nt_from(stdin().lock(), STDIN_BASE)
+                    .wrap_err("Error loading N-Triples from standard input")?,
+                (InputFormat::Nt, false) => Hdt::read_nt(&input_path)
                     .with_context(|| format!("Error loading input N-Triples file from {input_path:?}"))?,
-                _ => {
-                    panic!(
-                        "Input file has unsupported or no extension, RDF format cannot be determined, aborting."
-                    );
-                }
+                (InputFormat::Ttl, _) => bail!("Turtle input is only supported by the info command"),
             };
-            // let count = hdt.triples.len();
-            /*if args.count {
-                println!("Parsing returned {} triples", count);
-                return Ok(());
-            }*/
-            let output_file = File::create(&output_path)?;
-            let mut writer = BufWriter::new(output_file);
-            match output_path.extension().and_then(OsStr::to_str) {
-                Some("ttl") => {
-                    let config = TurtleConfig::new().with_pretty(true);
-                    //.with_own_prefix_map(prefixes().clone());
-                    //TurtleSerializer::new_stringifier_with_config(config)
-                    TurtleSerializer::new_with_config(writer, config)
-                        .serialize_graph(&hdt)
-                        .wrap_err("error serializing graph as RDF Turtle")?;
-                    //.to_string()
-                }
-                Some("nt") => {
-                    // Default: export the complete graph as N-Triples.
-                    //NtSerializer::new_stringifier()
-                    NTriplesSerializer::new(writer)
-                        .serialize_graph(&hdt)
-                        .wrap_err("error serializing graph as N-Triples")?;
-                    //.to_string()
+            let mut writer = create_output(&output_path)?;
+            match (format, format.rdf_format()) {
+                (ConvertFormat::Hdt, _) => hdt.write(&mut writer)?,
+                // serialized in parallel
+                (ConvertFormat::Nt, _) => {
+                    hdt.par_write_nt(&mut writer).wrap_err("error serializing graph as N-Triples")?;
                 }
-                Some("hdt") => {
-                    hdt.write(&mut writer)?;
+                // streamed natively with ";" and "," abbreviations
+                (ConvertFormat::Ttl, _) => hdt
+                    .write_turtle(&mut writer, &TurtleOptions { prefixes, inline_blank_nodes })
+                    .wrap_err("error serializing graph as RDF Turtle")?,
+                (_, Some(rdf_format)) => hdt
+                    .write_rdf(&mut writer, rdf_format, &ExportOptions { graph, prefixes })
+                    .wrap_err_with(|| format!("error serializing graph as {}", rdf_format.name()))?,
+                (_, None) => unreachable!("all other formats are RDF formats"),
+            }
+            writer.flush()?;
+            // keep standard output clean for pipelines
+            if !is_stdio(&output_path) {
+                let source = if is_stdio(&input_path) {
+                    "standard input".to_owned()
+                } else {
+                    format!("{input_path:?} ({})", ByteSize(metadata(&input_path)?.len()))
+                };
+                let out_size = ByteSize(metadata(&output_path)?.len());
+                println!(
+                    "Successfully converted {source} to {output_path:?} ({out_size}) in {:.2}s",
+                    t.elapsed().as_secs_f32()
+                );
+            }
+        }
+        Command::Extract { input_path, output_path, seeds, predicates, max_hops, base } => {
+            let base = base.map_or_else(|| file_iri(&output_path), Ok)?;
+            let hdt = load_hdt(&input_path)?;
+            let seeds: Vec<&str> = seeds.iter().map(String::as_str).collect();
+            let predicates: Vec<&str> = predicates.iter().map(String::as_str).collect();
+            let mut writer = create_output(&output_path)?;
+            let n = hdt
+                .extract_subgraph(&seeds, &predicates, max_hops, &base, &mut writer)
+                .wrap_err("Error extracting subgraph")?;
+            writer.flush()?;
+            if !is_stdio(&output_path) {
+                println!("Extracted {n} triples to {output_path:?}");
+            }
+        }
+        Command::Sparql { args, query_file, format, output } => sparql(args, query_file, format, output)?,
+        Command::Stats { input_path, top, json } => {
+            let stats = load_hdt(&input_path)?.statistics(top);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("{stats}");
+            }
+        }
+        Command::Void { input_path, base, endpoint, output } => {
+            let mut hdt = load_hdt(&input_path)?;
+            if let Some(output) = output {
+                hdt.add_void_to_header(&base);
+                if let Some(endpoint) = endpoint {
+                    hdt.add_service_description_to_header(&endpoint, &base);
                 }
-                _ => {
-                    panic!(
-                        "Output file has no extension or one signifying an unsupported export format, aborting."
-                    );
+                let mut writer = create_output(&output)?;
+                hdt.write(&mut writer).with_context(|| format!("Error writing HDT file {output:?}"))?;
+                writer.flush()?;
+            } else {
+                let mut out = BufWriter::new(stdout().lock());
+                let sd = endpoint.map(|e| hdt.service_description(&e, &base)).unwrap_or_default();
+                for triple in hdt.void_description(&base).iter().chain(&sd) {
+                    writeln!(out, "{triple}")?;
                 }
-            };
-            let in_size = ByteSize(metadata(&input_path)?.len());
-            let out_size = ByteSize(metadata(&output_path)?.len());
-            println!(
-                "Successfully converted {input_path:?} ({in_size}) to {output_path:?} ({out_size}) in {:.2}s",
-                t.elapsed().as_secs_f32()
-            );
-            // println!("{s}");
+                out.flush()?;
+            }
         }
     }
     Ok(())
@@ -2,6 +2,7 @@
 use super::concurrent_interner::{Interner, Terms};
 use crate::containers::rdf::Id;
 use crate::header::Header;
+use crate::progress::{self, Cancelled, Phase, Progress};
 use crate::triples::{Id as HdtId, TripleId, TriplesBitmap};
 use crate::{DictSectPFC, FourSectDict, Hdt};
 use bitset_core::BitSet;
@@ -10,8 +11,10 @@ use log::{debug, error};
 use oxttl::NTriplesParser;
 use rayon::prelude::*;
 use std::collections::BTreeSet;
+use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread;
 
 pub type Result<T> = std::io::Result<T>;
@@ -26,11 +29,46 @@ impl Hdt {
     /// let hdt = hdt::Hdt::read_nt("tests/resources/empty.nt").unwrap();
     /// ```
     pub fn read_nt(f: impl AsRef<Path>) -> Result<Self> {
-        let f = f.as_ref();
+        Self::read_nt_with(f.as_ref(), None)
+    }
+
+    /// Like [`Hdt::read_nt`] while reporting the progress to the observer, which can cancel the conversion.
+    /// Cancellation results in an IO error of kind [`std::io::ErrorKind::Other`] wrapping [`Cancelled`].
+    /// *This function is available only if HDT is built with the experimental `"nt"` feature.*
+    pub fn read_nt_with_progress(f: impl AsRef<Path>, progress: &dyn Progress) -> Result<Self> {
+        Self::read_nt_with(f.as_ref(), Some(progress))
+    }
+
+    fn read_nt_with(f: &Path, progress: Option<&dyn Progress>) -> Result<Self> {
         let base = Id::Named(format!("file://{}", f.canonicalize()?.display()));
         let original_size = std::fs::File::open(f)?.metadata()?.len();
-        let pool = parse_nt_terms(f)?;
-        Self::from_parsed_terms(pool, &base, Some(original_size))
+        let pool = parse_nt_terms(f, progress)?;
+        Self::from_parsed_terms(pool, &base, Some(original_size), progress)
+    }
+
+    /// Like [`Hdt::read_nt`] but parses N-Triples sequentially from a reader, such as standard input,
+    /// instead of splitting a file for parallel parsing. The base IRI denotes the dataset in the header.
+    /// *This function is available only if HDT is built with the experimental `"nt"` feature.*
+    /// # Example
+    /// ```
+    /// let nt = "<http://example.org/s> <http://example.org/p> \"o\"@en .\n";
+    /// let hdt = hdt::Hdt::read_nt_from(nt.as_bytes(), "http://example.org/dataset").unwrap();
+    /// assert_eq!(hdt.triples.len(), 1);
+    /// ```
+    pub fn read_nt_from(reader: impl Read, base_iri: &str) -> Result<Self> {
+        Self::read_nt_from_with(reader, base_iri, None)
+    }
+
+    /// Like [`Hdt::read_nt_from`] while reporting the progress to the observer, which can cancel the conversion.
+    /// *This function is available only if HDT is built with the experimental `"nt"` feature.*
+    pub fn read_nt_from_with_progress(reader: impl Read, base_iri: &str, progress: &dyn Progress) -> Result<Self> {
+        Self::read_nt_from_with(reader, base_iri, Some(progress))
+    }
+
+    fn read_nt_from_with(reader: impl Read, base_iri: &str, progress: Option<&dyn Progress>) -> Result<Self> {
+        let mut reader = CountingReader { inner: reader, count: 0 };
+        let pool = parse_nt_reader(&mut reader, progress)?;
+        Self::from_parsed_terms(pool, &Id::Named(base_iri.to_owned()), Some(reader.count), progress)
     }
 
     /// Builds an HDT with a FourSectionDictionary with DictionarySectionPlainFrontCoding and SPO order
@@ -46,17 +84,25 @@ impl Hdt {
     /// let hdt = hdt::Hdt::from_triples(triples, "http://example.org/mydataset").unwrap();
     /// ```
     pub fn from_triples<S: AsRef<str>>(triples: impl IntoIterator<Item = [S; 3]>, base_iri: &str) -> Result<Self> {
-        Self::from_parsed_terms(intern_terms(triples), &Id::Named(base_iri.to_owned()), None)
+        Self::from_parsed_terms(intern_terms(triples), &Id::Named(base_iri.to_owned()), None, None)
     }
 
-    fn from_parsed_terms(pool: ParsedTerms, base: &Id, original_size: Option<u64>) -> Result<Self> {
+    fn from_parsed_terms(
+        pool: ParsedTerms, base: &Id, original_size: Option<u64>, progress: Option<&dyn Progress>,
+    ) -> Result<Self> {
         const BLOCK_SIZE: usize = 16;
 
-        let (dict, mut encoded_triples) = dict_triples(pool, BLOCK_SIZE)?;
+        let parsed = pool.triples.len() as u64;
+        progress::report(progress, Phase::Encode, 0, Some(parsed))?;
+        let (dict, mut encoded_triples) = dict_triples(pool, BLOCK_SIZE, progress)?;
+        progress::report(progress, Phase::Encode, parsed, Some(parsed))?;
         let num_triples = encoded_triples.len();
         // Sort by final HDT ID (SPO order) before feeding into TriplesBitmap.
         encoded_triples.par_sort_unstable();
-        let triples = TriplesBitmap::from_triples(&encoded_triples);
+        progress::report(progress, Phase::Wavelet, 0, None)?;
+        let triples = TriplesBitmap::from_triples_lazy(&encoded_triples, progress)?;
+        drop(encoded_triples);
+        triples.build_op_index_with(progress)?;
 
         let header = Header { format: "ntriples".to_owned(), length: 0, body: BTreeSet::new() };
         let mut hdt = Hdt { header, dict, triples };
@@ -176,8 +222,11 @@ fn intern_terms<S: AsRef<str>>(triples: impl IntoIterator<Item = [S; 3]>) -> Par
 }
 
 /// Convert a parsed/interned term pool to a dictionary and encoded triple IDs.
-fn dict_triples(pool: ParsedTerms, block_size: usize) -> Result<(FourSectDict, Vec<TripleId>)> {
+fn dict_triples(
+    pool: ParsedTerms, block_size: usize, progress: Option<&dyn Progress>,
+) -> Result<(FourSectDict, Vec<TripleId>)> {
     let ParsedTerms { triples, interner, subjects, predicates, objects } = pool;
+    let parsed = triples.len() as u64;
 
     // In parallel with dictionary build: sort + dedup triples (by term index
     // — this removes exact duplicate triples; the final SPO-ID sort happens
@@ -197,31 +246,32 @@ fn dict_triples(pool: ParsedTerms, block_size: usize) -> Result<(FourSectDict, V
         // per-term copy), then drop it at the end of this block so the term
         // bytes are freed before the encoding peak.
         let terms = interner.into_terms();
-        build_dict_and_id_maps(&terms, &subjects, &predicates, &objects, block_size)
+        build_dict_and_id_maps(&terms, &subjects, &predicates, &objects, block_size)?
     };
     // Bitsets served their purpose; drop before the encoding peak.
     drop(subjects);
     drop(predicates);
     drop(objects);
 
-    // Drain the sorted index triples directly into HDT-id triples via the ID
-    // maps. `into_par_iter` consumes the Vec so the index triples are freed
-    // before this function returns — only `Vec<TripleId>` survives into
-    // `TriplesBitmap::from_triples`.
+    // Encode the sorted index triples into HDT-id triples via the ID maps, in
+    // parallel within each step so that the observer is called between steps.
+    // Only `Vec<TripleId>` survives into `TriplesBitmap::from_triples`.
     let sorted_triples = sorter.join().expect("NT sorter thread panicked");
-    let encoded_triples: Vec<TripleId> = sorted_triples
-        .into_par_iter()
-        .map(|[s_idx, p_idx, o_idx]| {
-            let s = subj_map[s_idx as usize] as HdtId;
-            let p = pred_map[p_idx as usize] as HdtId;
-            let o = obj_map[o_idx as usize] as HdtId;
-            if s == 0 || p == 0 || o == 0 {
-                error!("encoded triple [{s}, {p}, {o}] contains 0; term missing from dictionary");
-            }
-            [s, p, o]
-        })
-        .collect();
-
+    let encode = |&[s_idx, p_idx, o_idx]: &[u32; 3]| {
+        let s = subj_map[s_idx as usize] as HdtId;
+        let p = pred_map[p_idx as usize] as HdtId;
+        let o = obj_map[o_idx as usize] as HdtId;
+        if s == 0 || p == 0 || o == 0 {
+            error!("encoded triple [{s}, {p}, {o}] contains 0; term missing from dictionary");
+        }
+        [s, p, o]
+    };
+    let mut encoded_triples: Vec<TripleId> = Vec::with_capacity(sorted_triples.len());
+    for chunk in sorted_triples.chunks(progress::ENTRY_STEP) {
+        progress::report(progress, Phase::Encode, encoded_triples.len() as u64, Some(parsed))?;
+        encoded_triples.par_extend(chunk.par_iter().map(encode));
+    }
+    drop(sorted_triples);
     drop(subj_map);
     drop(pred_map);
     drop(obj_map);
@@ -230,45 +280,89 @@ fn dict_triples(pool: ParsedTerms, block_size: usize) -> Result<(FourSectDict, V
 }
 
 /// Parse N-Triples in parallel and collect terms into the interning pool + role bitsets.
-fn parse_nt_terms(path: &Path) -> Result<ParsedTerms> {
+fn parse_nt_terms(path: &Path, progress: Option<&dyn Progress>) -> Result<ParsedTerms> {
+    // number of triples a parser thread parses between two reports
+    const STEP: u64 = 1 << 16;
     let interner: Arc<Interner> = Arc::new(Interner::new());
     // use two threads when available parallelism cannot be determined as going to a single thread is around 38% slower
     // 16 chosen as a sane upper limit
     let num_parsers = std::cmp::min(16, thread::available_parallelism().map_or(2, std::num::NonZero::get));
     // Store triple indices instead of strings
     let readers = NTriplesParser::new().split_file_for_parallel_parsing(path, num_parsers)?;
+    progress::report(progress, Phase::Parse, 0, None)?;
+    let parsed = AtomicU64::new(0);
+    let cancelled = AtomicBool::new(false);
     let triples: Vec<[u32; 3]> = readers
         .into_par_iter()
         .flat_map_iter(|reader| {
-            reader.map(|q| {
-                let clean = |s: &mut String| {
-                    let mut chars = s.chars();
-                    if chars.next() == Some('<') && chars.nth_back(0) == Some('>') {
-                        s.remove(0);
-                        s.pop();
+            reader.enumerate().take_while(|_| !cancelled.load(Ordering::Relaxed)).map(|(i, q)| {
+                if i as u64 % STEP == STEP - 1 {
+                    let done = parsed.fetch_add(STEP, Ordering::Relaxed) + STEP;
+                    if progress::report(progress, Phase::Parse, done, None).is_err() {
+                        cancelled.store(true, Ordering::Relaxed);
                     }
-                };
+                }
                 let q = q.unwrap(); // TODO: error handling
-                let mut subj_str = q.subject.to_string();
-                clean(&mut subj_str);
-                let mut pred_str = q.predicate.to_string();
-                clean(&mut pred_str);
-                let mut obj_str = q.object.to_string();
-                clean(&mut obj_str);
-
-                let s = interner.get_or_intern(&subj_str);
-                let p = interner.get_or_intern(&pred_str);
-                let o = interner.get_or_intern(&obj_str);
-
-                [s, p, o]
+                intern_nt_terms(&interner, [q.subject.to_string(), q.predicate.to_string(), q.object.to_string()])
             })
         })
         .collect();
 
+    if cancelled.into_inner() {
+        return Err(Cancelled(Phase::Parse).into());
+    }
+    progress::report(progress, Phase::Parse, triples.len() as u64, Some(triples.len() as u64))?;
     let interner = Arc::try_unwrap(interner).expect("interner Arc still has outstanding references");
     Ok(ParsedTerms::new(interner, triples))
 }
 
+/// Interns the N-Triples forms of the terms of a triple in the HDT dictionary format,
+/// which differs only in that IRIs are not enclosed in angle brackets.
+fn intern_nt_terms(interner: &Interner, terms: [String; 3]) -> [u32; 3] {
+    terms.map(|mut term| {
+        if term.starts_with('<') && term.ends_with('>') {
+            term.pop();
+            term.remove(0);
+        }
+        interner.get_or_intern(&term)
+    })
+}
+
+/// Parse N-Triples sequentially from a reader, for input that cannot be split such as standard input.
+fn parse_nt_reader(reader: impl Read, progress: Option<&dyn Progress>) -> Result<ParsedTerms> {
+    // number of triples between two reports
+    const STEP: u64 = 1 << 16;
+    let interner = Interner::new();
+    let mut triples = Vec::new();
+    progress::report(progress, Phase::Parse, 0, None)?;
+    for t in NTriplesParser::new().for_reader(reader) {
+        let t = t.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        triples.push(intern_nt_terms(
+            &interner,
+            [t.subject.to_string(), t.predicate.to_string(), t.object.to_string()],
+        ));
+        if (triples.len() as u64).is_multiple_of(STEP) {
+            progress::report(progress, Phase::Parse, triples.len() as u64, None)?;
+        }
+    }
+    progress::report(progress, Phase::Parse, triples.len() as u64, Some(triples.len() as u64))?;
+    Ok(ParsedTerms::new(interner, triples))
+}
+
+/// Counts the bytes that pass through a reader.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
 /// Enumerate the set-bit positions (term indices) of a bitset. Uses
 /// `trailing_zeros` per word — far cheaper than iterating every bit and
 /// calling `bit_test` (the old `externalize` pattern).
@@ -300,7 +394,7 @@ fn collect_set_indices(bitset: &Indices) -> Vec<u32> {
 /// - predicates: all predicate terms (ids 1..=N_pred)
 fn build_dict_and_id_maps(
     terms: &Terms, subjects_bs: &Indices, predicates_bs: &Indices, objects_bs: &Indices, block_size: usize,
-) -> (FourSectDict, IdMap, IdMap, IdMap) {
+) -> Result<(FourSectDict, IdMap, IdMap, IdMap)> {
     use log::warn;
 
     if predicates_bs.is_empty() {
@@ -323,6 +417,13 @@ fn build_dict_and_id_maps(
     drop(shared_bs);
     drop(unique_subj_bs);
     drop(unique_obj_bs);
+    FourSectDict::check_id_range(
+        shared_keys.len(),
+        unique_subj_keys.len(),
+        pred_keys.len(),
+        unique_obj_keys.len(),
+    )
+    .map_err(std::io::Error::other)?;
 
     // Sort each section by the resolved string. Each `par_sort_unstable_by`
     // uses the rayon thread pool, so running the four sorts back-to-back lets
@@ -401,7 +502,7 @@ fn build_dict_and_id_maps(
         (h_shared.join().unwrap(), h_subj.join().unwrap(), h_pred.join().unwrap(), h_obj.join().unwrap())
     });
 
-    (FourSectDict { shared, subjects, predicates, objects }, subj_map, pred_map, obj_map)
+    Ok((FourSectDict::new(shared, subjects, predicates, objects), subj_map, pred_map, obj_map))
 }
 
 #[cfg(test)]
@@ -441,6 +542,19 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn read_nt_from() -> Result<()> {
+        init();
+        let snikmeta = snikmeta()?;
+        let mut nt = Vec::new();
+        snikmeta.write_nt(&mut nt)?;
+        let streamed = Hdt::read_nt_from(nt.as_slice(), "http://www.snik.eu/ontology/meta")?;
+        assert_eq!(streamed.triples_all().collect::<Vec<_>>(), snikmeta.triples_all().collect::<Vec<_>>());
+        snikmeta_check(&streamed)?;
+        assert!(Hdt::read_nt_from("<a> <b> .".as_bytes(), "http://example.org/invalid").is_err());
+        Ok(())
+    }
+
     #[test]
     fn from_triples() -> Result<()> {
         init();
@@ -0,0 +1,188 @@
+//! Query iterators that own a shared reference to the [`Hdt`] instead of borrowing it.
+//! They are `Send + 'static`, so they can be stored in structs, resumed later and moved into other threads or async tasks.
+//! The results are the same as those of the borrowing methods, such as [`Hdt::triples_with_pattern`].
+//! # Example
+//! ```
+//! use std::sync::Arc;
+//! let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+//! let hdt = Arc::new(hdt::Hdt::read(std::io::BufReader::new(file)).unwrap());
+//! let mut iter = hdt.triples_with_pattern_owned(None, Some("http://www.w3.org/2000/01/rdf-schema#label"), None);
+//! let first = iter.next();
+//! let rest = std::thread::spawn(move || iter.count()).join().unwrap();
+//! assert!(first.is_some() && rest > 0);
+//! ```
+use crate::Hdt;
+use crate::IdKind;
+use crate::hdt::TripleCache;
+use crate::triples::{PatternCursor, PredicateObjectCursor, PredicateObjectIter, TripleId};
+use log::error;
+use std::sync::Arc;
+
+/// Owned iterator over the triple IDs that fit a triple ID pattern, see [`Hdt::triple_ids_with_id_pattern_owned`].
+#[derive(Debug, Clone)]
+pub struct OwnedTripleIds {
+    hdt: Arc<Hdt>,
+    cursor: PatternCursor,
+}
+
+impl OwnedTripleIds {
+    /// Iterates over the triple IDs that fit the given pattern, where 0 stands for a variable.
+    pub fn new(hdt: Arc<Hdt>, pattern: TripleId) -> Self {
+        let cursor = PatternCursor::new(&hdt.triples, pattern);
+        OwnedTripleIds { hdt, cursor }
+    }
+
+    /// Iterator without results, for patterns with a constant that is not in the dictionary.
+    pub const fn empty(hdt: Arc<Hdt>) -> Self {
+        OwnedTripleIds { hdt, cursor: PatternCursor::empty() }
+    }
+
+    /// The queried HDT.
+    pub const fn hdt(&self) -> &Arc<Hdt> {
+        &self.hdt
+    }
+}
+
+impl Iterator for OwnedTripleIds {
+    type Item = TripleId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(&self.hdt.triples)
+    }
+}
+
+/// Owned iterator over the string triples that fit a triple pattern, see [`Hdt::triples_with_pattern_owned`].
+#[derive(Debug, Clone)]
+pub struct OwnedTriples {
+    ids: OwnedTripleIds,
+    cache: TripleCache,
+}
+
+impl OwnedTriples {
+    /// Iterates over the triples that fit the given pattern, where `None` stands for a variable.
+    pub fn new(hdt: Arc<Hdt>, sp: Option<&str>, pp: Option<&str>, op: Option<&str>) -> Self {
+        let ids = match hdt.id_pattern([sp, pp, op]) {
+            Some(pattern) => OwnedTripleIds::new(hdt, pattern),
+            None => OwnedTripleIds::empty(hdt),
+        };
+        OwnedTriples { ids, cache: TripleCache::default() }
+    }
+
+    /// The queried HDT.
+    pub const fn hdt(&self) -> &Arc<Hdt> {
+        self.ids.hdt()
+    }
+}
+
+impl Iterator for OwnedTriples {
+    type Item = [Arc<str>; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let t = self.ids.next()?;
+            match self.cache.translate(&self.ids.hdt.dict, t) {
+                Ok(t) => return Some(t),
+                Err(e) => error!("{e}"),
+            }
+        }
+    }
+}
+
+/// Owned iterator over the subjects with a given predicate and object, see [`Hdt::subjects_with_po_owned`].
+#[derive(Debug, Clone)]
+pub struct OwnedSubjects {
+    hdt: Arc<Hdt>,
+    cursor: Option<PredicateObjectCursor>,
+}
+
+impl OwnedSubjects {
+    /// Iterates over the subjects of the triples with the given predicate and object.
+    pub fn new(hdt: Arc<Hdt>, p: &str, o: &str) -> Self {
+        let pid = hdt.dict.string_to_id(p, IdKind::Predicate);
+        let oid = hdt.dict.string_to_id(o, IdKind::Object);
+        // predicate or object not in dictionary, iterator would interpret 0 as variable
+        let cursor =
+            (pid != 0 && oid != 0).then(|| PredicateObjectIter::new(&hdt.triples, pid, oid).into_cursor());
+        OwnedSubjects { hdt, cursor }
+    }
+
+    /// The queried HDT.
+    pub const fn hdt(&self) -> &Arc<Hdt> {
+        &self.hdt
+    }
+}
+
+impl Iterator for OwnedSubjects {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let sid = self.cursor.as_mut()?.next(&self.hdt.triples)?;
+            match self.hdt.dict.id_to_string(sid, IdKind::Subject) {
+                Ok(s) => return Some(s),
+                Err(e) => error!("Error on subject ID {sid}: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use pretty_assertions::assert_eq;
+
+    const fn assert_send_static<T: Send + 'static>() {}
+    const _: () = {
+        assert_send_static::<OwnedTripleIds>();
+        assert_send_static::<OwnedTriples>();
+        assert_send_static::<OwnedSubjects>();
+    };
+
+    #[test]
+    fn same_as_borrowed() -> color_eyre::Result<()> {
+        init();
+        let hdt = Arc::new(snikmeta()?);
+        let meta = "http://www.snik.eu/ontology/meta/";
+        let top = format!("{meta}Top");
+        let entity = format!("{meta}Entity");
+        let sub_class_of = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+        let type_ = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+        let class = "http://www.w3.org/2002/07/owl#Class";
+        let terms =
+            [None, Some(entity.as_str()), Some(top.as_str()), Some(sub_class_of), Some(type_), Some(class)];
+        for s in terms {
+            for p in terms {
+                for o in terms {
+                    let expected: Vec<_> = hdt.triples_with_pattern(s, p, o).collect();
+                    assert_eq!(hdt.triples_with_pattern_owned(s, p, o).collect::<Vec<_>>(), expected);
+                    let expected: Vec<_> = hdt.triple_ids_with_pattern(s, p, o).collect();
+                    let pattern = hdt.id_pattern([s, p, o]);
+                    let owned: Vec<_> = pattern
+                        .map_or_else(Vec::new, |pattern| hdt.triple_ids_with_id_pattern_owned(pattern).collect());
+                    assert_eq!(owned, expected);
+                }
+            }
+        }
+        let expected: Vec<_> = hdt.subjects_with_po(type_, class).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(hdt.subjects_with_po_owned(type_, class).collect::<Vec<_>>(), expected);
+        assert_eq!(hdt.subjects_with_po_owned(type_, "http://example.org/missing").count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn resume_in_thread() -> color_eyre::Result<()> {
+        init();
+        let hdt = Arc::new(snikmeta()?);
+        let expected: Vec<_> = hdt.triples_with_pattern(None, None, None).collect();
+        let mut iter = hdt.triples_with_pattern_owned(None, None, None);
+        let mut triples: Vec<_> = iter.by_ref().take(10).collect();
+        // stored iterators keep the HDT alive
+        drop(hdt);
+        triples.extend(std::thread::spawn(move || iter.collect::<Vec<_>>()).join().unwrap());
+        assert_eq!(triples, expected);
+        Ok(())
+    }
+}
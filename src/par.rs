@@ -0,0 +1,183 @@
+//! Parallel iteration over triples with [rayon].
+//! Queries are split into ranges of positions in the predicate layer for subject-based patterns,
+//! in the predicate occurrences for ?P? and in the object index for ?PO and ??O,
+//! which are then iterated on the rayon thread pool.
+//! Collecting the results into a `Vec` preserves the order of the sequential iterators.
+//! # Example
+//! ```
+//! use rayon::prelude::*;
+//! let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+//! let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+//! let labels = hdt.par_triples_with_pattern(None, Some("http://www.w3.org/2000/01/rdf-schema#label"), None).count();
+//! assert!(labels > 0);
+//! ```
+use crate::Hdt;
+use crate::hdt::TripleCache;
+use crate::triples::{PatternCursor, TripleId, TriplesBitmap};
+use log::error;
+use rayon::prelude::*;
+use std::iter;
+use std::sync::Arc;
+
+/// Number of parts per thread that a query is split into, more parts balance the load better.
+const PARTS_PER_THREAD: usize = 8;
+/// Approximate number of triples that are serialized into one buffer by [`Hdt::par_write_nt`].
+#[cfg(feature = "sophia")]
+const NT_PART_TRIPLES: usize = 1 << 16;
+
+/// Cursors for consecutive parts of the results of the given pattern.
+fn split(triples: &TriplesBitmap, pattern: TripleId, parts: usize) -> Vec<PatternCursor> {
+    PatternCursor::new(triples, pattern).split(triples, parts)
+}
+
+fn default_parts() -> usize {
+    rayon::current_num_threads() * PARTS_PER_THREAD
+}
+
+impl TriplesBitmap {
+    /// Parallel iterator over all triple IDs, see [`TriplesBitmap::par_triples_with_pattern`].
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = TripleId> + '_ {
+        self.par_triples_with_pattern([0; 3])
+    }
+
+    /// Parallel iterator over the triple IDs that fit the given pattern, where 0 stands for a variable.
+    /// Yields the same triples as [`Hdt::triple_ids_with_id_pattern`].
+    pub fn par_triples_with_pattern(&self, pattern: TripleId) -> impl ParallelIterator<Item = TripleId> + '_ {
+        split(self, pattern, default_parts())
+            .into_par_iter()
+            .flat_map_iter(move |mut cursor| iter::from_fn(move || cursor.next(self)))
+    }
+}
+
+impl Hdt {
+    /// Parallel version of [`Hdt::triple_ids_with_id_pattern`].
+    pub fn par_triple_ids_with_id_pattern(
+        &self, pattern: TripleId,
+    ) -> impl ParallelIterator<Item = TripleId> + '_ {
+        self.triples.par_triples_with_pattern(pattern)
+    }
+
+    /// Parallel version of [`Hdt::triples_all`].
+    pub fn par_triples_all(&self) -> impl ParallelIterator<Item = [Arc<str>; 3]> + '_ {
+        self.par_translate(split(&self.triples, [0; 3], default_parts()))
+    }
+
+    /// Parallel version of [`Hdt::triples_with_pattern`].
+    pub fn par_triples_with_pattern(
+        &self, sp: Option<&str>, pp: Option<&str>, op: Option<&str>,
+    ) -> impl ParallelIterator<Item = [Arc<str>; 3]> + '_ {
+        let cursors =
+            self.id_pattern([sp, pp, op]).map_or_else(Vec::new, |p| split(&self.triples, p, default_parts()));
+        self.par_translate(cursors)
+    }
+
+    /// Translates the triples of each cursor to strings, using a separate cache per cursor.
+    fn par_translate(&self, cursors: Vec<PatternCursor>) -> impl ParallelIterator<Item = [Arc<str>; 3]> + '_ {
+        cursors.into_par_iter().flat_map_iter(move |mut cursor| {
+            let mut cache = TripleCache::default();
+            iter::from_fn(move || cursor.next(&self.triples))
+                .filter_map(move |t| cache.translate(&self.dict, t).map_err(|e| error!("{e}")).ok())
+        })
+    }
+
+    /// Write as N-Triples like [`Hdt::write_nt`] but serialize in parallel.
+    /// Parts of the triples are serialized into buffers on the rayon thread pool and written in order,
+    /// so the output is identical and memory usage is bounded by a few buffers per thread.
+    #[cfg(feature = "sophia")]
+    pub fn par_write_nt(&self, write: &mut impl std::io::Write) -> std::io::Result<()> {
+        let threads = rayon::current_num_threads();
+        let parts = (self.triples.len() / NT_PART_TRIPLES).max(threads * PARTS_PER_THREAD);
+        let cursors = split(&self.triples, [0; 3], parts);
+        for batch in cursors.chunks(threads * 2) {
+            let buffers: Vec<std::io::Result<Vec<u8>>> =
+                batch.par_iter().map(|c| self.nt_part(c.clone())).collect();
+            for buffer in buffers {
+                write.write_all(&buffer?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the triples of the cursor as N-Triples.
+    #[cfg(feature = "sophia")]
+    fn nt_part(&self, mut cursor: PatternCursor) -> std::io::Result<Vec<u8>> {
+        use crate::hdt_graph::{HdtTerm, auto_term};
+        use sophia::api::prelude::TripleSerializer;
+        use sophia::api::term::IriRef;
+        use sophia::turtle::serializer::nt::NTriplesSerializer;
+        let mut cache = TripleCache::default();
+        let mut triples = Vec::new();
+        while let Some(t) = cursor.next(&self.triples) {
+            let [s, p, o] = cache.translate(&self.dict, t).map_err(std::io::Error::other)?;
+            triples.push([auto_term(&s)?, HdtTerm::Iri(IriRef::new_unchecked(p)), auto_term(&o)?]);
+        }
+        let mut buffer = Vec::new();
+        NTriplesSerializer::new(&mut buffer)
+            .serialize_graph(&triples)
+            .map_err(|e| std::io::Error::other(format!("{e}")))?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdKind;
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use crate::triples::Id;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn same_as_sequential() -> color_eyre::Result<()> {
+        init();
+        let hdt = snikmeta()?;
+        let expected: Vec<TripleId> = hdt.triples.into_iter().collect();
+        assert_eq!(hdt.triples.par_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(hdt.par_triples_all().collect::<Vec<_>>(), hdt.triples_all().collect::<Vec<_>>());
+        let meta = "http://www.snik.eu/ontology/meta/";
+        let top = format!("{meta}Top");
+        let entity = format!("{meta}Entity");
+        let sub_class_of = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+        let type_ = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+        let class = "http://www.w3.org/2002/07/owl#Class";
+        let terms =
+            [None, Some(entity.as_str()), Some(top.as_str()), Some(sub_class_of), Some(type_), Some(class)];
+        for s in terms {
+            for p in terms {
+                for o in terms {
+                    let expected: Vec<_> = hdt.triples_with_pattern(s, p, o).collect();
+                    assert_eq!(hdt.par_triples_with_pattern(s, p, o).collect::<Vec<_>>(), expected);
+                }
+            }
+        }
+        // all single constant patterns
+        for (pos, kind) in IdKind::KINDS.iter().enumerate() {
+            let n = match kind {
+                IdKind::Subject => hdt.dict.shared.num_strings() + hdt.dict.subjects.num_strings(),
+                IdKind::Predicate => hdt.dict.predicates.num_strings(),
+                IdKind::Object => hdt.dict.shared.num_strings() + hdt.dict.objects.num_strings(),
+            };
+            for id in 1..=n as Id {
+                let mut pattern = [0; 3];
+                pattern[pos] = id;
+                let expected: Vec<_> = hdt.triple_ids_with_id_pattern(pattern).collect();
+                assert_eq!(hdt.par_triple_ids_with_id_pattern(pattern).collect::<Vec<_>>(), expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "sophia")]
+    #[test]
+    fn write_nt() -> color_eyre::Result<()> {
+        init();
+        let hdt = snikmeta()?;
+        let mut expected = Vec::new();
+        hdt.write_nt(&mut expected)?;
+        let mut actual = Vec::new();
+        hdt.par_write_nt(&mut actual)?;
+        assert_eq!(String::from_utf8(actual)?, String::from_utf8(expected)?);
+        Ok(())
+    }
+}
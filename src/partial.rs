@@ -0,0 +1,190 @@
+//! Loading only selected sections of an HDT file, see [`PartialHdt`].
+use crate::Hdt;
+use crate::containers::ControlInfo;
+use crate::four_sect_dict::FourSectDict;
+use crate::hdt::{Error, ReadOptions, Result};
+use crate::header::Header;
+use crate::triples::TriplesBitmap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Sections of an HDT file that are materialised when reading it into a [`PartialHdt`].
+/// Load time and memory usage scale with the selected sections.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sections {
+    /// Only the header, for example to read metadata from a large file.
+    Header,
+    /// Header and dictionary for term lookup, the triples section is not read.
+    Dictionary,
+    /// Header and triples for queries in ID space.
+    /// The dictionary is skipped without decoding it and the object index is built on the first ?PO or ??O query.
+    Triples,
+    /// All sections including the object index, like [`Hdt::read`].
+    #[default]
+    All,
+}
+
+/// HDT with only some of its sections loaded into memory.
+/// When created with [`PartialHdt::read_from_path`], missing sections are loaded from the file on first use,
+/// otherwise accessing them returns [`Error::NotLoaded`].
+#[derive(Debug)]
+pub struct PartialHdt {
+    /// The header is always loaded.
+    pub header: Header,
+    dict: OnceLock<FourSectDict>,
+    triples: OnceLock<TriplesBitmap>,
+    path: Option<PathBuf>,
+}
+
+impl PartialHdt {
+    /// Reads the given sections from the reader, which must point to the beginning of the data of an HDT file.
+    /// The remaining data is not consumed.
+    /// # Example
+    /// ```
+    /// use hdt::partial::{PartialHdt, Sections};
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = PartialHdt::read(std::io::BufReader::new(file), Sections::Dictionary).unwrap();
+    /// let id = hdt.dict().unwrap().string_to_id("http://www.snik.eu/ontology/meta/Top", hdt::IdKind::Subject);
+    /// assert!(id != 0);
+    /// assert!(hdt.triples().is_err());
+    /// ```
+    pub fn read<R: BufRead>(mut reader: R, sections: Sections) -> Result<Self> {
+        let header = Hdt::read_header(&mut reader)?;
+        let (dict, triples) = match sections {
+            Sections::Header => (OnceLock::new(), OnceLock::new()),
+            Sections::Dictionary => {
+                (OnceLock::from(FourSectDict::read(&mut reader)?.validate()?), OnceLock::new())
+            }
+            Sections::Triples => {
+                FourSectDict::skip(&mut reader)?;
+                (
+                    OnceLock::new(),
+                    OnceLock::from(TriplesBitmap::read_sect_lazy(&mut reader, ReadOptions::default())?),
+                )
+            }
+            Sections::All => {
+                let unvalidated_dict = FourSectDict::read(&mut reader)?;
+                let triples = TriplesBitmap::read_sect(&mut reader)?;
+                (OnceLock::from(unvalidated_dict.validate()?), OnceLock::from(triples))
+            }
+        };
+        Ok(PartialHdt { header, dict, triples, path: None })
+    }
+
+    /// Reads the given sections from an HDT file and remembers the path to load the others on first use.
+    /// # Example
+    /// ```
+    /// use hdt::partial::{PartialHdt, Sections};
+    /// let hdt = PartialHdt::read_from_path("tests/resources/snikmeta.hdt", Sections::Header).unwrap();
+    /// // loads the triples section now, the dictionary is still not needed
+    /// assert_eq!(hdt.triples().unwrap().len(), 328);
+    /// ```
+    pub fn read_from_path(path: impl AsRef<Path>, sections: Sections) -> Result<Self> {
+        let path = path.as_ref();
+        let mut hdt = Self::read(BufReader::new(File::open(path)?), sections)?;
+        hdt.path = Some(path.to_owned());
+        Ok(hdt)
+    }
+
+    /// Opens the source file and positions the reader after the header.
+    fn reopen(&self, section: &'static str) -> Result<BufReader<File>> {
+        let path = self.path.as_ref().ok_or(Error::NotLoaded(section))?;
+        let mut reader = BufReader::new(File::open(path)?);
+        ControlInfo::read(&mut reader)?;
+        Header::read(&mut reader)?;
+        Ok(reader)
+    }
+
+    /// The dictionary, which is loaded from the file if necessary.
+    pub fn dict(&self) -> Result<&FourSectDict> {
+        if let Some(dict) = self.dict.get() {
+            return Ok(dict);
+        }
+        let dict = FourSectDict::read(&mut self.reopen("dictionary")?)?.validate()?;
+        Ok(self.dict.get_or_init(|| dict))
+    }
+
+    /// The triples, which are loaded from the file if necessary.
+    /// If they are loaded here, the object index is only built when first needed.
+    pub fn triples(&self) -> Result<&TriplesBitmap> {
+        if let Some(triples) = self.triples.get() {
+            return Ok(triples);
+        }
+        let mut reader = self.reopen("triples")?;
+        FourSectDict::skip(&mut reader)?;
+        let triples = TriplesBitmap::read_sect_lazy(&mut reader, ReadOptions::default())?;
+        Ok(self.triples.get_or_init(|| triples))
+    }
+
+    /// Converts into a complete HDT, loading missing sections if necessary.
+    pub fn into_hdt(self) -> Result<Hdt> {
+        self.dict()?;
+        self.triples()?;
+        let PartialHdt { header, dict, triples, .. } = self;
+        Ok(Hdt { header, dict: dict.into_inner().unwrap(), triples: triples.into_inner().unwrap() })
+    }
+
+    /// Recursive size in bytes on the heap of the loaded sections.
+    pub fn size_in_bytes(&self) -> usize {
+        self.dict.get().map_or(0, FourSectDict::size_in_bytes)
+            + self.triples.get().map_or(0, TriplesBitmap::size_in_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdKind;
+    use crate::hdt::tests::{snikmeta, snikmeta_check};
+    use crate::tests::init;
+    use crate::triples::ObjectIter;
+    use pretty_assertions::assert_eq;
+
+    const PATH: &str = "tests/resources/snikmeta.hdt";
+
+    fn read(sections: Sections) -> Result<PartialHdt> {
+        PartialHdt::read(BufReader::new(File::open(PATH)?), sections)
+    }
+
+    #[test]
+    fn sections() -> color_eyre::Result<()> {
+        init();
+        let full = snikmeta()?;
+        let header = read(Sections::Header)?;
+        assert_eq!(header.header.body, full.header.body);
+        assert_eq!(header.size_in_bytes(), 0);
+        assert!(matches!(header.dict(), Err(Error::NotLoaded("dictionary"))));
+        assert!(matches!(header.triples(), Err(Error::NotLoaded("triples"))));
+
+        let dict = read(Sections::Dictionary)?;
+        assert_eq!(dict.dict()?, &full.dict);
+        assert!(matches!(dict.triples(), Err(Error::NotLoaded("triples"))));
+
+        // the object index is only built on the first ??O query
+        let triples = read(Sections::Triples)?;
+        assert!(matches!(triples.dict(), Err(Error::NotLoaded("dictionary"))));
+        let t = triples.triples()?;
+        let without_op_index = triples.size_in_bytes();
+        assert!(without_op_index < full.triples.size_in_bytes());
+        let o = full.dict.string_to_id("http://www.snik.eu/ontology/meta/Top", IdKind::Object);
+        let expected: Vec<_> = ObjectIter::new(&full.triples, o).collect();
+        assert_eq!(ObjectIter::new(t, o).collect::<Vec<_>>(), expected);
+        assert_eq!(triples.size_in_bytes(), full.triples.size_in_bytes());
+
+        snikmeta_check(&read(Sections::All)?.into_hdt()?)?;
+        Ok(())
+    }
+
+    #[test]
+    fn load_on_first_use() -> color_eyre::Result<()> {
+        init();
+        let hdt = PartialHdt::read_from_path(PATH, Sections::Header)?;
+        assert_eq!(hdt.triples()?.len(), 328);
+        assert_eq!(hdt.dict()?.predicates.num_strings(), 23);
+        snikmeta_check(&hdt.into_hdt()?)?;
+        snikmeta_check(&PartialHdt::read_from_path(PATH, Sections::Triples)?.into_hdt()?)?;
+        Ok(())
+    }
+}
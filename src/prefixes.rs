@@ -0,0 +1,155 @@
+//! Prefixes for abbreviating IRIs in RDF serializations, derived from the most common namespaces of the dictionary.
+use crate::Hdt;
+use crate::dict_sect_pfc::DictSectPFC;
+use log::error;
+use std::collections::{HashMap, HashSet};
+
+/// Number of prefixes that are declared by default.
+pub const DEFAULT_PREFIXES: usize = 20;
+
+/// Usual names of well-known namespaces, which are preferred over generated ones.
+const WELL_KNOWN: [(&str, &str); 17] = [
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("sh", "http://www.w3.org/ns/shacl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("schema", "http://schema.org/"),
+    ("dbo", "http://dbpedia.org/ontology/"),
+    ("dbr", "http://dbpedia.org/resource/"),
+    ("wd", "http://www.wikidata.org/entity/"),
+    ("wdt", "http://www.wikidata.org/prop/direct/"),
+];
+
+/// The namespace of an IRI, which ends after its last `#` or `/`, or `None` if there is no local name.
+/// # Example
+/// ```
+/// assert_eq!(hdt::prefixes::namespace("http://example.org/ns#name"), Some("http://example.org/ns#"));
+/// assert_eq!(hdt::prefixes::namespace("http://example.org/"), None);
+/// ```
+pub fn namespace(iri: &str) -> Option<&str> {
+    let end = iri.rfind(['#', '/'])? + 1;
+    // exclude the authority part, such as "http://"
+    (end < iri.len() && iri[..end].trim_end_matches('/').contains('/')).then(|| &iri[..end])
+}
+
+/// IRI that a term in the HDT dictionary format contributes to the namespace counts, which is the datatype for literals.
+fn term_iri(term: &str) -> Option<&str> {
+    if term.starts_with('"') {
+        term.rsplit_once("^^<").and_then(|(_, dt)| dt.strip_suffix('>'))
+    } else if term.starts_with("_:") {
+        None
+    } else {
+        Some(term)
+    }
+}
+
+/// A prefix name for a namespace that is neither well-known nor already taken, based on its last path segment.
+fn prefix_name(namespace: &str, taken: &HashSet<String>) -> String {
+    let segment = namespace.trim_end_matches(['#', '/']).rsplit(['/', '#', '.', ':']).next().unwrap_or_default();
+    let mut base: String =
+        segment.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_lowercase();
+    if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        base.insert(0, 'n');
+    }
+    let mut name = base.clone();
+    let mut i = 1;
+    while taken.contains(&name) {
+        i += 1;
+        name = format!("{base}{i}");
+    }
+    name
+}
+
+impl Hdt {
+    /// Up to `n` (prefix name, namespace) pairs for the namespaces with the most terms in the dictionary,
+    /// ordered by descending number of terms. Literals count towards the namespace of their datatype.
+    /// Well-known namespaces, such as that of RDFS, get their usual names.
+    /// Decodes the whole dictionary, so this takes a while for large files.
+    /// # Example
+    /// ```
+    /// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+    /// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+    /// let prefixes = hdt.common_prefixes(3);
+    /// assert_eq!(prefixes[0], ("meta".to_owned(), "http://www.snik.eu/ontology/meta/".to_owned()));
+    /// ```
+    pub fn common_prefixes(&self, n: usize) -> Vec<(String, String)> {
+        let mut counts = HashMap::<String, usize>::new();
+        let dict = &self.dict;
+        for sect in [&dict.shared, &dict.subjects, &dict.predicates, &dict.objects] {
+            count_namespaces(sect, &mut counts);
+        }
+        let mut namespaces: Vec<(String, usize)> = counts.into_iter().collect();
+        namespaces.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        namespaces.truncate(n);
+        let well_known: HashMap<&str, &str> = WELL_KNOWN.iter().map(|&(name, ns)| (ns, name)).collect();
+        let mut taken: HashSet<String> = WELL_KNOWN.iter().map(|(name, _)| (*name).to_owned()).collect();
+        namespaces
+            .into_iter()
+            .map(|(namespace, _)| {
+                let name = well_known.get(namespace.as_str()).map_or_else(
+                    || {
+                        let name = prefix_name(&namespace, &taken);
+                        taken.insert(name.clone());
+                        name
+                    },
+                    |name| (*name).to_owned(),
+                );
+                (name, namespace)
+            })
+            .collect()
+    }
+}
+
+fn count_namespaces(sect: &DictSectPFC, counts: &mut HashMap<String, usize>) {
+    for block in 0..sect.num_strings.div_ceil(sect.block_size.max(1)) {
+        let strings = match sect.extract_block(block) {
+            Ok(strings) => strings,
+            Err(e) => {
+                error!("{e}");
+                continue;
+            }
+        };
+        for ns in strings.iter().filter_map(|s| term_iri(s)).filter_map(namespace) {
+            // avoid allocating for namespaces that are already counted
+            if let Some(count) = counts.get_mut(ns) {
+                *count += 1;
+            } else {
+                counts.insert(ns.to_owned(), 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+
+    #[test]
+    fn prefixes() -> color_eyre::Result<()> {
+        init();
+        assert_eq!(namespace("http://example.org/a/b"), Some("http://example.org/a/"));
+        assert_eq!(namespace("urn:isbn:123"), None);
+        assert_eq!(namespace("http://example.org"), None);
+        assert_eq!(
+            term_iri("\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>"),
+            Some("http://www.w3.org/2001/XMLSchema#integer")
+        );
+        assert_eq!(term_iri("\"a\"@en"), None);
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let prefixes = hdt.common_prefixes(100);
+        let names: HashSet<&str> = prefixes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names.len(), prefixes.len(), "prefix names are unique");
+        assert!(prefixes.iter().any(|(name, ns)| name == "rdfs" && ns == "http://www.w3.org/2000/01/rdf-schema#"));
+        assert_eq!(hdt.common_prefixes(2), prefixes[..2]);
+        Ok(())
+    }
+}
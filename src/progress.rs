@@ -0,0 +1,252 @@
+//! Progress reporting and cancellation of long running operations like loading, converting and writing HDT.
+use std::io::{BufRead, Read, Write};
+use std::ops::ControlFlow;
+
+/// Phase of a long running operation, reported to a [`Progress`] observer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading the header, measured in bytes read.
+    Header,
+    /// Reading the dictionary sections, measured in bytes read.
+    Dictionary,
+    /// Reading the triples section or the cache, measured in bytes read.
+    Triples,
+    /// Building the wavelet matrix for predicate-based access, measured in predicate layer entries.
+    Wavelet,
+    /// Building the object index, measured in triples.
+    OpIndex,
+    /// Parsing N-Triples, measured in triples parsed.
+    Parse,
+    /// Building the dictionary from parsed terms, measured in triples.
+    Encode,
+    /// Writing HDT, measured in bytes written.
+    Write,
+}
+
+/// Observer of long running operations, which can cancel them by returning [`ControlFlow::Break`].
+/// Implemented for closures `Fn(Phase, u64, Option<u64>) -> ControlFlow<()>`.
+/// # Example
+/// ```
+/// use hdt::hdt::ReadOptions;
+/// use hdt::progress::Phase;
+/// use std::ops::ControlFlow;
+/// let progress = |phase: Phase, done: u64, total: Option<u64>| {
+///     println!("{phase:?} {done}/{total:?}");
+///     ControlFlow::Continue(())
+/// };
+/// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+/// let options = ReadOptions { progress: Some(&progress), ..Default::default() };
+/// let hdt = hdt::Hdt::read_with_options(std::io::BufReader::new(file), options).unwrap();
+/// ```
+pub trait Progress: Sync {
+    /// Called at the start of each phase and regularly during it with the amount of work done and the total if known.
+    /// Return [`ControlFlow::Break`] to cancel the operation, which then fails with a [`Cancelled`] error.
+    fn report(&self, phase: Phase, done: u64, total: Option<u64>) -> ControlFlow<()>;
+}
+
+impl<F: Fn(Phase, u64, Option<u64>) -> ControlFlow<()> + Sync> Progress for F {
+    fn report(&self, phase: Phase, done: u64, total: Option<u64>) -> ControlFlow<()> {
+        self(phase, done, total)
+    }
+}
+
+/// The error when a [`Progress`] observer cancels an operation.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("cancelled during phase {0:?}")]
+pub struct Cancelled(pub Phase);
+
+impl From<Cancelled> for std::io::Error {
+    fn from(c: Cancelled) -> Self {
+        std::io::Error::other(c)
+    }
+}
+
+/// Number of bytes between two reports while reading or writing.
+const STEP: u64 = 1 << 20;
+
+/// Number of entries between two reports while building indexes or encoding triples.
+pub(crate) const ENTRY_STEP: usize = 1 << 20;
+
+/// Reports to the observer, if there is one.
+pub(crate) fn report(
+    progress: Option<&dyn Progress>, phase: Phase, done: u64, total: Option<u64>,
+) -> Result<(), Cancelled> {
+    match progress.map(|p| p.report(phase, done, total)) {
+        Some(ControlFlow::Break(())) => Err(Cancelled(phase)),
+        _ => Ok(()),
+    }
+}
+
+/// Reader that reports the number of bytes read in the current phase.
+/// After cancellation, all reads fail so that parsing stops at the next read.
+pub(crate) struct ProgressRead<'a, R> {
+    inner: R,
+    progress: Option<&'a dyn Progress>,
+    phase: Phase,
+    total: Option<u64>,
+    pos: u64,
+    reported: u64,
+    cancelled: Option<Cancelled>,
+}
+
+impl<'a, R> ProgressRead<'a, R> {
+    pub(crate) const fn new(inner: R, progress: Option<&'a dyn Progress>, total: Option<u64>) -> Self {
+        ProgressRead { inner, progress, phase: Phase::Header, total, pos: 0, reported: 0, cancelled: None }
+    }
+
+    /// Starts a new phase and reports the current position.
+    pub(crate) fn phase(&mut self, phase: Phase) -> Result<(), Cancelled> {
+        self.phase = phase;
+        self.reported = self.pos;
+        report(self.progress, phase, self.pos, self.total)
+    }
+
+    /// The cancellation error if the operation was cancelled, otherwise the given error.
+    /// Needed because cancellation while reading surfaces as an IO error of the section that is read.
+    pub(crate) fn error<E: Into<crate::hdt::Error>>(&self, e: E) -> crate::hdt::Error {
+        self.cancelled.map_or_else(|| e.into(), Into::into)
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.pos += n as u64;
+        if self.pos - self.reported >= STEP {
+            self.reported = self.pos;
+            if let Err(c) = report(self.progress, self.phase, self.pos, self.total) {
+                self.cancelled = Some(c);
+            }
+        }
+    }
+
+    fn check(&self) -> std::io::Result<()> {
+        self.cancelled.map_or(Ok(()), |c| Err(c.into()))
+    }
+}
+
+impl<R: Read> Read for ProgressRead<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.check()?;
+        let n = self.inner.read(buf)?;
+        self.advance(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for ProgressRead<'_, R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.check()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.advance(amt);
+    }
+}
+
+impl<R: std::io::Seek> std::io::Seek for ProgressRead<'_, R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        self.reported = self.pos;
+        Ok(self.pos)
+    }
+}
+
+/// Writer that reports the number of bytes written in the [`Phase::Write`] phase.
+pub(crate) struct ProgressWrite<'a, W> {
+    inner: W,
+    progress: &'a dyn Progress,
+    pos: u64,
+    reported: u64,
+    pub(crate) cancelled: Option<Cancelled>,
+}
+
+impl<'a, W> ProgressWrite<'a, W> {
+    pub(crate) const fn new(inner: W, progress: &'a dyn Progress) -> Self {
+        ProgressWrite { inner, progress, pos: 0, reported: 0, cancelled: None }
+    }
+}
+
+impl<W: Write> Write for ProgressWrite<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(c) = self.cancelled {
+            return Err(c.into());
+        }
+        let n = self.inner.write(buf)?;
+        self.pos += n as u64;
+        if self.pos - self.reported >= STEP {
+            self.reported = self.pos;
+            if let Err(c) = report(Some(self.progress), Phase::Write, self.pos, None) {
+                self.cancelled = Some(c);
+            }
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hdt;
+    use crate::hdt::tests::snikmeta;
+    use crate::hdt::{Error, ReadOptions};
+    use crate::tests::init;
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+
+    fn read(progress: &dyn Progress) -> crate::hdt::Result<Hdt> {
+        let file = std::fs::File::open("tests/resources/snikmeta.hdt")?;
+        let options = ReadOptions { progress: Some(progress), ..Default::default() };
+        Hdt::read_with_options(std::io::BufReader::new(file), options)
+    }
+
+    #[test]
+    fn phases() -> color_eyre::Result<()> {
+        init();
+        let phases = Mutex::new(Vec::new());
+        let progress = |phase, _, _| {
+            phases.lock().unwrap().push(phase);
+            ControlFlow::Continue(())
+        };
+        read(&progress)?;
+        let mut phases = phases.into_inner()?;
+        phases.dedup();
+        assert_eq!(phases, [Phase::Header, Phase::Dictionary, Phase::Triples, Phase::Wavelet, Phase::OpIndex]);
+        Ok(())
+    }
+
+    #[test]
+    fn cancel() -> color_eyre::Result<()> {
+        init();
+        for cancel_at in [Phase::Dictionary, Phase::Wavelet, Phase::OpIndex] {
+            let progress =
+                |phase, _, _| if phase == cancel_at { ControlFlow::Break(()) } else { ControlFlow::Continue(()) };
+            assert!(matches!(read(&progress), Err(Error::Cancelled(Cancelled(p))) if p == cancel_at));
+        }
+        let hdt = snikmeta()?;
+        let cancel = |_, _, _| ControlFlow::Break(());
+        let mut buf = Vec::new();
+        assert!(matches!(
+            hdt.write_with_progress(&mut buf, &cancel),
+            Err(Error::Cancelled(Cancelled(Phase::Write)))
+        ));
+        buf.clear();
+        let count = |_, _, _| ControlFlow::Continue(());
+        hdt.write_with_progress(&mut buf, &count)?;
+        crate::hdt::tests::snikmeta_check(&Hdt::read(std::io::Cursor::new(buf))?)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn cancel_nt() {
+        init();
+        let progress =
+            |phase, _, _| if phase == Phase::Encode { ControlFlow::Break(()) } else { ControlFlow::Continue(()) };
+        let e = Hdt::read_nt_with_progress("tests/resources/empty.nt", &progress).unwrap_err();
+        assert_eq!(e.get_ref().and_then(|e| e.downcast_ref::<Cancelled>()), Some(&Cancelled(Phase::Encode)));
+    }
+}
@@ -0,0 +1,238 @@
+//! Python bindings, built as the native module `hdt_rs._hdt` of the Python package in the `python` directory.
+//! The package also contains a read-only [rdflib](https://rdflib.readthedocs.io) store, see the README.
+//!
+//! Terms are exchanged in the string format of the HDT dictionary:
+//! IRIs without angle brackets, blank nodes starting with `_:` and literals starting with a quotation mark.
+//! Query results at the ID level are returned as `array.array('I')` of flattened triple IDs,
+//! which NumPy can use without copying, e.g. with `numpy.frombuffer(ids, dtype=numpy.uint32).reshape(-1, 3)`.
+// arguments are extracted from Python objects as owned values
+#![allow(clippy::needless_pass_by_value)]
+use crate::owned::OwnedTriples;
+use crate::triples::TripleId;
+use crate::{Hdt, IdKind};
+use pyo3::buffer::PyBuffer;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+create_exception!(_hdt, HdtError, PyException, "Error reading, converting, writing or querying an HDT.");
+
+/// Converts an error to a Python exception whose message includes the chain of causes.
+fn error(e: impl Error) -> PyErr {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    HdtError::new_err(message)
+}
+
+fn id_kind(position: &str) -> PyResult<IdKind> {
+    match position {
+        "subject" => Ok(IdKind::Subject),
+        "predicate" => Ok(IdKind::Predicate),
+        "object" => Ok(IdKind::Object),
+        _ => Err(PyValueError::new_err(format!(
+            "invalid position {position:?}, expected \"subject\", \"predicate\" or \"object\""
+        ))),
+    }
+}
+
+/// Flattened triple IDs as `array.array('I')`, which is allocated with its final length and filled via its buffer.
+fn id_array(py: Python<'_>, ids: impl Iterator<Item = TripleId>) -> PyResult<Bound<'_, PyAny>> {
+    let ids: Vec<u32> = ids.flatten().collect();
+    let array = py.import("array")?.getattr("array")?.call1(("I", [0u32]))?.mul(ids.len())?;
+    PyBuffer::<u32>::get(&array)?.copy_from_slice(py, &ids)?;
+    Ok(array)
+}
+
+/// A loaded HDT, which can be shared between Python threads.
+#[pyclass(name = "Hdt", module = "hdt_rs", frozen)]
+pub struct PyHdt {
+    hdt: Arc<Hdt>,
+}
+
+impl PyHdt {
+    fn new(hdt: Hdt) -> Self {
+        Self { hdt: Arc::new(hdt) }
+    }
+}
+
+#[pymethods]
+impl PyHdt {
+    /// Loads an HDT file.
+    #[staticmethod]
+    fn read(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        py.detach(|| {
+            let file = File::open(&path).map_err(|e| HdtError::new_err(format!("{}: {e}", path.display())))?;
+            Hdt::read(BufReader::new(file)).map(Self::new).map_err(error)
+        })
+    }
+
+    /// Converts an N-Triples file to HDT.
+    #[staticmethod]
+    fn read_nt(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        py.detach(|| Hdt::read_nt(&path).map(Self::new).map_err(error))
+    }
+
+    /// Builds an HDT from an iterable of (subject, predicate, object) string triples.
+    /// The base IRI denotes the dataset in the header.
+    #[staticmethod]
+    fn from_triples(py: Python<'_>, triples: &Bound<'_, PyAny>, base_iri: &str) -> PyResult<Self> {
+        let triples = triples.try_iter()?.map(|t| t?.extract::<[String; 3]>()).collect::<PyResult<Vec<_>>>()?;
+        py.detach(|| Hdt::from_triples(triples, base_iri).map(Self::new).map_err(error))
+    }
+
+    /// Writes the HDT to a file.
+    fn write(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| {
+            let mut writer = BufWriter::new(File::create(&path).map_err(error)?);
+            self.hdt.write(&mut writer).map_err(error)?;
+            writer.flush().map_err(error)
+        })
+    }
+
+    /// Number of triples.
+    fn __len__(&self) -> usize {
+        self.hdt.triples.len()
+    }
+
+    /// Lazily iterates over the (subject, predicate, object) triples that fit the pattern, where `None` is a variable.
+    #[pyo3(signature = (subject=None, predicate=None, object=None))]
+    fn triples_with_pattern(
+        &self, subject: Option<&str>, predicate: Option<&str>, object: Option<&str>,
+    ) -> TripleIterator {
+        TripleIterator { inner: OwnedTriples::new(self.hdt.clone(), subject, predicate, object) }
+    }
+
+    /// IDs of the triples that fit the pattern, where `None` is a variable, flattened into an `array.array('I')`.
+    #[pyo3(signature = (subject=None, predicate=None, object=None))]
+    fn triple_ids_with_pattern<'py>(
+        &self, py: Python<'py>, subject: Option<&str>, predicate: Option<&str>, object: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        id_array(py, self.hdt.triple_ids_with_pattern(subject, predicate, object))
+    }
+
+    /// IDs of the triples that fit the ID pattern, where 0 is a variable, flattened into an `array.array('I')`.
+    #[pyo3(signature = (subject=0, predicate=0, object=0))]
+    fn triple_ids_with_id_pattern<'py>(
+        &self, py: Python<'py>, subject: u32, predicate: u32, object: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let dict = &self.hdt.dict;
+        let shared = dict.shared.num_strings();
+        let sizes = [
+            shared + dict.subjects.num_strings(),
+            dict.predicates.num_strings(),
+            shared + dict.objects.num_strings(),
+        ];
+        let pattern = [subject, predicate, object];
+        for ((id, size), kind) in pattern.into_iter().zip(sizes).zip(IdKind::KINDS) {
+            if id as usize > size {
+                return Err(HdtError::new_err(format!("{kind:?} ID {id} does not exist, there are only {size}")));
+            }
+        }
+        id_array(py, self.hdt.triple_ids_with_id_pattern(pattern))
+    }
+
+    /// ID of a term at the given position ("subject", "predicate" or "object"), or 0 if it does not exist.
+    fn string_to_id(&self, term: &str, position: &str) -> PyResult<u32> {
+        Ok(self.hdt.dict.string_to_id(term, id_kind(position)?))
+    }
+
+    /// Term with the given ID at the given position ("subject", "predicate" or "object").
+    fn id_to_string(&self, id: u32, position: &str) -> PyResult<String> {
+        self.hdt.dict.id_to_string(id, id_kind(position)?).map_err(error)
+    }
+
+    /// Terms of flattened triple IDs, such as those returned by [`PyHdt::triple_ids_with_pattern`], as a list of tuples.
+    fn ids_to_strings<'py>(&self, py: Python<'py>, ids: Vec<u32>) -> PyResult<Bound<'py, PyList>> {
+        if !ids.len().is_multiple_of(3) {
+            return Err(PyValueError::new_err(format!("the number of IDs {} is not a multiple of 3", ids.len())));
+        }
+        let list = PyList::empty(py);
+        for t in ids.chunks_exact(3) {
+            let mut terms = Vec::with_capacity(3);
+            for (&id, kind) in t.iter().zip(IdKind::KINDS) {
+                terms.push(self.hdt.dict.id_to_string(id, kind).map_err(error)?);
+            }
+            list.append(PyTuple::new(py, terms)?)?;
+        }
+        Ok(list)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Hdt with {} triples>", self.hdt.triples.len())
+    }
+}
+
+/// Lazy iterator over string triples, returned by `Hdt.triples_with_pattern`.
+#[pyclass(module = "hdt_rs")]
+pub struct TripleIterator {
+    inner: OwnedTriples,
+}
+
+#[pymethods]
+impl TripleIterator {
+    const fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<(String, String, String)> {
+        let [s, p, o] = self.inner.next()?;
+        Some((s.to_string(), p.to_string(), o.to_string()))
+    }
+}
+
+/// Evaluates a SPARQL query. Returns a list of dictionaries from variable names to terms for SELECT queries,
+/// a boolean for ASK queries and a list of (subject, predicate, object) tuples for CONSTRUCT and DESCRIBE queries.
+#[pyfunction]
+fn query<'py>(py: Python<'py>, hdt: &PyHdt, query: &str) -> PyResult<Bound<'py, PyAny>> {
+    use crate::sparql::term_to_hdt_bgp_str;
+    use spareval::QueryResults;
+    match crate::sparql::query(query, &hdt.hdt).map_err(error)? {
+        QueryResults::Boolean(b) => Ok(pyo3::types::PyBool::new(py, b).to_owned().into_any()),
+        QueryResults::Solutions(solutions) => {
+            let list = PyList::empty(py);
+            for solution in solutions {
+                let dict = PyDict::new(py);
+                for (variable, term) in solution.map_err(error)?.iter() {
+                    dict.set_item(variable.as_str(), term_to_hdt_bgp_str(term.clone()))?;
+                }
+                list.append(dict)?;
+            }
+            Ok(list.into_any())
+        }
+        QueryResults::Graph(triples) => {
+            let list = PyList::empty(py);
+            for triple in triples {
+                let triple = triple.map_err(error)?;
+                list.append((
+                    term_to_hdt_bgp_str(triple.subject.into()),
+                    triple.predicate.into_string(),
+                    term_to_hdt_bgp_str(triple.object),
+                ))?;
+            }
+            Ok(list.into_any())
+        }
+    }
+}
+
+#[pymodule]
+fn _hdt(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyHdt>()?;
+    m.add_class::<TripleIterator>()?;
+    m.add("HdtError", py.get_type::<HdtError>())?;
+    let sparql = PyModule::new(py, "sparql")?;
+    sparql.add_function(wrap_pyfunction!(query, &sparql)?)?;
+    m.add_submodule(&sparql)?;
+    Ok(())
+}
@@ -0,0 +1,488 @@
+//! RDFS entailment on top of an [`Hdt`], see [`Rdfs`].
+use crate::four_sect_dict::{ExtractError, FourSectDict};
+use crate::triples::{Id, ObjectIter, PredicateIter, PredicateObjectIter, SubjectIter, TripleId};
+use crate::vocab::{RDF_TYPE, RDFS_DOMAIN, RDFS_RANGE, RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF};
+use crate::{Hdt, IdKind};
+use log::error;
+use qwt::AccessUnsigned;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::iter;
+use std::ops::Range;
+use std::sync::Arc;
+
+type StringTriple = [Arc<str>; 3];
+
+/// Read-only view of an [`Hdt`] that includes triples entailed by RDFS.
+/// Supports the transitivity of `rdfs:subClassOf` (rdfs11) and `rdfs:subPropertyOf` (rdfs5),
+/// inheritance of types (rdfs9) and properties (rdfs7) as well as typing via `rdfs:domain` (rdfs2) and `rdfs:range` (rdfs3).
+/// Axiomatic triples and the reflexive rules rdfs6, rdfs8, rdfs10, rdfs12 and rdfs13 are not included.
+/// The schema closure tables are built once on creation, instance level entailments are computed lazily in ID space.
+///
+/// The ID space is extended in two ways to cover terms that do not occur in the required position in the HDT dictionary:
+/// Properties that are only reachable via `rdfs:subPropertyOf` get predicate IDs after the last predicate ID of the dictionary
+/// and terms that only occur as objects get the subject ID of their object ID plus the number of subject-only terms,
+/// so that they can be typed by `rdfs:range`.
+/// Use [`Rdfs::string_to_id`] and [`Rdfs::id_to_string`] instead of the dictionary to translate IDs of entailed triples.
+/// # Example
+/// All instances of meta:EntityType including those of its subclasses:
+/// ```
+/// let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+/// let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+/// let rdfs = hdt::rdfs::Rdfs::new(&hdt).unwrap();
+/// let rdf_type = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+/// let entity_type = "http://www.snik.eu/ontology/meta/EntityType";
+/// for [s, _, _] in rdfs.triples_with_pattern(None, Some(rdf_type), Some(entity_type)) {
+///     println!("{s}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Rdfs<'a> {
+    hdt: &'a Hdt,
+    /// properties without a predicate ID in the dictionary, which are numbered consecutively after the last one
+    extra_predicates: Vec<String>,
+    /// predicate ID of rdf:type, which is always defined because domain and range entail type triples
+    type_id: Id,
+    /// predicate ID of rdfs:subClassOf or 0 if it is not used
+    sub_class_id: Id,
+    /// predicate ID of rdfs:subPropertyOf or 0 if it is not used
+    sub_property_id: Id,
+    /// transitive closure of rdfs:subClassOf from subject ID to object IDs
+    super_classes: BTreeMap<Id, BTreeSet<Id>>,
+    /// object ID of a class to the object IDs of its direct and indirect subclasses
+    sub_classes: HashMap<Id, BTreeSet<Id>>,
+    /// transitive closure of rdfs:subPropertyOf from subject ID to object IDs
+    super_property_closure: BTreeMap<Id, BTreeSet<Id>>,
+    /// predicate ID of a property to the predicate IDs of its direct and indirect subproperties
+    sub_properties: HashMap<Id, BTreeSet<Id>>,
+    /// predicate ID to the object IDs of the classes that its subjects are instances of
+    domains: HashMap<Id, BTreeSet<Id>>,
+    /// predicate ID to the object IDs of the classes that its objects are instances of
+    ranges: HashMap<Id, BTreeSet<Id>>,
+    /// object ID of a class to the predicate IDs whose subjects are instances of it
+    domain_of: HashMap<Id, BTreeSet<Id>>,
+    /// object ID of a class to the predicate IDs whose objects are instances of it
+    range_of: HashMap<Id, BTreeSet<Id>>,
+    /// object IDs of literals, which cannot be typed by rdfs:range
+    literals: Range<Id>,
+}
+
+/// Transitive closure of the given predicate from subject ID to object IDs.
+/// Only objects in the shared section can continue a path because the others never occur as subjects.
+fn closure(hdt: &Hdt, p: Id) -> BTreeMap<Id, BTreeSet<Id>> {
+    if p == 0 {
+        return BTreeMap::new();
+    }
+    let shared = hdt.dict.shared.num_strings() as Id;
+    let mut direct = BTreeMap::<Id, Vec<Id>>::new();
+    for [s, _, o] in PredicateIter::new(&hdt.triples, p) {
+        direct.entry(s).or_default().push(o);
+    }
+    direct
+        .iter()
+        .map(|(&s, os)| {
+            let mut reachable = BTreeSet::new();
+            let mut stack = os.clone();
+            while let Some(o) = stack.pop() {
+                if reachable.insert(o) && o <= shared {
+                    stack.extend(direct.get(&o).into_iter().flatten());
+                }
+            }
+            (s, reachable)
+        })
+        .collect()
+}
+
+/// Predicate ID of the given property, which is appended to the extra predicates if it is not in the dictionary.
+fn predicate_id(dict: &FourSectDict, extra: &mut Vec<String>, property: String) -> Id {
+    let id = dict.string_to_id(&property, IdKind::Predicate);
+    if id != 0 {
+        return id;
+    }
+    let i = extra.iter().position(|e| *e == property).unwrap_or_else(|| {
+        extra.push(property);
+        extra.len() - 1
+    });
+    dict.predicates.num_strings() as Id + 1 + i as Id
+}
+
+impl<'a> Rdfs<'a> {
+    /// Builds the closure tables of the RDFS schema contained in the given HDT.
+    pub fn new(hdt: &'a Hdt) -> Result<Self, ExtractError> {
+        let dict = &hdt.dict;
+        let shared = dict.shared.num_strings() as Id;
+        let num_predicates = dict.predicates.num_strings() as Id;
+        let mut extra_predicates = Vec::new();
+        let type_id = predicate_id(dict, &mut extra_predicates, RDF_TYPE.to_owned());
+        let [sub_class_id, sub_property_id, domain_id, range_id] =
+            [RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF, RDFS_DOMAIN, RDFS_RANGE]
+                .map(|p| dict.string_to_id(p, IdKind::Predicate));
+
+        let super_classes = closure(hdt, sub_class_id);
+        let mut sub_classes = HashMap::<Id, BTreeSet<Id>>::new();
+        // subclasses outside of the shared section are never objects of rdf:type, rdfs:domain or rdfs:range
+        for (&s, supers) in super_classes.range(..=shared) {
+            for &c in supers.iter().filter(|&&c| c != s) {
+                sub_classes.entry(c).or_default().insert(s);
+            }
+        }
+
+        let super_property_closure = closure(hdt, sub_property_id);
+        let mut sub_properties = HashMap::<Id, BTreeSet<Id>>::new();
+        for (&s, supers) in &super_property_closure {
+            let p = predicate_id(dict, &mut extra_predicates, dict.id_to_string(s, IdKind::Subject)?);
+            for &o in supers {
+                let q = predicate_id(dict, &mut extra_predicates, dict.id_to_string(o, IdKind::Object)?);
+                if q != p {
+                    sub_properties.entry(q).or_default().insert(p);
+                }
+            }
+        }
+
+        let mut rdfs = Rdfs {
+            hdt,
+            extra_predicates,
+            type_id,
+            sub_class_id,
+            sub_property_id,
+            super_classes,
+            sub_classes,
+            super_property_closure,
+            sub_properties,
+            domains: HashMap::new(),
+            ranges: HashMap::new(),
+            domain_of: HashMap::new(),
+            range_of: HashMap::new(),
+            literals: dict.literal_ids(),
+        };
+        // domain and range are inherited by subproperties and entail the superclasses as well
+        for (schema_id, is_domain) in [(domain_id, true), (range_id, false)] {
+            if schema_id == 0 {
+                continue;
+            }
+            for [s, _, c] in PredicateIter::new(&hdt.triples, schema_id) {
+                let p = predicate_id(dict, &mut rdfs.extra_predicates, dict.id_to_string(s, IdKind::Subject)?);
+                let classes: Vec<Id> = rdfs.class_and_supers(c).collect();
+                let subs = rdfs.sub_properties.get(&p).into_iter().flatten().copied();
+                // properties without triples cannot entail anything
+                for target in iter::once(p).chain(subs).filter(|&t| t <= num_predicates) {
+                    let (by_property, by_class) = if is_domain {
+                        (&mut rdfs.domains, &mut rdfs.domain_of)
+                    } else {
+                        (&mut rdfs.ranges, &mut rdfs.range_of)
+                    };
+                    by_property.entry(target).or_default().extend(&classes);
+                    for &class in &classes {
+                        by_class.entry(class).or_default().insert(target);
+                    }
+                }
+            }
+        }
+        Ok(rdfs)
+    }
+
+    /// The underlying HDT.
+    pub const fn hdt(&self) -> &'a Hdt {
+        self.hdt
+    }
+
+    /// Highest predicate ID including the properties that are not in the dictionary.
+    pub const fn max_predicate_id(&self) -> Id {
+        self.hdt.dict.predicates.num_strings() as Id + self.extra_predicates.len() as Id
+    }
+
+    /// Highest subject ID including the terms that only occur as objects.
+    pub const fn max_subject_id(&self) -> Id {
+        let dict = &self.hdt.dict;
+        dict.shared.num_strings() as Id + dict.subjects.num_strings() as Id + dict.objects.num_strings() as Id
+    }
+
+    /// Number of terms that are only used as subjects, which is the offset between an object only term and its subject ID.
+    const fn num_subject_only(&self) -> Id {
+        self.hdt.dict.subjects.num_strings() as Id
+    }
+
+    /// Object ID of the term with the given subject ID, if it occurs as an object.
+    fn subject_as_object(&self, s: Id) -> Option<Id> {
+        let shared = self.hdt.dict.shared.num_strings() as Id;
+        if s <= shared {
+            Some(s)
+        } else if s <= shared + self.num_subject_only() {
+            None
+        } else {
+            Some(s - self.num_subject_only()).filter(|o| !self.literals.contains(o))
+        }
+    }
+
+    /// Subject ID of the term with the given object ID, `None` for literals.
+    fn object_as_subject(&self, o: Id) -> Option<Id> {
+        if o <= self.hdt.dict.shared.num_strings() as Id {
+            Some(o)
+        } else if self.literals.contains(&o) {
+            None
+        } else {
+            Some(o + self.num_subject_only())
+        }
+    }
+
+    /// The class with the given object ID and all its superclasses.
+    fn class_and_supers(&self, c: Id) -> impl Iterator<Item = Id> + '_ {
+        let supers = if c <= self.hdt.dict.shared.num_strings() as Id { self.super_classes.get(&c) } else { None };
+        iter::once(c).chain(supers.into_iter().flatten().copied())
+    }
+
+    /// Get the ID for a given string in the extended ID space or 0 if not found.
+    pub fn string_to_id(&self, s: &str, id_kind: IdKind) -> Id {
+        let dict = &self.hdt.dict;
+        match id_kind {
+            IdKind::Subject => match dict.string_to_id(s, IdKind::Subject) {
+                0 => self.object_as_subject(dict.string_to_id(s, IdKind::Object)).filter(|&s| s != 0).unwrap_or(0),
+                id => id,
+            },
+            IdKind::Predicate => match dict.string_to_id(s, IdKind::Predicate) {
+                0 => self
+                    .extra_predicates
+                    .iter()
+                    .position(|e| e == s)
+                    .map_or(0, |i| dict.predicates.num_strings() as Id + 1 + i as Id),
+                id => id,
+            },
+            IdKind::Object => dict.string_to_id(s, IdKind::Object),
+        }
+    }
+
+    /// Get the string value of a given ID in the extended ID space.
+    pub fn id_to_string(&self, id: Id, id_kind: IdKind) -> Result<String, ExtractError> {
+        let dict = &self.hdt.dict;
+        match id_kind {
+            IdKind::Subject if id > dict.shared.num_strings() as Id + self.num_subject_only() => {
+                dict.id_to_string(id - self.num_subject_only(), IdKind::Object)
+            }
+            IdKind::Predicate if id > dict.predicates.num_strings() as Id => {
+                match self.extra_predicates.get((id - dict.predicates.num_strings() as Id - 1) as usize) {
+                    Some(p) => Ok(p.clone()),
+                    None => dict.id_to_string(id, id_kind),
+                }
+            }
+            _ => dict.id_to_string(id, id_kind),
+        }
+    }
+
+    /// Get all triples including entailed ones that fit the given triple patterns, where `None` stands for a variable.
+    pub fn triples_with_pattern<'b>(
+        &'b self, sp: Option<&'b str>, pp: Option<&'b str>, op: Option<&'b str>,
+    ) -> Box<dyn Iterator<Item = StringTriple> + 'b> {
+        let pattern: [Option<Id>; 3] =
+            [(0, sp), (1, pp), (2, op)].map(|(i, x)| x.map(|x| self.string_to_id(x, IdKind::KINDS[i])));
+        if pattern.contains(&Some(0)) {
+            return Box::new(iter::empty());
+        }
+        Box::new(self.triple_ids_with_id_pattern(pattern.map(|x| x.unwrap_or(0))).filter_map(move |t| {
+            match [0, 1, 2].map(|i| self.id_to_string(t[i], IdKind::KINDS[i])) {
+                [Ok(s), Ok(p), Ok(o)] => Some([s, p, o].map(Arc::from)),
+                [Err(e), _, _] | [_, Err(e), _] | [_, _, Err(e)] => {
+                    error!("Error translating entailed triple {t:?}: {e}");
+                    None
+                }
+            }
+        }))
+    }
+
+    /// Get all triple IDs including entailed ones in the extended ID space that fit the given pattern, where 0 stands for a variable.
+    /// Does not contain duplicates.
+    pub fn triple_ids_with_id_pattern(&self, pattern: TripleId) -> Box<dyn Iterator<Item = TripleId> + '_> {
+        let [s, p, o] = pattern;
+        if p == 0 {
+            Box::new((1..=self.max_predicate_id()).flat_map(move |p| self.triple_ids_with_id_pattern([s, p, o])))
+        } else if p == self.type_id {
+            self.types(s, o)
+        } else if p == self.sub_class_id {
+            closure_ids(&self.super_classes, [s, p, o])
+        } else if p == self.sub_property_id {
+            closure_ids(&self.super_property_closure, [s, p, o])
+        } else {
+            self.property(s, p, o)
+        }
+    }
+
+    /// Triples of the given property and its subproperties.
+    fn property(&self, s: Id, p: Id, o: Id) -> Box<dyn Iterator<Item = TripleId> + '_> {
+        let dict = &self.hdt.dict;
+        // terms that only occur as objects only have entailed types
+        if s > dict.shared.num_strings() as Id + self.num_subject_only() {
+            return Box::new(iter::empty());
+        }
+        let num_predicates = dict.predicates.num_strings() as Id;
+        let subs = self.sub_properties.get(&p).into_iter().flatten().copied();
+        let ps: Vec<Id> = iter::once(p).chain(subs).filter(|&p| p <= num_predicates).collect();
+        match ps.as_slice() {
+            [] => Box::new(iter::empty()),
+            [q] => Box::new(self.hdt.triple_ids_with_id_pattern([s, *q, o]).map(move |[s, _, o]| [s, p, o])),
+            _ => {
+                let mut seen = HashSet::new();
+                Box::new(
+                    ps.into_iter()
+                        .flat_map(move |q| self.hdt.triple_ids_with_id_pattern([s, q, o]))
+                        .filter(move |t| seen.insert([t[0], t[2]]))
+                        .map(move |[s, _, o]| [s, p, o]),
+                )
+            }
+        }
+    }
+
+    /// rdf:type triples, either explicit or entailed.
+    fn types(&self, s: Id, o: Id) -> Box<dyn Iterator<Item = TripleId> + '_> {
+        let t = self.type_id;
+        match (s, o) {
+            (1.., 1..) => Box::new(iter::once([s, t, o]).filter(move |_| self.types_of(s).contains(&o))),
+            (1.., 0) => Box::new(self.types_of(s).into_iter().map(move |c| [s, t, c])),
+            (0, 1..) => self.instances_of(o),
+            (0, 0) => Box::new(
+                (1..=self.max_subject_id())
+                    .flat_map(move |s| self.types_of(s).into_iter().map(move |c| [s, t, c])),
+            ),
+        }
+    }
+
+    /// Object IDs of all types of the given subject.
+    fn types_of(&self, s: Id) -> BTreeSet<Id> {
+        let triples = &self.hdt.triples;
+        let mut types = BTreeSet::new();
+        if s <= self.hdt.dict.shared.num_strings() as Id + self.num_subject_only() {
+            if self.type_id <= self.hdt.dict.predicates.num_strings() as Id {
+                for [_, _, c] in SubjectIter::with_pattern(triples, [s, self.type_id, 0]) {
+                    types.extend(self.class_and_supers(c));
+                }
+            }
+            if !self.domains.is_empty() {
+                for pos_y in triples.find_y(s - 1)..=triples.last_y(s - 1) {
+                    let p = triples.wavelet_y.get(pos_y).unwrap() as Id;
+                    types.extend(self.domains.get(&p).into_iter().flatten());
+                }
+            }
+        }
+        if let Some(o) = self.subject_as_object(s).filter(|_| !self.ranges.is_empty()) {
+            for [_, p, _] in ObjectIter::new(triples, o) {
+                types.extend(self.ranges.get(&p).into_iter().flatten());
+            }
+        }
+        types
+    }
+
+    /// rdf:type triples of all instances of the class with the given object ID.
+    fn instances_of(&self, c: Id) -> Box<dyn Iterator<Item = TripleId> + '_> {
+        let triples = &self.hdt.triples;
+        let t = self.type_id;
+        let explicit: Box<dyn Iterator<Item = Id>> = if t <= self.hdt.dict.predicates.num_strings() as Id {
+            let classes = iter::once(c).chain(self.sub_classes.get(&c).into_iter().flatten().copied());
+            Box::new(classes.flat_map(move |c| PredicateObjectIter::new(triples, t, c)))
+        } else {
+            Box::new(iter::empty())
+        };
+        let domain = self
+            .domain_of
+            .get(&c)
+            .into_iter()
+            .flatten()
+            .flat_map(|&p| PredicateIter::new(triples, p).map(|t| t[0]));
+        let range = self
+            .range_of
+            .get(&c)
+            .into_iter()
+            .flatten()
+            .flat_map(|&p| PredicateIter::new(triples, p).filter_map(|t| self.object_as_subject(t[2])));
+        let mut seen = HashSet::new();
+        Box::new(explicit.chain(domain).chain(range).filter(move |&s| seen.insert(s)).map(move |s| [s, t, c]))
+    }
+}
+
+/// Triples of a closure table that fit the given pattern.
+fn closure_ids(
+    closure: &BTreeMap<Id, BTreeSet<Id>>, [s, p, o]: TripleId,
+) -> Box<dyn Iterator<Item = TripleId> + '_> {
+    let pairs: Box<dyn Iterator<Item = (&Id, &BTreeSet<Id>)>> =
+        if s == 0 { Box::new(closure.iter()) } else { Box::new(closure.get_key_value(&s).into_iter()) };
+    Box::new(
+        pairs.flat_map(move |(&s, os)| os.iter().filter(move |&&x| o == 0 || x == o).map(move |&o| [s, p, o])),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn entailment() -> color_eyre::Result<()> {
+        init();
+        let hdt = snikmeta()?;
+        let rdfs = Rdfs::new(&hdt)?;
+        let all: Vec<TripleId> = rdfs.triple_ids_with_id_pattern([0, 0, 0]).collect();
+        let unique: HashSet<TripleId> = all.iter().copied().collect();
+        assert_eq!(all.len(), unique.len(), "duplicate entailed triples");
+        // all explicit triples are included
+        for t in hdt.triple_ids_with_id_pattern([0, 0, 0]) {
+            assert!(unique.contains(&t), "missing explicit triple {t:?}");
+        }
+        assert!(all.len() > 328);
+        // subClassOf is transitive and types are inherited
+        let sc = rdfs.string_to_id(RDFS_SUB_CLASS_OF, IdKind::Predicate);
+        let t = rdfs.string_to_id(RDF_TYPE, IdKind::Predicate);
+        for &[a, _, b] in all.iter().filter(|t| t[1] == sc) {
+            for &[_, _, c] in all.iter().filter(|x| x[1] == sc && x[0] == b) {
+                assert!(unique.contains(&[a, sc, c]), "subClassOf not transitive");
+            }
+            for &[x, _, _] in all.iter().filter(|x| x[1] == t && x[2] == a) {
+                assert!(unique.contains(&[x, t, b]), "type not inherited");
+            }
+        }
+        // every pattern is consistent with the full closure
+        for &[s, p, o] in all.iter().step_by(7) {
+            for pattern in [[s, 0, 0], [0, p, 0], [0, 0, o], [s, p, 0], [0, p, o], [s, 0, o], [s, p, o]] {
+                let expected =
+                    unique.iter().filter(|t| (0..3).all(|i| pattern[i] == 0 || pattern[i] == t[i])).count();
+                assert_eq!(rdfs.triple_ids_with_id_pattern(pattern).count(), expected, "pattern {pattern:?}");
+            }
+        }
+        // string API
+        let entity_type = "http://www.snik.eu/ontology/meta/EntityType";
+        let explicit = hdt.triples_with_pattern(None, Some(RDF_TYPE), Some(entity_type)).count();
+        let entailed: Vec<_> = rdfs.triples_with_pattern(None, Some(RDF_TYPE), Some(entity_type)).collect();
+        assert!(entailed.len() >= explicit);
+        for [s, _, _] in &entailed {
+            assert_eq!(rdfs.triples_with_pattern(Some(s), Some(RDF_TYPE), Some(entity_type)).count(), 1);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn domain_range() -> color_eyre::Result<()> {
+        init();
+        let ex = |s: &str| format!("http://example.org/{s}");
+        let triples = [
+            [ex("hasPart"), RDFS_SUB_PROPERTY_OF.to_owned(), ex("related")],
+            [ex("related"), RDFS_DOMAIN.to_owned(), ex("Thing")],
+            [ex("hasPart"), RDFS_RANGE.to_owned(), ex("Part")],
+            [ex("Part"), RDFS_SUB_CLASS_OF.to_owned(), ex("Thing")],
+            [ex("a"), ex("hasPart"), ex("b")],
+            [ex("a"), ex("hasPart"), "\"literal\"".to_owned()],
+        ];
+        let hdt = Hdt::from_triples(triples, "http://example.org/")?;
+        let rdfs = Rdfs::new(&hdt)?;
+        let count = |s: Option<&str>, p: Option<&str>, o: Option<&str>| rdfs.triples_with_pattern(s, p, o).count();
+        let [a, b, related, thing, part] = ["a", "b", "related", "Thing", "Part"].map(ex);
+        // subproperty without own triples
+        assert_eq!(count(Some(&a), Some(&related), None), 2);
+        // domain inherited by the subproperty
+        assert_eq!(count(Some(&a), Some(RDF_TYPE), None), 1);
+        // range on an object that is never a subject, including its superclass
+        assert_eq!(count(Some(&b), Some(RDF_TYPE), None), 2);
+        assert_eq!(count(None, Some(RDF_TYPE), Some(&thing)), 2);
+        assert_eq!(count(None, Some(RDF_TYPE), Some(&part)), 1);
+        // literals are not typed
+        assert_eq!(count(None, Some(RDF_TYPE), None), 3);
+        Ok(())
+    }
+}
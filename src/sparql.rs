@@ -1,9 +1,18 @@
-use crate::Hdt;
-use spareval::{InternalQuad, QueryEvaluationError, QueryEvaluator, QueryableDataset};
-use spargebra::SparqlParser;
-use spargebra::term::{BlankNode, NamedNode, Term};
+use crate::describe::DescribeOptions;
+use crate::hdt::{Direction, TripleCache};
+use crate::triples::Id;
+use crate::{Hdt, IdKind};
+use spareval::{
+    InternalQuad, QueryEvaluationError, QueryEvaluator, QueryResults, QueryTripleIter, QueryableDataset,
+};
+use spargebra::algebra::{GraphPattern, PropertyPathExpression};
+use spargebra::term::{BlankNode, GroundTerm, NamedNode, NamedOrBlankNode, Term, TermPattern, Triple};
+use spargebra::{Query, SparqlParser};
+use std::collections::HashSet;
 use std::io::{Error, ErrorKind};
+use std::iter;
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// Create the correct term for a given resource string.
 /// Slow, use the appropriate method if you know which type (Literal, URI, or blank node) the string has.
@@ -34,7 +43,8 @@ fn hdt_bgp_str_to_term(s: &str) -> Result<Term, Error> {
 }
 
 /// Convert triple string formats from OxRDF to HDT.
-fn term_to_hdt_bgp_str(term: Term) -> String {
+/// Useful for turning the results of CONSTRUCT and DESCRIBE queries into input for [`Hdt::from_triples`].
+pub fn term_to_hdt_bgp_str(term: Term) -> String {
     match term {
         Term::NamedNode(named_node) => named_node.into_string(),
         Term::Literal(literal) => literal.to_string(),
@@ -76,12 +86,312 @@ impl<'a> QueryableDataset<'a> for &'a Hdt {
     }
 }
 
-pub fn query<'a>(q: &str, hdt: &'a Hdt) -> Result<spareval::QueryResults<'a>, QueryEvaluationError> {
-    let query = SparqlParser::new().parse_query(q)?;
+/// Several HDT files combined into a single default graph, which is the union of all of them.
+/// Named graphs are not supported.
+#[derive(Clone, Copy, Debug)]
+pub struct HdtUnion<'a>(pub &'a [Hdt]);
+
+impl<'a> QueryableDataset<'a> for HdtUnion<'a> {
+    type InternalTerm = String;
+    type Error = Error;
+
+    fn internal_quads_for_pattern(
+        &self, subject: Option<&String>, predicate: Option<&String>, object: Option<&String>,
+        graph_name: Option<Option<&String>>,
+    ) -> impl Iterator<Item = Result<InternalQuad<Self::InternalTerm>, Error>> + use<'a> {
+        if let Some(Some(graph_name)) = graph_name {
+            let e =
+                Error::new(ErrorKind::InvalidData, format!("HDT does not support named graph: {graph_name:?}"));
+            return Box::new(iter::once(Err(e))) as Box<dyn Iterator<Item = _>>;
+        }
+        let hdts = self.0;
+        let pattern = [subject, predicate, object].map(Option::<&String>::cloned);
+        Box::new(hdts.iter().enumerate().flat_map(move |(i, hdt)| {
+            let mut cache = TripleCache::default();
+            hdt.id_pattern(pattern.each_ref().map(Option::as_deref))
+                .into_iter()
+                .flat_map(|pattern| hdt.triple_ids_with_id_pattern(pattern))
+                .map(move |t| cache.translate(&hdt.dict, t).map_err(|e| Error::new(ErrorKind::InvalidData, e)))
+                // a triple that occurs in several files is only contained once in the union
+                .filter(move |r| r.as_ref().map_or(true, |t| !hdts[..i].iter().any(|h| contains(h, t))))
+                .map(|r| {
+                    r.map(|[s, p, o]| InternalQuad {
+                        subject: s.to_string(),
+                        predicate: p.to_string(),
+                        object: o.to_string(),
+                        graph_name: None,
+                    })
+                })
+        }))
+    }
+
+    fn internalize_term(&self, term: Term) -> Result<String, Error> {
+        Ok(term_to_hdt_bgp_str(term))
+    }
+
+    fn externalize_term(&self, term: String) -> Result<Term, Error> {
+        hdt_bgp_str_to_term(&term)
+    }
+}
+
+/// Whether the HDT contains the given triple.
+fn contains(hdt: &Hdt, triple: &[Arc<str>; 3]) -> bool {
+    hdt.id_pattern(triple.each_ref().map(|x| Some(x.as_ref())))
+        .is_some_and(|pattern| hdt.triple_ids_with_id_pattern(pattern).next().is_some())
+}
+
+/// Predicates, direction and depth bounds of a `*`, `+` or `?` path over a set of predicates with a uniform direction,
+/// such as `rdfs:subClassOf*` or `^(skos:broader|skos:broaderTransitive)+`.
+struct ClosurePath<'p> {
+    predicates: Vec<&'p str>,
+    reverse: bool,
+    min_depth: usize,
+    max_depth: Option<usize>,
+}
+
+/// Collects the predicates of an alternative of possibly inverse predicates and returns whether they are inverse.
+fn path_predicates<'p>(path: &'p PropertyPathExpression, predicates: &mut Vec<&'p str>) -> Option<bool> {
+    match path {
+        PropertyPathExpression::NamedNode(p) => {
+            predicates.push(p.as_str());
+            Some(false)
+        }
+        PropertyPathExpression::Reverse(inner) => path_predicates(inner, predicates).map(|r| !r),
+        PropertyPathExpression::Alternative(a, b) => {
+            let ra = path_predicates(a, predicates)?;
+            (path_predicates(b, predicates)? == ra).then_some(ra)
+        }
+        _ => None,
+    }
+}
+
+fn closure_path(path: &PropertyPathExpression) -> Option<ClosurePath<'_>> {
+    let (inner, min_depth, max_depth) = match path {
+        PropertyPathExpression::ZeroOrMore(inner) => (inner, 0, None),
+        PropertyPathExpression::OneOrMore(inner) => (inner, 1, None),
+        PropertyPathExpression::ZeroOrOne(inner) => (inner, 0, Some(1)),
+        PropertyPathExpression::Reverse(inner) => {
+            return closure_path(inner).map(|c| ClosurePath { reverse: !c.reverse, ..c });
+        }
+        _ => return None,
+    };
+    let mut predicates = Vec::new();
+    let reverse = path_predicates(inner, &mut predicates)?;
+    Some(ClosurePath { predicates, reverse, min_depth, max_depth })
+}
+
+/// Evaluates a closure path with at least one constant end using [`Hdt::reachable`], or [`reachable_union`] for several HDT files,
+/// and returns the solutions as a VALUES pattern.
+/// Returns `None` if the pattern is not supported, in which case it is left to the generic evaluator.
+fn evaluate_path(
+    hdts: &[Hdt], subject: &TermPattern, path: &PropertyPathExpression, object: &TermPattern,
+) -> Option<GraphPattern> {
+    let c = closure_path(path)?;
+    let (from, to) = if c.reverse { (object, subject) } else { (subject, object) };
+    let constant = |t: &TermPattern| match t {
+        TermPattern::NamedNode(n) => Some(Term::from(n.clone())),
+        TermPattern::Literal(l) => Some(Term::from(l.clone())),
+        _ => None,
+    };
+    let (start, other, direction, [start_kind, target_kind]) = match (constant(from), constant(to)) {
+        (Some(start), _) => (start, to, Direction::Forward, [IdKind::Subject, IdKind::Object]),
+        (None, Some(start)) => (start, from, Direction::Backward, [IdKind::Object, IdKind::Subject]),
+        (None, None) => return None,
+    };
+    let start = term_to_hdt_bgp_str(start);
+    let mut nodes = Vec::new();
+    // like the generic evaluator, the zero length path only matches terms that occur as subject or object
+    let in_graph = hdts.iter().any(|hdt| {
+        hdt.dict.string_to_id(&start, start_kind) != 0 || hdt.dict.string_to_id(&start, target_kind) != 0
+    });
+    if c.min_depth == 0 && in_graph {
+        nodes.push(start.clone());
+    }
+    let reached = match hdts {
+        [hdt] => {
+            let start_id = hdt.dict.string_to_id(&start, start_kind);
+            let pids: Vec<_> = c.predicates.iter().map(|p| hdt.dict.string_to_id(p, IdKind::Predicate)).collect();
+            hdt.reachable(start_id, &pids, direction, c.min_depth.max(1), c.max_depth)
+                .into_iter()
+                .map(|id| hdt.dict.id_to_string(id, target_kind).ok())
+                .collect::<Option<Vec<_>>>()?
+        }
+        _ => reachable_union(hdts, &start, &c.predicates, direction, c.max_depth)?,
+    };
+    for node in reached {
+        if node != start || c.min_depth > 0 {
+            nodes.push(node);
+        }
+    }
+    if let TermPattern::Variable(v) = other {
+        let mut bindings = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            // blank nodes cannot be used in VALUES
+            let term = match hdt_bgp_str_to_term(&node).ok()? {
+                Term::NamedNode(n) => GroundTerm::NamedNode(n),
+                Term::Literal(l) => GroundTerm::Literal(l),
+                Term::BlankNode(_) => return None,
+            };
+            bindings.push(vec![Some(term)]);
+        }
+        Some(GraphPattern::Values { variables: vec![v.clone()], bindings })
+    } else {
+        let other = term_to_hdt_bgp_str(constant(other)?);
+        let bindings = if nodes.contains(&other) { vec![vec![]] } else { vec![] };
+        Some(GraphPattern::Values { variables: vec![], bindings })
+    }
+}
+
+/// The terms reachable from the start term within the maximum depth over the union of the HDT files,
+/// like [`Hdt::reachable`] with a minimum depth of 1.
+/// Paths may continue in another file, so nodes are identified by their strings instead of their IDs.
+/// Returns `None` if an ID cannot be translated.
+fn reachable_union(
+    hdts: &[Hdt], start: &str, predicates: &[&str], direction: Direction, max_depth: Option<usize>,
+) -> Option<Vec<String>> {
+    let [source_kind, target_kind] = match direction {
+        Direction::Forward => [IdKind::Subject, IdKind::Object],
+        Direction::Backward => [IdKind::Object, IdKind::Subject],
+    };
+    let pids: Vec<Vec<Id>> = hdts
+        .iter()
+        .map(|hdt| {
+            predicates.iter().map(|p| hdt.dict.string_to_id(p, IdKind::Predicate)).filter(|&p| p != 0).collect()
+        })
+        .collect();
+    let mut visited = HashSet::new();
+    let mut reached = Vec::new();
+    let mut frontier = vec![start.to_owned()];
+    let mut depth = 0;
+    while !frontier.is_empty() && max_depth.is_none_or(|max| depth < max) {
+        depth += 1;
+        let mut next = Vec::new();
+        for (hdt, pids) in hdts.iter().zip(&pids) {
+            for node in &frontier {
+                let id = hdt.dict.string_to_id(node, source_kind);
+                if id == 0 {
+                    continue;
+                }
+                for &p in pids {
+                    let pattern = match direction {
+                        Direction::Forward => [id, p, 0],
+                        Direction::Backward => [0, p, id],
+                    };
+                    for t in hdt.triple_ids_with_id_pattern(pattern) {
+                        let n =
+                            hdt.dict.id_to_string(if pattern[0] == 0 { t[0] } else { t[2] }, target_kind).ok()?;
+                        if visited.insert(n.clone()) {
+                            reached.push(n.clone());
+                            next.push(n);
+                        }
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+    Some(reached)
+}
+
+/// Replaces transitive property paths with a constant end by their solutions, which are computed natively in ID space.
+/// Paths inside GRAPH, SERVICE and EXISTS are not changed.
+fn evaluate_paths(pattern: &mut GraphPattern, hdts: &[Hdt]) {
+    match pattern {
+        GraphPattern::Path { subject, path, object } => {
+            if let Some(values) = evaluate_path(hdts, subject, path, object) {
+                *pattern = values;
+            }
+        }
+        GraphPattern::Join { left, right }
+        | GraphPattern::LeftJoin { left, right, .. }
+        | GraphPattern::Union { left, right }
+        | GraphPattern::Minus { left, right } => {
+            evaluate_paths(left, hdts);
+            evaluate_paths(right, hdts);
+        }
+        GraphPattern::Filter { inner, .. }
+        | GraphPattern::Extend { inner, .. }
+        | GraphPattern::OrderBy { inner, .. }
+        | GraphPattern::Project { inner, .. }
+        | GraphPattern::Distinct { inner }
+        | GraphPattern::Reduced { inner }
+        | GraphPattern::Slice { inner, .. }
+        | GraphPattern::Group { inner, .. } => evaluate_paths(inner, hdts),
+        _ => {}
+    }
+}
+
+fn dataset_error(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> QueryEvaluationError {
+    QueryEvaluationError::Dataset(e.into())
+}
+
+/// The Concise Bounded Descriptions of all terms of the solutions, computed with [`Hdt::describe_ids`] in each HDT,
+/// so that DESCRIBE queries return the same triples as [`Hdt::describe`].
+fn describe<'a>(results: QueryResults<'a>, hdts: &'a [Hdt]) -> Result<QueryResults<'a>, QueryEvaluationError> {
+    let QueryResults::Solutions(solutions) = results else {
+        unreachable!("SELECT queries have solutions");
+    };
+    let mut seeds = HashSet::new();
+    for solution in solutions {
+        seeds.extend(solution?.iter().map(|(_, term)| term_to_hdt_bgp_str(term.clone())));
+    }
+    let seeds: Vec<&str> = seeds.iter().map(String::as_str).collect();
+    let options = DescribeOptions::default();
+    let mut seen = HashSet::<[Arc<str>; 3]>::new();
+    let mut triples = Vec::new();
+    for hdt in hdts {
+        for [s, p, o] in hdt.translate_all(hdt.describe_ids(&seeds, &options)) {
+            // a triple that occurs in several files is only contained once in the union
+            if hdts.len() > 1 && !seen.insert([s.clone(), p.clone(), o.clone()]) {
+                continue;
+            }
+            let subject = NamedOrBlankNode::try_from(hdt_bgp_str_to_term(&s).map_err(dataset_error)?)
+                .map_err(dataset_error)?;
+            let predicate = NamedNode::new(p.as_ref()).map_err(dataset_error)?;
+            let object = hdt_bgp_str_to_term(&o).map_err(dataset_error)?;
+            triples.push(Ok(Triple::new(subject, predicate, object)));
+        }
+    }
+    Ok(QueryResults::Graph(QueryTripleIter::new(triples.into_iter())))
+}
+
+/// Evaluates the query over the given HDT.
+/// Transitive property paths such as `rdfs:subClassOf*` with a constant subject or object are evaluated natively using [`Hdt::reachable`].
+/// DESCRIBE queries return the Concise Bounded Description of each resource, see [`Hdt::describe`].
+pub fn query<'a>(q: &str, hdt: &'a Hdt) -> Result<QueryResults<'a>, QueryEvaluationError> {
+    let mut query = SparqlParser::new().parse_query(q)?;
     //.unwrap_or_else(|_| panic!("error processing SPARQL query:\n{q}"));
+    match &mut query {
+        Query::Select { pattern, .. }
+        | Query::Construct { pattern, .. }
+        | Query::Describe { pattern, .. }
+        | Query::Ask { pattern, .. } => evaluate_paths(pattern, std::slice::from_ref(hdt)),
+    }
+    // DESCRIBE selects the resources, which are then described natively
+    if let Query::Describe { dataset, pattern, base_iri } = query {
+        let select = Query::Select { dataset, pattern, base_iri };
+        return describe(QueryEvaluator::new().prepare(&select).execute(hdt)?, std::slice::from_ref(hdt));
+    }
     QueryEvaluator::new().prepare(&query).execute(hdt)
 }
 
+/// Evaluates the query over the union of the given HDT files as the default graph.
+/// Transitive property paths and DESCRIBE queries are evaluated natively like in [`query`], with paths that may span several files.
+pub fn query_union<'a>(q: &str, hdts: &'a [Hdt]) -> Result<QueryResults<'a>, QueryEvaluationError> {
+    let mut query = SparqlParser::new().parse_query(q)?;
+    match &mut query {
+        Query::Select { pattern, .. }
+        | Query::Construct { pattern, .. }
+        | Query::Describe { pattern, .. }
+        | Query::Ask { pattern, .. } => evaluate_paths(pattern, hdts),
+    }
+    if let Query::Describe { dataset, pattern, base_iri } = query {
+        let select = Query::Select { dataset, pattern, base_iri };
+        return describe(QueryEvaluator::new().prepare(&select).execute(HdtUnion(hdts))?, hdts);
+    }
+    QueryEvaluator::new().prepare(&query).execute(HdtUnion(hdts))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +449,110 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn union() -> Result<()> {
+        use crate::hdt::tests::snikmeta;
+        init();
+        let hdts = [snikmeta()?, snikmeta()?];
+        let q = "SELECT (COUNT(*) AS ?x) { ?s ?p ?o }";
+        for res in [query(q, &hdts[0])?, query_union(q, &hdts)?] {
+            let spareval::QueryResults::Solutions(mut solutions) = res else {
+                panic!("SELECT query results expected but got something else")
+            };
+            let solution = solutions.next().unwrap()?;
+            assert_eq!(
+                "\"328\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+                solution.get("x").unwrap().to_string()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn describe() -> Result<()> {
+        use crate::hdt::tests::snikmeta;
+        init();
+        let hdts = [snikmeta()?, snikmeta()?];
+        let resource = "http://www.snik.eu/ontology/meta/MethodApplication";
+        let mut expected: Vec<[String; 3]> =
+            hdts[0].describe(resource, 1, true).map(|t| t.map(|x| x.to_string())).collect();
+        expected.sort_unstable();
+        assert!(expected.iter().any(|[s, _, _]| s.starts_with("_:")), "blank nodes are described");
+        for q in [format!("DESCRIBE <{resource}>"), format!("DESCRIBE ?x {{ BIND(<{resource}> AS ?x) }}")] {
+            for res in [query(&q, &hdts[0])?, query_union(&q, &hdts)?] {
+                let spareval::QueryResults::Graph(triples) = res else {
+                    panic!("DESCRIBE query results expected but got something else")
+                };
+                let mut actual = Vec::new();
+                for t in triples {
+                    let t = t?;
+                    actual.push([
+                        term_to_hdt_bgp_str(t.subject.into()),
+                        t.predicate.into_string(),
+                        term_to_hdt_bgp_str(t.object),
+                    ]);
+                }
+                actual.sort_unstable();
+                assert_eq!(actual, expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn property_paths() -> Result<()> {
+        use crate::hdt::tests::snikmeta;
+        init();
+        let hdt = snikmeta()?;
+        let prefix = "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#> PREFIX meta: <http://www.snik.eu/ontology/meta/>";
+        for pattern in [
+            "meta:ComputerBasedApplicationComponent rdfs:subClassOf* ?x",
+            "meta:ComputerBasedApplicationComponent rdfs:subClassOf+ ?x",
+            "meta:ComputerBasedApplicationComponent rdfs:subClassOf? ?x", "?x rdfs:subClassOf+ meta:Top",
+            "meta:Top ^rdfs:subClassOf* ?x", "meta:DoesNotExist rdfs:subClassOf* ?x",
+            "?x (rdfs:subClassOf|rdfs:subPropertyOf)* meta:EntityType",
+            "meta:DataSetType rdfs:subClassOf+ meta:Top . BIND(true AS ?x)",
+        ] {
+            let q = format!("{prefix} SELECT ?x {{ {pattern} }} ORDER BY ?x");
+            let solutions = |res| -> Result<Vec<String>> {
+                let spareval::QueryResults::Solutions(solutions) = res else { panic!("SELECT results expected") };
+                solutions.map(|s| Ok(s?.get("x").unwrap().to_string())).collect::<Result<_>>()
+            };
+            // compare with the generic evaluator
+            let expected =
+                solutions(QueryEvaluator::new().prepare(&SparqlParser::new().parse_query(&q)?).execute(&hdt)?)?;
+            assert_eq!(solutions(query(&q, &hdt)?)?, expected, "{pattern}");
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn union_property_paths() -> Result<()> {
+        use crate::hdt::tests::snikmeta;
+        init();
+        let hdt = snikmeta()?;
+        // split the triples between two files, so that paths continue in the other file
+        let triples: Vec<_> = hdt.triples_with_pattern(None, None, None).collect();
+        let halves = [0, 1].map(|i| triples.iter().skip(i).step_by(2).cloned());
+        let hdts = halves.map(|h| Hdt::from_triples(h, "http://example.org/half").unwrap());
+        let prefix = "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#> PREFIX meta: <http://www.snik.eu/ontology/meta/>";
+        for pattern in [
+            "meta:ComputerBasedApplicationComponent rdfs:subClassOf* ?x", "?x rdfs:subClassOf+ meta:Top",
+            "meta:Top ^rdfs:subClassOf* ?x", "meta:DataSetType rdfs:subClassOf+ meta:Top . BIND(true AS ?x)",
+        ] {
+            let q = format!("{prefix} SELECT ?x {{ {pattern} }} ORDER BY ?x");
+            let solutions = |res| -> Result<Vec<String>> {
+                let spareval::QueryResults::Solutions(solutions) = res else { panic!("SELECT results expected") };
+                solutions.map(|s| Ok(s?.get("x").unwrap().to_string())).collect::<Result<_>>()
+            };
+            let expected = solutions(query(&q, &hdt)?)?;
+            assert!(!expected.is_empty(), "{pattern}");
+            assert_eq!(solutions(query_union(&q, &hdts)?)?, expected, "{pattern}");
+        }
+        Ok(())
+    }
+
     const MF: Namespace<&str> =
         Namespace::new_unchecked_const("http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#");
     const QT: Namespace<&str> =
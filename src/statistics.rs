@@ -0,0 +1,357 @@
+//! Dataset profiling, see [`Hdt::statistics`].
+use crate::containers::{ControlInfo, Sequence};
+use crate::triples::{Id, OpIndex, PredicateIter};
+use crate::vocab::{RDF_LANG_STRING, RDF_TYPE, XSD_STRING};
+use crate::{Hdt, IdKind};
+use bytesize::ByteSize;
+use log::error;
+use qwt::RankUnsigned;
+use qwt::mem_dbg::{MemSize, SizeFlags};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+
+/// Profile of an HDT dataset as returned by [`Hdt::statistics`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Statistics {
+    /// number of triples
+    pub triples: usize,
+    /// number of terms in the shared subject-object section
+    pub shared_terms: usize,
+    /// number of terms that only occur as subjects
+    pub subject_terms: usize,
+    /// number of distinct predicates
+    pub predicate_terms: usize,
+    /// number of terms that only occur as objects
+    pub object_terms: usize,
+    /// size of each component in memory and in the HDT file
+    pub components: Vec<ComponentSize>,
+    /// the most frequent predicates, ordered by descending number of triples
+    pub top_predicates: Vec<PredicateCount>,
+    /// the classes with the most instances via rdf:type, ordered by descending number of instances
+    pub top_classes: Vec<TermCount>,
+    /// number of distinct literals
+    pub literals: usize,
+    /// number of distinct literals by datatype, language tagged literals are counted as rdf:langString
+    pub datatypes: Vec<TermCount>,
+    /// number of distinct language tagged literals by language tag
+    pub languages: Vec<TermCount>,
+    /// average number of triples per distinct subject
+    pub avg_out_degree: f64,
+    /// average number of triples per distinct object
+    pub avg_in_degree: f64,
+}
+
+/// Size of a single HDT component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ComponentSize {
+    /// name of the component such as "dictionary shared" or "triples op_index"
+    pub name: &'static str,
+    /// size on the heap in bytes
+    pub memory: usize,
+    /// number of bytes in the HDT file, `None` for indexes that are only built in memory
+    pub disk: Option<usize>,
+}
+
+/// Usage count of a predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PredicateCount {
+    /// the IRI of the predicate
+    pub predicate: String,
+    /// number of triples with this predicate
+    pub triples: usize,
+    /// number of distinct subjects with this predicate
+    pub subjects: usize,
+}
+
+/// Number of occurrences of a term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TermCount {
+    /// the term in the HDT string format
+    pub term: String,
+    /// number of occurrences
+    pub count: usize,
+}
+
+/// Counts the bytes that would have been written.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Number of bytes the given write function produces or `None` if it fails.
+fn disk_size<E>(write: impl FnOnce(&mut ByteCounter) -> Result<(), E>) -> Option<usize> {
+    let mut counter = ByteCounter(0);
+    write(&mut counter).ok().map(|()| counter.0)
+}
+
+/// The n entries with the highest counts, ties broken by term.
+fn top(counts: HashMap<String, usize>, n: usize) -> Vec<TermCount> {
+    let mut v: Vec<TermCount> = counts.into_iter().map(|(term, count)| TermCount { term, count }).collect();
+    v.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    v.truncate(n);
+    v
+}
+
+impl Hdt {
+    /// Memory and disk size of each component, which add up to the size of the HDT file.
+    fn component_sizes(&self) -> Vec<ComponentSize> {
+        let dict = &self.dict;
+        let t = &self.triples;
+        let sects = [
+            ("dictionary shared", &dict.shared),
+            ("dictionary subjects", &dict.subjects),
+            ("dictionary predicates", &dict.predicates),
+            ("dictionary objects", &dict.objects),
+        ];
+        let control_info = [
+            ControlInfo::global(),
+            ControlInfo::four_sect_dict(),
+            // the order is a single digit so it does not influence the size
+            ControlInfo::bitmap_triples(1, t.adjlist_z.len() as u32),
+        ];
+        let mut components = vec![
+            ComponentSize {
+                name: "control information",
+                memory: 0,
+                disk: disk_size(|w| control_info.iter().try_for_each(|ci| ci.write(w))),
+            },
+            ComponentSize {
+                name: "header",
+                memory: self.header.size_in_bytes(),
+                disk: disk_size(|w| self.header.write(w)),
+            },
+        ];
+        components.extend(sects.iter().map(|(name, sect)| ComponentSize {
+            name,
+            memory: sect.size_in_bytes(),
+            disk: disk_size(|w| sect.write(w)),
+        }));
+        components.extend([
+            ComponentSize {
+                name: "triples bitmap_y",
+                memory: t.bitmap_y.size_in_bytes(),
+                disk: disk_size(|w| t.bitmap_y.write(w)),
+            },
+            ComponentSize {
+                name: "triples adjlist_z",
+                memory: t.adjlist_z.size_in_bytes(),
+                disk: disk_size(|w| t.adjlist_z.bitmap.write(w))
+                    .zip(disk_size(|w| t.adjlist_z.sequence.write(w)))
+                    .map(|(a, b)| a + b),
+            },
+            // stored as a sequence in the file and converted to a wavelet matrix on loading
+            ComponentSize {
+                name: "triples wavelet_y",
+                memory: t.wavelet_y.mem_size(SizeFlags::default()),
+                disk: Some(Sequence::disk_size(
+                    t.wavelet_y.len(),
+                    t.wavelet_y.sigma().map_or(0, |sigma| (usize::BITS - sigma.leading_zeros()) as usize),
+                )),
+            },
+            // only counted if it has already been built
+            ComponentSize {
+                name: "triples op_index",
+                memory: t.built_op_index().map_or(0, OpIndex::size_in_bytes),
+                disk: None,
+            },
+        ]);
+        components
+    }
+
+    /// Number of literals as well as their datatype and language tag distribution.
+    /// Literals only occur as objects and never in the shared section.
+    fn literal_distribution(&self) -> (usize, HashMap<String, usize>, HashMap<String, usize>) {
+        let mut datatypes = HashMap::<String, usize>::new();
+        let mut languages = HashMap::<String, usize>::new();
+        let mut literals = 0;
+        for id in 1..=self.dict.objects.num_strings() as Id {
+            let o = match self.dict.objects.extract(id) {
+                Ok(o) => o,
+                Err(e) => {
+                    error!("{e}");
+                    continue;
+                }
+            };
+            if !o.starts_with('"') {
+                continue;
+            }
+            literals += 1;
+            let rest = &o[o.rfind('"').unwrap_or(0) + 1..];
+            if let Some(lang) = rest.strip_prefix('@') {
+                *datatypes.entry(RDF_LANG_STRING.to_owned()).or_default() += 1;
+                *languages.entry(lang.to_owned()).or_default() += 1;
+            } else if let Some(dt) = rest.strip_prefix("^^<").and_then(|dt| dt.strip_suffix('>')) {
+                *datatypes.entry(dt.to_owned()).or_default() += 1;
+            } else {
+                *datatypes.entry(XSD_STRING.to_owned()).or_default() += 1;
+            }
+        }
+        (literals, datatypes, languages)
+    }
+
+    /// Number of triples for each predicate ID in a single pass over the predicate level, index 0 is unused.
+    pub(crate) fn predicate_triple_counts(&self) -> Vec<usize> {
+        let t = &self.triples;
+        let mut counts = vec![0usize; self.dict.predicates.num_strings() + 1];
+        for (pos_y, p) in t.wavelet_y.iter().enumerate() {
+            counts[p] += t.adjlist_z.last(pos_y) + 1 - t.adjlist_z.find(pos_y);
+        }
+        counts
+    }
+
+    /// Profiles the dataset, for example to decide how to publish it.
+    /// Lists of predicates and classes are restricted to the `top_n` most frequent entries.
+    /// Decodes all literals, so this can take a while for large graphs.
+    /// # Example
+    /// ```
+    /// let hdt = hdt::Hdt::read_from_path("tests/resources/snikmeta.hdt").unwrap();
+    /// println!("{}", hdt.statistics(10));
+    /// ```
+    pub fn statistics(&self, top_n: usize) -> Statistics {
+        let dict = &self.dict;
+        let t = &self.triples;
+        let components = self.component_sizes();
+
+        // predicates
+        let predicate_triples = self.predicate_triple_counts();
+        let mut pids: Vec<Id> = (1..predicate_triples.len() as Id).collect();
+        pids.sort_by(|a, b| predicate_triples[*b as usize].cmp(&predicate_triples[*a as usize]).then(a.cmp(b)));
+        let top_predicates = pids
+            .into_iter()
+            .take(top_n)
+            .filter_map(|p| match dict.id_to_string(p, IdKind::Predicate) {
+                Ok(predicate) => Some(PredicateCount {
+                    predicate,
+                    triples: predicate_triples[p as usize],
+                    // each subject occurs at most once per predicate in the wavelet matrix
+                    subjects: t.wavelet_y.rank(p as usize, t.wavelet_y.len()).unwrap_or(0),
+                }),
+                Err(e) => {
+                    error!("{e}");
+                    None
+                }
+            })
+            .collect();
+
+        // classes
+        let mut class_ids = HashMap::<Id, usize>::new();
+        let type_id = dict.string_to_id(RDF_TYPE, IdKind::Predicate);
+        if type_id != 0 {
+            for [_, _, o] in PredicateIter::new(t, type_id) {
+                *class_ids.entry(o).or_default() += 1;
+            }
+        }
+        let class_counts = class_ids
+            .into_iter()
+            .filter_map(|(id, count)| {
+                dict.id_to_string(id, IdKind::Object).map_err(|e| error!("{e}")).ok().map(|c| (c, count))
+            })
+            .collect();
+
+        let (literals, datatypes, languages) = self.literal_distribution();
+        let triples = t.len();
+        let [num_subjects, num_objects] =
+            [dict.subjects.num_strings(), dict.objects.num_strings()].map(|n| n + dict.shared.num_strings());
+        #[allow(clippy::cast_precision_loss)]
+        let avg = |n: usize| if n == 0 { 0.0 } else { triples as f64 / n as f64 };
+        Statistics {
+            triples,
+            shared_terms: dict.shared.num_strings(),
+            subject_terms: dict.subjects.num_strings(),
+            predicate_terms: dict.predicates.num_strings(),
+            object_terms: dict.objects.num_strings(),
+            components,
+            top_predicates,
+            top_classes: top(class_counts, top_n),
+            literals,
+            datatypes: top(datatypes, usize::MAX),
+            languages: top(languages, usize::MAX),
+            avg_out_degree: avg(num_subjects),
+            avg_in_degree: avg(num_objects),
+        }
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "triples: {}", self.triples)?;
+        writeln!(f, "dictionary terms:")?;
+        writeln!(f, "    shared: {}", self.shared_terms)?;
+        writeln!(f, "    subjects: {}", self.subject_terms)?;
+        writeln!(f, "    predicates: {}", self.predicate_terms)?;
+        writeln!(f, "    objects: {}", self.object_terms)?;
+        writeln!(f, "component sizes (memory / disk):")?;
+        for c in &self.components {
+            let disk = c.disk.map_or_else(|| "-".to_owned(), |d| ByteSize(d as u64).to_string());
+            writeln!(f, "    {}: {} / {disk}", c.name, ByteSize(c.memory as u64))?;
+        }
+        writeln!(f, "top predicates (triples / subjects):")?;
+        for p in &self.top_predicates {
+            writeln!(f, "    {}: {} / {}", p.predicate, p.triples, p.subjects)?;
+        }
+        writeln!(f, "top classes (instances):")?;
+        for c in &self.top_classes {
+            writeln!(f, "    {}: {}", c.term, c.count)?;
+        }
+        writeln!(f, "literals: {}", self.literals)?;
+        writeln!(f, "literal datatypes:")?;
+        for d in &self.datatypes {
+            writeln!(f, "    {}: {}", d.term, d.count)?;
+        }
+        writeln!(f, "literal languages:")?;
+        for l in &self.languages {
+            writeln!(f, "    {}: {}", l.term, l.count)?;
+        }
+        writeln!(f, "average out degree: {:.2}", self.avg_out_degree)?;
+        write!(f, "average in degree: {:.2}", self.avg_in_degree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn statistics() -> color_eyre::Result<()> {
+        init();
+        let hdt = snikmeta()?;
+        let stats = hdt.statistics(3);
+        assert_eq!(stats.triples, 328);
+        assert_eq!(
+            [stats.shared_terms, stats.subject_terms, stats.predicate_terms, stats.object_terms],
+            [43, 6, 23, 133]
+        );
+        assert_eq!(stats.top_predicates.len(), 3);
+        assert!(stats.top_predicates.windows(2).all(|w| w[0].triples >= w[1].triples));
+        let label = "http://www.w3.org/2000/01/rdf-schema#label";
+        assert_eq!(
+            hdt.triples_with_pattern(None, Some(label), None).count(),
+            hdt.statistics(23).top_predicates.iter().find(|p| p.predicate == label).unwrap().triples
+        );
+        let rdf_type = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+        let class = &stats.top_classes[0];
+        assert_eq!(hdt.triples_with_pattern(None, Some(rdf_type), Some(&class.term)).count(), class.count);
+        assert_eq!(stats.datatypes.iter().map(|d| d.count).sum::<usize>(), stats.literals);
+        let mut buf = Vec::new();
+        hdt.write(&mut buf)?;
+        assert_eq!(stats.components.iter().filter_map(|c| c.disk).sum::<usize>(), buf.len());
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use crate::ControlInfo;
 use crate::containers::{AdjList, Bitmap, Sequence, bitmap, control_info, sequence};
+use crate::hdt::ReadOptions;
+use crate::progress::{self, Cancelled, Phase, Progress};
 use bytesize::ByteSize;
 use log::error;
 use qwt::QWT512;
@@ -8,15 +10,22 @@ use qwt::{AccessUnsigned, BitVector, BitVectorMut, bitvector::rs_narrow::RSNarro
 use std::cmp::Ordering;
 use std::fmt;
 use std::io::BufRead;
+use std::sync::OnceLock;
 
 mod subject_iter;
+pub(crate) use subject_iter::SubjectCursor;
 pub use subject_iter::SubjectIter;
 mod predicate_iter;
+pub(crate) use predicate_iter::PredicateCursor;
 pub use predicate_iter::PredicateIter;
 mod predicate_object_iter;
+pub(crate) use predicate_object_iter::PredicateObjectCursor;
 pub use predicate_object_iter::PredicateObjectIter;
 mod object_iter;
+pub(crate) use object_iter::ObjectCursor;
 pub use object_iter::ObjectIter;
+mod pattern_cursor;
+pub(crate) use pattern_cursor::PatternCursor;
 #[cfg(feature = "cache")]
 use serde::{self, Deserialize, Serialize};
 
@@ -92,11 +101,11 @@ impl OpIndex {
     }
     /// Find the first position in the OP index of the given object ID.
     pub fn find(&self, o: Id) -> usize {
-        self.bitmap.select1(o - 1).unwrap() as usize
+        self.bitmap.select1(o as usize - 1).unwrap() as usize
     }
     /// Find the last position in the object index of the given object ID.
     pub fn last(&self, o: Id) -> usize {
-        self.bitmap.select1(o).map_or_else(|| self.bitmap.len() - 1, |index| index as usize - 1)
+        self.bitmap.select1(o as usize).map_or_else(|| self.bitmap.len() - 1, |index| index as usize - 1)
     }
 }
 
@@ -112,12 +121,14 @@ pub struct TriplesBitmap {
     /// adjacency list storing the object IDs
     pub adjlist_z: AdjList,
     /// Index for object-based access. Points to the predicate layer.
-    pub op_index: OpIndex,
+    /// Built on first use if the triples were read without it, see [`TriplesBitmap::op_index`].
+    #[cfg_attr(feature = "cache", serde(with = "lazy_op_index"))]
+    op_index: OnceLock<OpIndex>,
     /// wavelet matrix for predicate-based access
     pub wavelet_y: WT,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
     Y,
     Z,
@@ -143,9 +154,11 @@ pub enum Error {
     #[error("triple lists are not supported yet")]
     TriplesList,
     #[error("({0},{1},{2}) none of the components of a triple may be 0.")]
-    TripleComponentZero(usize, usize, usize),
+    TripleComponentZero(Id, Id, Id),
     #[error("unspecified external library error")]
     External(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
     #[error("cache decode error")]
     #[cfg(feature = "cache")]
     Decode(#[from] bincode::error::DecodeError),
@@ -155,7 +168,10 @@ impl fmt::Debug for TriplesBitmap {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "total size {}", ByteSize(self.size_in_bytes() as u64))?;
         writeln!(f, "adjlist_z {:#?}", self.adjlist_z)?;
-        writeln!(f, "op_index {:#?}", self.op_index)?;
+        match self.op_index.get() {
+            Some(op_index) => writeln!(f, "op_index {op_index:#?}")?,
+            None => writeln!(f, "op_index not built")?,
+        }
         write!(f, "wavelet_y {}", ByteSize(self.wavelet_y.mem_size(SizeFlags::default()) as u64))
     }
 }
@@ -199,41 +215,108 @@ fn build_op_index_from_entries(mut entries: Vec<(u32, u32, u32)>) -> (Vec<u32>,
     (cv, bitmap)
 }
 
+/// Builds the wavelet matrix of the predicate level while reporting the [`Phase::Wavelet`] phase.
+/// Reports while decoding the sequence, the final construction of the matrix is not interrupted.
+fn build_wavelet(sequence_y: &Sequence, progress: Option<&dyn Progress>) -> core::result::Result<WT, Cancelled> {
+    let total = sequence_y.entries as u64;
+    let mut y = Vec::with_capacity(sequence_y.entries);
+    for (i, entry) in sequence_y.into_iter().enumerate() {
+        if i.is_multiple_of(progress::ENTRY_STEP) {
+            progress::report(progress, Phase::Wavelet, i as u64, Some(total))?;
+        }
+        y.push(entry);
+    }
+    let wavelet_y = WT::new(&mut y);
+    progress::report(progress, Phase::Wavelet, total, Some(total))?;
+    Ok(wavelet_y)
+}
+
 impl TriplesBitmap {
     /// builds the necessary indexes and constructs TriplesBitmap
     pub fn new(order: Order, sequence_y: &Sequence, bitmap_y: Bitmap, adjlist_z: AdjList) -> Self {
-        use rayon::prelude::*;
+        let triples = Self::new_lazy(order, sequence_y, bitmap_y, adjlist_z);
+        triples.op_index();
+        triples
+    }
+
+    /// Constructs TriplesBitmap with the wavelet matrix but without the object index, which is built on first use.
+    fn new_lazy(order: Order, sequence_y: &Sequence, bitmap_y: Bitmap, adjlist_z: AdjList) -> Self {
         //let wavelet_thread = std::thread::spawn(move || WT::from_iter(&sequence_y));
         let wavelet_y = WT::from_iter(sequence_y);
+        Self { order, bitmap_y, adjlist_z, op_index: OnceLock::new(), wavelet_y }
+    }
+
+    /// Returns the object index, which is needed for ?PO and ??O patterns,
+    /// building it on first use if it was deferred by [`TriplesBitmap::read_sect_lazy`].
+    pub fn op_index(&self) -> &OpIndex {
+        self.op_index.get_or_init(|| self.build_op_index(None).expect("only a progress observer can cancel"))
+    }
+
+    /// The object index if it has already been built, without building it.
+    pub(crate) fn built_op_index(&self) -> Option<&OpIndex> {
+        self.op_index.get()
+    }
+
+    /// Builds the object index if necessary while reporting the [`Phase::OpIndex`] phase.
+    pub(crate) fn build_op_index_with(
+        &self, progress: Option<&dyn Progress>,
+    ) -> core::result::Result<(), Cancelled> {
+        if self.op_index.get().is_none() {
+            let op_index = self.build_op_index(progress)?;
+            self.op_index.get_or_init(|| op_index);
+        }
+        Ok(())
+    }
 
+    /// Reports the [`Phase::OpIndex`] phase while collecting the entries, sorting them is not interrupted.
+    fn build_op_index(&self, progress: Option<&dyn Progress>) -> core::result::Result<OpIndex, Cancelled> {
+        use rayon::prelude::*;
+        let adjlist_z = &self.adjlist_z;
+        let wavelet_y = &self.wavelet_y;
         let entries = adjlist_z.sequence.entries;
         // Collect (object, predicate, pos_y) tuples for the op-index build.
         // Each iteration does one sequence.get + one bitmap.rank + one wavelet.get;
         // all are read-only so we parallelize with rayon for ~6-8× speedup.
-        let pairs: Vec<(u32, u32, u32)> = (0..entries)
-            .into_par_iter()
-            .filter_map(|pos_z| {
-                let object = adjlist_z.sequence.get(pos_z);
-                if object == 0 {
-                    error!("ERROR: There is a zero value in the Z level.");
-                    return None;
-                }
-                let pos_y = adjlist_z.bitmap.rank(pos_z) as u32;
-                let predicate = wavelet_y.get(pos_y as usize).unwrap() as u32;
-                Some((object as u32, predicate, pos_y))
-            })
-            .collect();
+        let entry = |pos_z| {
+            let object = adjlist_z.sequence.get(pos_z);
+            if object == 0 {
+                error!("ERROR: There is a zero value in the Z level.");
+                return None;
+            }
+            let pos_y = adjlist_z.bitmap.rank(pos_z) as u32;
+            let predicate = wavelet_y.get(pos_y as usize).unwrap() as u32;
+            Some((object as u32, predicate, pos_y))
+        };
+        let total = entries as u64;
+        let mut pairs: Vec<(u32, u32, u32)> = Vec::with_capacity(entries);
+        // parallel within each step so that the observer is called between steps
+        for start in (0..entries).step_by(progress::ENTRY_STEP) {
+            progress::report(progress, Phase::OpIndex, start as u64, Some(total))?;
+            let end = entries.min(start + progress::ENTRY_STEP);
+            pairs.par_extend((start..end).into_par_iter().filter_map(entry));
+        }
 
         // Sort + stream → op-index components. `pairs` is consumed and freed.
         let (cv, bitmap_index_bitvector) = build_op_index_from_entries(pairs);
         let bv = BitVector::from(bitmap_index_bitvector);
         let bitmap_index = Bitmap { dict: RSNarrow::from(bv) };
         let op_index = OpIndex { sequence: Sequence::new_from_u32(&cv), bitmap: bitmap_index };
-        Self { order, bitmap_y, adjlist_z, op_index, wavelet_y }
+        progress::report(progress, Phase::OpIndex, total, Some(total))?;
+        Ok(op_index)
     }
 
     /// Creates a new TriplesBitmap from a list of sorted RDF triples
     pub fn from_triples(triples: &[TripleId]) -> Self {
+        let triples = Self::from_triples_lazy(triples, None).expect("only a progress observer can cancel");
+        triples.op_index();
+        triples
+    }
+
+    /// Like [`TriplesBitmap::from_triples`] but the object index is built on first use.
+    /// Reports the [`Phase::Wavelet`] phase to the observer, which can cancel it.
+    pub(crate) fn from_triples_lazy(
+        triples: &[TripleId], progress: Option<&dyn Progress>,
+    ) -> core::result::Result<Self, Cancelled> {
         let mut y_bitmap = BitVectorMut::new();
         let mut z_bitmap = BitVectorMut::new();
         let mut array_y = Vec::new();
@@ -283,19 +366,34 @@ impl TriplesBitmap {
         // bit_width() only in nightly for now
         /*let sequence_y = Sequence::new(&array_y, (Id::BITS - max_y.leading_zeros()) as usize);
         let sequence_z = Sequence::new(&array_z, (Id::BITS - max_z.leading_zeros()) as usize);*/
-        let sequence_y = Sequence::new(&array_y);
-        let sequence_z = Sequence::new(&array_z);
+        let sequence_y = Sequence::new_from_u32(&array_y);
+        let sequence_z = Sequence::new_from_u32(&array_z);
         let adjlist_z = AdjList::new(sequence_z, bitmap_z);
-        TriplesBitmap::new(Order::SPO, &sequence_y, bitmap_y, adjlist_z)
+        let wavelet_y = build_wavelet(&sequence_y, progress)?;
+        Ok(Self { order: Order::SPO, bitmap_y, adjlist_z, op_index: OnceLock::new(), wavelet_y })
     }
 
     /// read the whole triple section including control information
     // TODO: rename to "read" for consistency with the other components and rename existing read function accordingly
     pub fn read_sect<R: BufRead>(reader: &mut R) -> Result<Self> {
+        let triples = Self::read_sect_lazy(reader, ReadOptions::default())?;
+        triples.op_index();
+        Ok(triples)
+    }
+
+    /// Like [`TriplesBitmap::read_sect`] but defers building the object index until it is first used.
+    /// Reading is faster and uses less memory if there are no ?PO or ??O queries.
+    /// Only the checksums selected by the options are verified.
+    pub fn read_sect_lazy<R: BufRead>(reader: &mut R, options: ReadOptions) -> Result<Self> {
         let triples_ci = ControlInfo::read(reader)?;
+        Self::check_format(&triples_ci)?;
+        TriplesBitmap::read(reader, &triples_ci, options)
+    }
 
+    /// Checks that the control information describes a triples section in the only supported format.
+    pub(crate) fn check_format(triples_ci: &ControlInfo) -> Result<()> {
         match &triples_ci.format[..] {
-            "<http://purl.org/HDT/hdt#triplesBitmap>" => TriplesBitmap::read(reader, &triples_ci),
+            "<http://purl.org/HDT/hdt#triplesBitmap>" => Ok(()),
             "<http://purl.org/HDT/hdt#triplesList>" => Err(Error::TriplesList),
             f => Err(Error::UnknownTriplesFormat(f.to_owned())),
         }
@@ -304,11 +402,8 @@ impl TriplesBitmap {
     /// load the cached HDT index file, only supports TriplesBitmap
     #[cfg(feature = "cache")]
     pub fn load_cache<R: BufRead>(reader: &mut R, info: &ControlInfo) -> Result<Self> {
-        match &info.format[..] {
-            "<http://purl.org/HDT/hdt#triplesBitmap>" => TriplesBitmap::load(reader),
-            "<http://purl.org/HDT/hdt#triplesList>" => Err(Error::TriplesList),
-            f => Err(Error::UnknownTriplesFormat(f.to_owned())),
-        }
+        Self::check_format(info)?;
+        TriplesBitmap::load(reader)
     }
 
     /// load the entire cached TriplesBitmap object
@@ -321,7 +416,7 @@ impl TriplesBitmap {
     /// Size in bytes on the heap.
     pub fn size_in_bytes(&self) -> usize {
         self.adjlist_z.size_in_bytes()
-            + self.op_index.size_in_bytes()
+            + self.op_index.get().map_or(0, OpIndex::size_in_bytes)
             + self.wavelet_y.mem_size(SizeFlags::default())
     }
 
@@ -330,14 +425,14 @@ impl TriplesBitmap {
         if subject_id == 0 {
             return 0;
         }
-        self.bitmap_y.select1(subject_id - 1).unwrap_or_else(|| {
+        self.bitmap_y.select1(subject_id as usize - 1).unwrap_or_else(|| {
             panic!("invalid s_id {subject_id}, there are only {} subjects", self.bitmap_y.num_ones())
         }) as usize
             + 1
     }
 
     /// Position in the wavelet index of the last predicate for the given subject ID.
-    pub fn last_y(&self, subject_id: usize) -> usize {
+    pub fn last_y(&self, subject_id: Id) -> usize {
         self.find_y(subject_id + 1) - 1
     }
 
@@ -358,8 +453,8 @@ impl TriplesBitmap {
     }
 
     /// Search the wavelet matrix for the position of a given subject, predicate pair.
-    pub fn search_y(&self, subject_id: usize, property_id: usize) -> Option<usize> {
-        self.bin_search_y(property_id, self.find_y(subject_id), self.last_y(subject_id) + 1)
+    pub fn search_y(&self, subject_id: Id, property_id: Id) -> Option<usize> {
+        self.bin_search_y(property_id as usize, self.find_y(subject_id), self.last_y(subject_id) + 1)
     }
 
     /*
@@ -370,26 +465,57 @@ impl TriplesBitmap {
     }
     */
 
-    fn read<R: BufRead>(reader: &mut R, triples_ci: &ControlInfo) -> Result<Self> {
-        //let order: Order = Order::try_from(triples_ci.get("order").unwrap().parse::<u32>());
-        let order: Order;
-        if let Some(n) = triples_ci.get("order").and_then(|v| v.parse::<u32>().ok()) {
-            order = Order::try_from(n)?;
-        } else {
-            return Err(Error::UnspecifiedTriplesOrder);
-        }
+    fn read<R: BufRead>(reader: &mut R, triples_ci: &ControlInfo, options: ReadOptions) -> Result<Self> {
+        let verify = options.verify;
+        let order = Self::read_order(triples_ci)?;
 
         // read bitmaps
-        let bitmap_y = Bitmap::read(reader).map_err(|e| Error::Bitmap(Level::Y, e))?;
-        let bitmap_z = Bitmap::read(reader).map_err(|e| Error::Bitmap(Level::Z, e))?;
+        let bitmap_y = Bitmap::read_with(reader, verify).map_err(|e| Error::Bitmap(Level::Y, e))?;
+        let bitmap_z = Bitmap::read_with(reader, verify).map_err(|e| Error::Bitmap(Level::Z, e))?;
 
-        // read sequences
-        let sequence_y = Sequence::read(reader).map_err(|e| Error::Sequence(Level::Y, e))?;
-        let sequence_z = Sequence::read(reader).map_err(|e| Error::Sequence(Level::Z, e))?;
-        let adjlist_z = AdjList::new(sequence_z, bitmap_z);
+        // read sequences, their checksums are verified later
+        let sequence_y = Sequence::read_unverified(reader, verify).map_err(|e| Error::Sequence(Level::Y, e))?;
+        let sequence_z = Sequence::read_unverified(reader, verify).map_err(|e| Error::Sequence(Level::Z, e))?;
+        Self::from_parts(order, bitmap_y, bitmap_z, sequence_y, sequence_z, options)
+    }
 
-        let triples_bitmap = TriplesBitmap::new(order, &sequence_y, bitmap_y, adjlist_z);
-        Ok(triples_bitmap)
+    /// The order given in the control information of the triples section.
+    pub(crate) fn read_order(triples_ci: &ControlInfo) -> Result<Order> {
+        //let order: Order = Order::try_from(triples_ci.get("order").unwrap().parse::<u32>());
+        match triples_ci.get("order").and_then(|v| v.parse::<u32>().ok()) {
+            Some(n) => Ok(Order::try_from(n)?),
+            None => Err(Error::UnspecifiedTriplesOrder),
+        }
+    }
+
+    /// Verifies the checksums of the sequences, which are given together with their stored CRC32 if it should be checked,
+    /// and builds the in-memory structures from the containers of the triples section.
+    pub(crate) fn from_parts(
+        order: Order, bitmap_y: Bitmap, bitmap_z: Bitmap, (sequence_y, crc_y): (Sequence, Option<u32>),
+        (sequence_z, crc_z): (Sequence, Option<u32>), options: ReadOptions,
+    ) -> Result<Self> {
+        let verify_crc = |level, sequence: &Sequence, crc: Option<u32>| {
+            crc.map_or(Ok(()), |crc| sequence.verify_crc32(crc)).map_err(|e| Error::Sequence(level, e))
+        };
+
+        // the sequences are the largest part of the file, so verify them in parallel with building the wavelet matrix
+        #[cfg(not(any(target_arch = "wasm32", target_arch = "wasm64")))]
+        let wavelet_y = std::thread::scope(|scope| {
+            let y = scope.spawn(|| verify_crc(Level::Y, &sequence_y, crc_y));
+            let z = scope.spawn(|| verify_crc(Level::Z, &sequence_z, crc_z));
+            let wavelet_y = build_wavelet(&sequence_y, options.progress)?;
+            y.join().unwrap()?;
+            z.join().unwrap()?;
+            Ok::<_, Error>(wavelet_y)
+        })?;
+        #[cfg(any(target_arch = "wasm32", target_arch = "wasm64"))]
+        let wavelet_y = {
+            verify_crc(Level::Y, &sequence_y, crc_y)?;
+            verify_crc(Level::Z, &sequence_z, crc_z)?;
+            build_wavelet(&sequence_y, options.progress)?
+        };
+        let adjlist_z = AdjList::new(sequence_z, bitmap_z);
+        Ok(Self { order, bitmap_y, adjlist_z, op_index: OnceLock::new(), wavelet_y })
     }
 
     pub fn write(&self, write: &mut impl std::io::Write) -> Result<()> {
@@ -436,13 +562,36 @@ impl<'a> IntoIterator for &'a TriplesBitmap {
     }
 }
 
+/// Splits the non-empty range from start to the exclusive end into at most `parts` consecutive ranges of similar length.
+pub(crate) fn split_range(start: usize, end: usize, parts: usize) -> impl Iterator<Item = (usize, usize)> {
+    let len = end.saturating_sub(start);
+    let parts = parts.clamp(1, len.max(1));
+    (0..parts).map(move |i| (start + len * i / parts, start + len * (i + 1) / parts)).filter(|(a, b)| a < b)
+}
+
+/// Serializes the object index, which must already be built, as if it were a plain field.
+#[cfg(feature = "cache")]
+mod lazy_op_index {
+    use super::OpIndex;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::sync::OnceLock;
+
+    pub fn serialize<S: Serializer>(op_index: &OnceLock<OpIndex>, s: S) -> Result<S::Ok, S::Error> {
+        op_index.get().ok_or_else(|| serde::ser::Error::custom("object index not built"))?.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<OnceLock<OpIndex>, D::Error> {
+        Ok(OnceLock::from(OpIndex::deserialize(d)?))
+    }
+}
+
 /// Subject, predicate or object ID, starting at 1.
 ///
 /// Subjects and predicate share IDs, starting at 1, for common values.
 /// A value of 0 indicates either not found (as a return value) or all of them (in a triple pattern).
-/// In the official documentation, u32 is used, however here, usize is used.
-/// While u32 caps out at 4 billion, more is not supported by the format anyways so this can probably be changed to u32.
-pub type Id = usize;
+/// As in the official documentation, IDs are 32 bit, which halves the size of triple IDs and indexes compared to usize.
+/// Dictionary sections with more strings are rejected with an error when reading or converting.
+pub type Id = u32;
 
 /// Type for a triple encoded as numeric IDs for subject, predicate and object, respectively.
 /// See <https://www.rdfhdt.org/hdt-binary-format/#triples>.
@@ -465,7 +614,7 @@ mod tests {
     use std::io::BufReader;
 
     /// Iterator over all triples with a given ID in the specified position (subject, predicate or object).
-    fn triples_with_id<'a>(t: &'a TriplesBitmap, id: usize, k: IdKind) -> Box<dyn Iterator<Item = TripleId> + 'a> {
+    fn triples_with_id<'a>(t: &'a TriplesBitmap, id: Id, k: IdKind) -> Box<dyn Iterator<Item = TripleId> + 'a> {
         match k {
             IdKind::Subject => Box::new(SubjectIter::with_s(t, id)),
             IdKind::Predicate => Box::new(PredicateIter::new(t, id)),
@@ -1,4 +1,4 @@
-use crate::triples::{Id, TripleId, TriplesBitmap};
+use crate::triples::{Id, TripleId, TriplesBitmap, split_range};
 use qwt::AccessUnsigned;
 
 // see "Exchange and Consumption of Huge RDF Data" by Martinez et al. 2012
@@ -9,6 +9,12 @@ use qwt::AccessUnsigned;
 /// Iterator over all triples with a given object ID, answering an (?S,?P,O) query.
 pub struct ObjectIter<'a> {
     triples: &'a TriplesBitmap,
+    cursor: ObjectCursor,
+}
+
+/// Position of an [`ObjectIter`], which can be resumed with any reference to the same triples.
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectCursor {
     o: Id,
     pos_index: usize,
     max_index: usize,
@@ -19,22 +25,39 @@ impl<'a> ObjectIter<'a> {
     /// Panics if the object does not exist.
     pub fn new(triples: &'a TriplesBitmap, o: Id) -> Self {
         assert!(o != 0, "object 0 does not exist, cant iterate");
-        let pos_index = triples.op_index.find(o);
-        let max_index = triples.op_index.last(o);
+        let pos_index = triples.op_index().find(o);
+        let max_index = triples.op_index().last(o);
         //println!("ObjectIter o={} pos_index={} max_index={}", o, pos_index, max_index);
-        ObjectIter { triples, o, pos_index, max_index }
+        ObjectIter { triples, cursor: ObjectCursor { o, pos_index, max_index } }
+    }
+
+    pub(crate) const fn into_cursor(self) -> ObjectCursor {
+        self.cursor
     }
 }
 
 impl Iterator for ObjectIter<'_> {
     type Item = TripleId;
     fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(self.triples)
+    }
+}
+
+impl ObjectCursor {
+    /// Splits the remaining object index positions into at most `parts` consecutive cursors.
+    pub(crate) fn split(&self, parts: usize) -> Vec<Self> {
+        split_range(self.pos_index, self.max_index + 1, parts)
+            .map(|(pos_index, end)| ObjectCursor { o: self.o, pos_index, max_index: end - 1 })
+            .collect()
+    }
+
+    pub(crate) fn next(&mut self, triples: &TriplesBitmap) -> Option<TripleId> {
         if self.pos_index > self.max_index {
             return None;
         }
-        let pos_y = self.triples.op_index.sequence.get(self.pos_index);
-        let y = self.triples.wavelet_y.get(pos_y).unwrap() as Id;
-        let x = self.triples.bitmap_y.rank(pos_y) as Id + 1;
+        let pos_y = triples.op_index().sequence.get(self.pos_index);
+        let y = triples.wavelet_y.get(pos_y).unwrap() as Id;
+        let x = triples.bitmap_y.rank(pos_y) as Id + 1;
         self.pos_index += 1;
         Some([x, y, self.o])
     }
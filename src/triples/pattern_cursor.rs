@@ -0,0 +1,68 @@
+use crate::triples::{
+    ObjectCursor, ObjectIter, PredicateCursor, PredicateIter, PredicateObjectCursor, PredicateObjectIter,
+    SubjectCursor, SubjectIter, TripleId, TriplesBitmap,
+};
+
+/// Position of the iterator that answers a triple ID pattern, which can be resumed with any reference to the same triples.
+/// Chooses the iterator in the same way as [`crate::Hdt::triple_ids_with_id_pattern`].
+#[derive(Debug, Clone)]
+pub(crate) struct PatternCursor {
+    pattern: TripleId,
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    Subject(SubjectCursor),
+    PredicateObject(PredicateObjectCursor),
+    Predicate(PredicateCursor),
+    Object(ObjectCursor),
+    Empty,
+}
+
+impl PatternCursor {
+    /// Cursor at the first triple that fits the given pattern, where 0 stands for a variable.
+    pub(crate) fn new(triples: &TriplesBitmap, pattern: TripleId) -> Self {
+        let [s, p, o] = pattern;
+        // can't use slice: half_open_range_patterns_in_slices is still unstable, see https://github.com/rust-lang/rust/issues/67264
+        let inner = match (s, p, o) {
+            (1.., _, _) => Inner::Subject(SubjectIter::with_pattern(triples, pattern).into_cursor()),
+            (0, 1.., 1..) => Inner::PredicateObject(PredicateObjectIter::new(triples, p, o).into_cursor()),
+            (0, 1.., 0) => Inner::Predicate(PredicateIter::new(triples, p).into_cursor()),
+            (0, 0, 1..) => Inner::Object(ObjectIter::new(triples, o).into_cursor()),
+            (0, 0, 0) => Inner::Subject(SubjectIter::new(triples).into_cursor()),
+        };
+        PatternCursor { pattern, inner }
+    }
+
+    /// Cursor without results, for patterns with a constant that is not in the dictionary.
+    pub(crate) const fn empty() -> Self {
+        PatternCursor { pattern: [0; 3], inner: Inner::Empty }
+    }
+
+    /// Splits the remaining results into at most `parts` consecutive cursors, which together yield the same triples in the same order.
+    /// Must be called before the first step.
+    pub(crate) fn split(&self, triples: &TriplesBitmap, parts: usize) -> Vec<Self> {
+        let with = |inner| PatternCursor { pattern: self.pattern, inner };
+        match &self.inner {
+            Inner::Subject(c) => c.split(triples, parts).into_iter().map(|c| with(Inner::Subject(c))).collect(),
+            Inner::PredicateObject(c) => {
+                c.split(parts).into_iter().map(|c| with(Inner::PredicateObject(c))).collect()
+            }
+            Inner::Predicate(c) => c.split(parts).into_iter().map(|c| with(Inner::Predicate(c))).collect(),
+            Inner::Object(c) => c.split(parts).into_iter().map(|c| with(Inner::Object(c))).collect(),
+            Inner::Empty => Vec::new(),
+        }
+    }
+
+    pub(crate) fn next(&mut self, triples: &TriplesBitmap) -> Option<TripleId> {
+        let [s, p, o] = self.pattern;
+        match &mut self.inner {
+            Inner::Subject(c) => c.next(triples).map(|t| if s == 0 { t } else { [s, t[1], t[2]] }),
+            Inner::PredicateObject(c) => c.next(triples).map(|sid| [sid, p, o]),
+            Inner::Predicate(c) => c.next(triples).map(|t| [t[0], p, t[2]]),
+            Inner::Object(c) => c.next(triples).map(|t| [t[0], t[1], o]),
+            Inner::Empty => None,
+        }
+    }
+}
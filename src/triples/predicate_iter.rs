@@ -1,9 +1,15 @@
-use crate::triples::{Id, TripleId, TriplesBitmap};
+use crate::triples::{Id, TripleId, TriplesBitmap, split_range};
 use qwt::{RankUnsigned, SelectUnsigned};
 
 /// Iterator over all triples with a given property ID, answering an (?S,P,?O) query.
 pub struct PredicateIter<'a> {
     triples: &'a TriplesBitmap,
+    cursor: PredicateCursor,
+}
+
+/// Position of a [`PredicateIter`], which can be resumed with any reference to the same triples.
+#[derive(Debug, Clone)]
+pub(crate) struct PredicateCursor {
     s: Id,
     p: Id,
     i: usize,
@@ -19,24 +25,43 @@ impl<'a> PredicateIter<'a> {
         assert!(p != 0, "object 0 does not exist, cant iterate");
         let occs = triples.wavelet_y.rank(p as usize, triples.wavelet_y.len()).unwrap();
         //println!("the predicate {} is used by {} subjects in the index", p, occs);
-        PredicateIter { triples, p, i: 0, pos_z: 0, os: 0, s: 0, occs }
+        PredicateIter { triples, cursor: PredicateCursor { p, i: 0, pos_z: 0, os: 0, s: 0, occs } }
+    }
+
+    pub(crate) const fn into_cursor(self) -> PredicateCursor {
+        self.cursor
     }
 }
 
 impl Iterator for PredicateIter<'_> {
     type Item = TripleId;
     fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(self.triples)
+    }
+}
+
+impl PredicateCursor {
+    /// Splits the remaining occurrences of the predicate into at most `parts` consecutive cursors.
+    /// Must be called before the first step.
+    pub(crate) fn split(&self, parts: usize) -> Vec<Self> {
+        debug_assert_eq!(self.os, 0);
+        split_range(self.i, self.occs, parts)
+            .map(|(i, occs)| PredicateCursor { s: 0, p: self.p, i, os: 0, pos_z: 0, occs })
+            .collect()
+    }
+
+    pub(crate) fn next(&mut self, triples: &TriplesBitmap) -> Option<TripleId> {
         if self.i >= self.occs {
             return None;
         }
         if self.os == 0 {
             // Algorithm 1 findSubj from Martinez et al. 2012 ******
-            let pos_y = self.triples.wavelet_y.select(self.p as usize, self.i).unwrap();
-            self.s = self.triples.bitmap_y.rank(pos_y) as Id + 1;
+            let pos_y = triples.wavelet_y.select(self.p as usize, self.i).unwrap();
+            self.s = triples.bitmap_y.rank(pos_y) as Id + 1;
             // *****************************************************
             // SP can have multiple O
-            self.pos_z = self.triples.adjlist_z.find(pos_y as Id);
-            let pos_z_end = self.triples.adjlist_z.last(pos_y as Id);
+            self.pos_z = triples.adjlist_z.find(pos_y);
+            let pos_z_end = triples.adjlist_z.last(pos_y);
             //println!("**** found predicate {} between {} and {} (inclusive)", self.p, self.pos_z, pos_z_end);
             self.os = pos_z_end - self.pos_z;
         } else {
@@ -44,10 +69,10 @@ impl Iterator for PredicateIter<'_> {
             self.pos_z += 1;
         }
 
-        let o = self.triples.adjlist_z.sequence.get(self.pos_z) as Id;
+        let o = triples.adjlist_z.sequence.get(self.pos_z) as Id;
         if self.os == 0 {
             self.i += 1;
         }
-        Some(self.triples.coord_to_triple(self.s, self.p, o).unwrap())
+        Some(triples.coord_to_triple(self.s, self.p, o).unwrap())
     }
 }
@@ -1,4 +1,4 @@
-use crate::triples::{Id, TriplesBitmap};
+use crate::triples::{Id, TriplesBitmap, split_range};
 use qwt::AccessUnsigned;
 use std::cmp::Ordering;
 
@@ -8,6 +8,12 @@ use std::cmp::Ordering;
 /// Iterator over all subject IDs with a given predicate and object ID, answering an (?S,P,O) query.
 pub struct PredicateObjectIter<'a> {
     triples: &'a TriplesBitmap,
+    cursor: PredicateObjectCursor,
+}
+
+/// Position of a [`PredicateObjectIter`], which can be resumed with any reference to the same triples.
+#[derive(Debug, Clone)]
+pub(crate) struct PredicateObjectCursor {
     pos_index: usize,
     max_index: usize,
 }
@@ -18,10 +24,10 @@ impl<'a> PredicateObjectIter<'a> {
     pub fn new(triples: &'a TriplesBitmap, p: Id, o: Id) -> Self {
         assert_ne!(0, p, "predicate 0 does not exist, cant iterate");
         assert_ne!(0, o, "object 0 does not exist, cant iterate");
-        let mut low = triples.op_index.find(o);
-        let mut high = triples.op_index.last(o);
+        let mut low = triples.op_index().find(o);
+        let mut high = triples.op_index().last(o);
         let get_y = |pos_index| {
-            let pos_y = triples.op_index.sequence.get(pos_index);
+            let pos_y = triples.op_index().sequence.get(pos_index);
             triples.wavelet_y.get(pos_y).unwrap() as Id
         };
         // Binary search with a twist:
@@ -53,7 +59,10 @@ impl<'a> PredicateObjectIter<'a> {
                             _ => right_low = mid,
                         }
                     }
-                    return PredicateObjectIter { triples, pos_index: low, max_index: high };
+                    return PredicateObjectIter {
+                        triples,
+                        cursor: PredicateObjectCursor { pos_index: low, max_index: high },
+                    };
                 }
             }
             if (high == 0 && low == 0) || (high == low && high == mid) {
@@ -61,20 +70,37 @@ impl<'a> PredicateObjectIter<'a> {
             }
         }
         // not found
-        PredicateObjectIter { triples, pos_index: 999, max_index: 0 }
+        PredicateObjectIter { triples, cursor: PredicateObjectCursor { pos_index: 999, max_index: 0 } }
+    }
+
+    pub(crate) const fn into_cursor(self) -> PredicateObjectCursor {
+        self.cursor
     }
 }
 
 impl Iterator for PredicateObjectIter<'_> {
     type Item = Id;
     fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(self.triples)
+    }
+}
+
+impl PredicateObjectCursor {
+    /// Splits the remaining object index positions into at most `parts` consecutive cursors.
+    pub(crate) fn split(&self, parts: usize) -> Vec<Self> {
+        split_range(self.pos_index, self.max_index + 1, parts)
+            .map(|(pos_index, end)| PredicateObjectCursor { pos_index, max_index: end - 1 })
+            .collect()
+    }
+
+    pub(crate) fn next(&mut self, triples: &TriplesBitmap) -> Option<Id> {
         if self.pos_index > self.max_index {
             return None;
         }
-        let pos_y = self.triples.op_index.sequence.get(self.pos_index);
-        //let y = self.triples.wavelet_y.get(pos_y as usize) as Id;
+        let pos_y = triples.op_index().sequence.get(self.pos_index);
+        //let y = triples.wavelet_y.get(pos_y as usize) as Id;
         //println!(" op p {y}");
-        let s = self.triples.bitmap_y.rank(pos_y) as Id + 1;
+        let s = triples.bitmap_y.rank(pos_y) as Id + 1;
         self.pos_index += 1;
         Some(s)
     }
@@ -1,4 +1,4 @@
-use super::{Id, TripleId, TriplesBitmap};
+use super::{Id, TripleId, TriplesBitmap, split_range};
 use qwt::AccessUnsigned;
 
 /// Iterator over triples fitting an SPO, SP? S?? or ??? triple pattern.
@@ -6,6 +6,12 @@ use qwt::AccessUnsigned;
 pub struct SubjectIter<'a> {
     // triples data
     triples: &'a TriplesBitmap,
+    cursor: SubjectCursor,
+}
+
+/// Position of a [`SubjectIter`], which can be resumed with any reference to the same triples.
+#[derive(Debug, Clone)]
+pub(crate) struct SubjectCursor {
     // x-coordinate identifier
     x: Id,
     // current position
@@ -13,36 +19,40 @@ pub struct SubjectIter<'a> {
     pos_z: usize,
     max_y: usize,
     max_z: usize,
-    search_z: usize, // for S?O
+    search_z: Id, // for S?O
 }
 
 impl<'a> SubjectIter<'a> {
     /// Create an iterator over all triples.
     pub fn new(triples: &'a TriplesBitmap) -> Self {
-        SubjectIter {
-            triples,
+        let cursor = SubjectCursor {
             x: 1, // was 0 in the old code but it should start at 1
             pos_y: 0,
             pos_z: 0,
             max_y: triples.wavelet_y.len(), // exclusive
             max_z: triples.adjlist_z.len(), // exclusive
             search_z: 0,
-        }
+        };
+        SubjectIter { triples, cursor }
     }
 
     /// Use when no results are found.
     pub const fn empty(triples: &'a TriplesBitmap) -> Self {
-        SubjectIter { triples, x: 1, pos_y: 0, pos_z: 0, max_y: 0, max_z: 0, search_z: 0 }
+        SubjectIter {
+            triples,
+            cursor: SubjectCursor { x: 1, pos_y: 0, pos_z: 0, max_y: 0, max_z: 0, search_z: 0 },
+        }
     }
 
     /// Convenience method for the S?? triple pattern.
     /// See <https://github.com/rdfhdt/hdt-cpp/blob/develop/libhdt/src/triples/BitmapTriplesIterators.cpp>.
     pub fn with_s(triples: &'a TriplesBitmap, subject_id: Id) -> Self {
         let min_y = triples.find_y(subject_id - 1);
-        let min_z = triples.adjlist_z.find(min_y as Id);
+        let min_z = triples.adjlist_z.find(min_y);
         let max_y = triples.find_y(subject_id);
-        let max_z = triples.adjlist_z.find(max_y as Id);
-        SubjectIter { triples, x: subject_id, pos_y: min_y, pos_z: min_z, max_y, max_z, search_z: 0 }
+        let max_z = triples.adjlist_z.find(max_y);
+        let cursor = SubjectCursor { x: subject_id, pos_y: min_y, pos_z: min_z, max_y, max_z, search_z: 0 };
+        SubjectIter { triples, cursor }
     }
 
     /// Iterate over triples fitting the given SPO, SP? S??, S?O or ??? triple pattern.
@@ -103,7 +113,11 @@ impl<'a> SubjectIter<'a> {
             max_y = triples.wavelet_y.len();
             max_z = triples.adjlist_z.len();
         }
-        SubjectIter { triples, x, pos_y: min_y, pos_z: min_z, max_y, max_z, search_z }
+        SubjectIter { triples, cursor: SubjectCursor { x, pos_y: min_y, pos_z: min_z, max_y, max_z, search_z } }
+    }
+
+    pub(crate) const fn into_cursor(self) -> SubjectCursor {
+        self.cursor
     }
 }
 
@@ -111,20 +125,40 @@ impl Iterator for SubjectIter<'_> {
     type Item = TripleId;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(self.triples)
+    }
+}
+
+impl SubjectCursor {
+    /// Splits the remaining predicate layer positions into at most `parts` consecutive cursors.
+    pub(crate) fn split(&self, triples: &TriplesBitmap, parts: usize) -> Vec<Self> {
+        split_range(self.pos_y, self.max_y, parts)
+            .map(|(min_y, max_y)| SubjectCursor {
+                x: if min_y == self.pos_y { self.x } else { triples.bitmap_y.rank(min_y) as Id + 1 },
+                pos_y: min_y,
+                pos_z: if min_y == self.pos_y { self.pos_z } else { triples.adjlist_z.find(min_y) },
+                max_y,
+                max_z: if max_y == self.max_y { self.max_z } else { triples.adjlist_z.find(max_y) },
+                search_z: self.search_z,
+            })
+            .collect()
+    }
+
+    pub(crate) fn next(&mut self, triples: &TriplesBitmap) -> Option<TripleId> {
         if self.pos_y >= self.max_y {
             return None;
         }
 
-        let y = self.triples.wavelet_y.get(self.pos_y).unwrap() as Id;
+        let y = triples.wavelet_y.get(self.pos_y).unwrap() as Id;
 
         if self.search_z > 0 {
             self.pos_y += 1;
-            match self.triples.adjlist_z.search(self.pos_y - 1, self.search_z) {
+            match triples.adjlist_z.search(self.pos_y - 1, self.search_z) {
                 Some(_) => {
-                    return Some(self.triples.coord_to_triple(self.x, y, self.search_z).unwrap());
+                    return Some(triples.coord_to_triple(self.x, y, self.search_z).unwrap());
                 }
                 None => {
-                    return self.next();
+                    return self.next(triples);
                 }
             }
         }
@@ -132,13 +166,13 @@ impl Iterator for SubjectIter<'_> {
         if self.pos_z >= self.max_z {
             return None;
         }
-        let z = self.triples.adjlist_z.get_id(self.pos_z);
-        let triple_id = self.triples.coord_to_triple(self.x, y, z).unwrap();
+        let z = triples.adjlist_z.get_id(self.pos_z);
+        let triple_id = triples.coord_to_triple(self.x, y, z).unwrap();
 
         // theoretically the second condition should only be true if the first is as well but in practise it wasn't, which screwed up the subject identifiers
         // fixed by moving the second condition inside the first one but there may be another reason for the bug occuring in the first place
-        if self.triples.adjlist_z.at_last_sibling(self.pos_z) {
-            if self.triples.bitmap_y.at_last_sibling(self.pos_y) {
+        if triples.adjlist_z.at_last_sibling(self.pos_z) {
+            if triples.bitmap_y.at_last_sibling(self.pos_y) {
                 self.x += 1;
             }
             self.pos_y += 1;
@@ -0,0 +1,394 @@
+//! Streaming Turtle serialization that uses the SPO order of the triples section to group them by subject and predicate.
+//! In contrast to a pretty printer over an in-memory graph, the memory usage does not depend on the number of triples.
+//! # Example
+//! ```
+//! use hdt::turtle::TurtleOptions;
+//! let file = std::fs::File::open("tests/resources/snikmeta.hdt").unwrap();
+//! let hdt = hdt::Hdt::read(std::io::BufReader::new(file)).unwrap();
+//! let mut turtle = Vec::new();
+//! hdt.write_turtle(&mut turtle, &TurtleOptions { inline_blank_nodes: true, ..Default::default() }).unwrap();
+//! let turtle = String::from_utf8(turtle).unwrap();
+//! assert!(turtle.contains("@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> ."));
+//! assert!(turtle.contains(" [\n"));
+//! ```
+use crate::prefixes::{DEFAULT_PREFIXES, namespace};
+use crate::triples::Id;
+use crate::vocab::RDF_TYPE;
+use crate::{Hdt, IdKind};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const INDENT: &str = "    ";
+/// Maximum nesting depth of inlined blank nodes, deeper ones are labelled instead,
+/// so that long chains such as RDF lists neither exhaust the stack nor get indented ever further.
+const MAX_INLINE_DEPTH: u32 = 16;
+
+/// Options for [`Hdt::write_turtle`].
+#[derive(Debug, Clone)]
+pub struct TurtleOptions {
+    /// Number of the most common namespaces of the dictionary that are declared as prefixes, see [`Hdt::common_prefixes`].
+    pub prefixes: usize,
+    /// Writes blank nodes that occur exactly once as an object inside square brackets at that position
+    /// instead of labelling them, for example for OWL restrictions.
+    /// Requires a lookup in the object index for each blank node object and memory for the nesting depth of each of them.
+    /// Blank nodes nested deeper than 16 levels are labelled.
+    pub inline_blank_nodes: bool,
+}
+
+impl Default for TurtleOptions {
+    fn default() -> Self {
+        Self { prefixes: DEFAULT_PREFIXES, inline_blank_nodes: false }
+    }
+}
+
+/// Whether a local name can be written as part of a prefixed name without escaping.
+fn valid_local(local: &str) -> bool {
+    let valid = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+    local.chars().all(valid) && !local.starts_with(['-', '.']) && !local.ends_with('.')
+}
+
+/// Writes an IRI in angle brackets, escaping the characters that are not allowed there.
+fn write_iriref(w: &mut impl Write, iri: &str) -> io::Result<()> {
+    w.write_all(b"<")?;
+    for c in iri.chars() {
+        if c <= ' ' || "<>\"{}|^`\\".contains(c) {
+            write!(w, "\\u{:04X}", c as u32)?;
+        } else {
+            write!(w, "{c}")?;
+        }
+    }
+    w.write_all(b">")
+}
+
+struct TurtleWriter<'a, W: Write> {
+    hdt: &'a Hdt,
+    write: W,
+    /// prefix names by namespace
+    prefixes: HashMap<String, String>,
+    inline_blank_nodes: bool,
+    shared: Id,
+    /// nesting depth of the blank node objects that were already checked, 0 if they are labelled
+    depths: HashMap<Id, u32>,
+}
+
+impl<W: Write> TurtleWriter<'_, W> {
+    fn string(&self, id: Id, kind: IdKind) -> io::Result<String> {
+        self.hdt.dict.id_to_string(id, kind).map_err(io::Error::other)
+    }
+
+    fn write_iri(&mut self, iri: &str) -> io::Result<()> {
+        if let Some(ns) = namespace(iri) {
+            let local = &iri[ns.len()..];
+            if let Some(name) = self.prefixes.get(ns).filter(|_| valid_local(local)) {
+                return write!(self.write, "{name}:{local}");
+            }
+        }
+        write_iriref(&mut self.write, iri)
+    }
+
+    /// Writes an IRI, blank node or literal in the HDT dictionary format.
+    fn write_term(&mut self, term: &str) -> io::Result<()> {
+        if term.starts_with("_:") {
+            return self.write.write_all(term.as_bytes());
+        }
+        if !term.starts_with('"') {
+            return self.write_iri(term);
+        }
+        let Some(end) = term.rfind('"').filter(|&end| end > 0) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid literal {term}")));
+        };
+        let (value, suffix) = (&term[1..end], &term[end + 1..]);
+        if let Some(datatype) = suffix.strip_prefix("^^<").and_then(|dt| dt.strip_suffix('>')) {
+            let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+            let integer =
+                datatype == XSD_INTEGER && !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+            if integer || (datatype == XSD_BOOLEAN && (value == "true" || value == "false")) {
+                return self.write.write_all(value.as_bytes());
+            }
+        }
+        self.write.write_all(b"\"")?;
+        // literals of files created by other tools may contain unescaped line breaks
+        let mut start = 0;
+        for (i, b) in value.bytes().enumerate() {
+            let escaped: &[u8] = match b {
+                b'\n' => b"\\n",
+                b'\r' => b"\\r",
+                _ => continue,
+            };
+            self.write.write_all(&value.as_bytes()[start..i])?;
+            self.write.write_all(escaped)?;
+            start = i + 1;
+        }
+        self.write.write_all(&value.as_bytes()[start..])?;
+        self.write.write_all(b"\"")?;
+        match suffix.strip_prefix("^^<").and_then(|dt| dt.strip_suffix('>')) {
+            Some(datatype) => {
+                self.write.write_all(b"^^")?;
+                self.write_iri(datatype)
+            }
+            None => self.write.write_all(suffix.as_bytes()),
+        }
+    }
+
+    fn is_blank(&self, id: Id, kind: IdKind) -> io::Result<bool> {
+        Ok(self.string(id, kind)?.starts_with("_:"))
+    }
+
+    /// Subject of the only triple with the given object or `None` if there are zero or several such triples.
+    fn only_referrer(&self, o: Id) -> Option<Id> {
+        let mut triples = self.hdt.triple_ids_with_id_pattern([0, 0, o]);
+        let first = triples.next()?;
+        triples.next().is_none().then_some(first[0])
+    }
+
+    /// The only referrer of a blank node object, which is a candidate for being inlined.
+    fn inline_candidate(&self, o: Id) -> io::Result<Option<Id>> {
+        if !self.is_blank(o, IdKind::Object)? {
+            return Ok(None);
+        }
+        Ok(self.only_referrer(o))
+    }
+
+    /// Nesting depth at which the blank node with the given object ID is written inside square brackets
+    /// at its only occurrence as an object, or 0 if it is labelled instead.
+    /// Walks up the chain of referrers iteratively and memoizes the depths of all nodes on it,
+    /// so that each blank node is only looked up once.
+    fn inline_depth(&mut self, o: Id) -> io::Result<u32> {
+        if !self.inline_blank_nodes {
+            return Ok(0);
+        }
+        if let Some(&depth) = self.depths.get(&o) {
+            return Ok(depth);
+        }
+        // candidates from o upwards with their position in the chain
+        let mut chain = Vec::new();
+        let mut positions = HashMap::new();
+        let mut current = o;
+        let base = loop {
+            if let Some(&depth) = self.depths.get(&current) {
+                break depth;
+            }
+            let Some(referrer) = self.inline_candidate(current)? else {
+                self.depths.insert(current, 0);
+                break 0;
+            };
+            positions.insert(current, chain.len());
+            chain.push(current);
+            // subject IDs beyond the shared section never occur as objects
+            if referrer > self.shared || !self.is_blank(referrer, IdKind::Subject)? {
+                break 0;
+            }
+            // blank nodes on a cycle of such blank nodes are labelled, as otherwise none of them would be written
+            if let Some(&i) = positions.get(&referrer) {
+                for node in chain.drain(i..) {
+                    self.depths.insert(node, 0);
+                }
+                break 0;
+            }
+            current = referrer;
+        };
+        let mut depth = base;
+        for &node in chain.iter().rev() {
+            depth = (depth + 1) % (MAX_INLINE_DEPTH + 1);
+            self.depths.insert(node, depth);
+        }
+        Ok(self.depths[&o])
+    }
+
+    /// Writes the predicates and objects of a subject, grouped with `;` and `,`, at the given nesting depth.
+    fn write_description(&mut self, s: Id, depth: usize) -> io::Result<()> {
+        let mut last_p = 0;
+        for [_, p, o] in self.hdt.triple_ids_with_id_pattern([s, 0, 0]) {
+            if p == last_p {
+                self.write.write_all(b", ")?;
+            } else {
+                if last_p != 0 {
+                    write!(self.write, " ;\n{}", INDENT.repeat(depth + 1))?;
+                } else if depth > 0 {
+                    write!(self.write, "\n{}", INDENT.repeat(depth + 1))?;
+                } else {
+                    self.write.write_all(b" ")?;
+                }
+                let predicate = self.string(p, IdKind::Predicate)?;
+                if predicate == RDF_TYPE {
+                    self.write.write_all(b"a")?;
+                } else {
+                    self.write_iri(&predicate)?;
+                }
+                self.write.write_all(b" ")?;
+                last_p = p;
+            }
+            if self.inline_depth(o)? > 0 {
+                self.write.write_all(b"[")?;
+                // blank nodes outside of the shared section have no description
+                if o <= self.shared {
+                    self.write_description(o, depth + 1)?;
+                    write!(self.write, "\n{}", INDENT.repeat(depth + 1))?;
+                }
+                self.write.write_all(b"]")?;
+            } else {
+                let object = self.string(o, IdKind::Object)?;
+                self.write_term(&object)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all(&mut self, prefixes: &[(String, String)]) -> io::Result<()> {
+        for (name, namespace) in prefixes {
+            write!(self.write, "@prefix {name}: ")?;
+            write_iriref(&mut self.write, namespace)?;
+            self.write.write_all(b" .\n")?;
+        }
+        let subjects = self.shared as usize + self.hdt.dict.subjects.num_strings();
+        for s in 1..=subjects as Id {
+            let subject = self.string(s, IdKind::Subject)?;
+            if s <= self.shared && subject.starts_with("_:") && self.inline_depth(s)? > 0 {
+                continue;
+            }
+            // separate prefixes and subjects by blank lines
+            if s > 1 || !prefixes.is_empty() {
+                self.write.write_all(b"\n")?;
+            }
+            self.write_term(&subject)?;
+            self.write_description(s, 0)?;
+            self.write.write_all(b" .\n")?;
+        }
+        self.write.flush()
+    }
+}
+
+impl Hdt {
+    /// Writes all triples as Turtle, grouping objects of the same subject and predicate with `,`
+    /// and predicates of the same subject with `;`.
+    /// The triples are streamed subject by subject, so the memory usage is independent of the size of the HDT.
+    pub fn write_turtle(&self, write: impl Write, options: &TurtleOptions) -> io::Result<()> {
+        let prefixes = if options.prefixes > 0 { self.common_prefixes(options.prefixes) } else { Vec::new() };
+        let mut writer = TurtleWriter {
+            hdt: self,
+            write,
+            prefixes: prefixes.iter().map(|(name, ns)| (ns.clone(), name.clone())).collect(),
+            inline_blank_nodes: options.inline_blank_nodes,
+            shared: self.dict.shared.num_strings() as Id,
+            depths: HashMap::new(),
+        };
+        writer.write_all(&prefixes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init;
+
+    #[test]
+    fn terms() {
+        assert!(valid_local("Top"));
+        assert!(valid_local("a.b-c_1"));
+        assert!(!valid_local("a."));
+        assert!(!valid_local("a/b"));
+        let mut iri = Vec::new();
+        write_iriref(&mut iri, "http://example.org/a b").unwrap();
+        assert_eq!(iri, b"<http://example.org/a\\u0020b>");
+    }
+
+    /// Parses Turtle into sorted triples in the HDT dictionary format, with blank node labels replaced by "_:".
+    #[cfg(feature = "nt")]
+    fn parse(turtle: &[u8]) -> color_eyre::Result<Vec<[String; 3]>> {
+        let term = |t: String| if t.starts_with("_:") { "_:".to_owned() } else { t };
+        let mut triples = Vec::new();
+        let iri =
+            |t: String| t.strip_prefix('<').and_then(|t| t.strip_suffix('>')).map(str::to_owned).unwrap_or(t);
+        for t in oxttl::TurtleParser::new().for_slice(turtle) {
+            let t = t?;
+            triples.push([
+                term(iri(t.subject.to_string())),
+                t.predicate.into_string(),
+                term(iri(t.object.to_string())),
+            ]);
+        }
+        triples.sort_unstable();
+        Ok(triples)
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn snikmeta() -> color_eyre::Result<()> {
+        init();
+        let hdt = crate::hdt::tests::snikmeta()?;
+        let mut expected: Vec<[String; 3]> = hdt
+            .triples_all()
+            .map(|t| t.map(|t| if t.starts_with("_:") { "_:".to_owned() } else { t.to_string() }))
+            .collect();
+        expected.sort_unstable();
+        for inline_blank_nodes in [false, true] {
+            let mut turtle = Vec::new();
+            hdt.write_turtle(&mut turtle, &TurtleOptions { inline_blank_nodes, ..Default::default() })?;
+            assert_eq!(parse(&turtle)?, expected);
+            let turtle = String::from_utf8(turtle)?;
+            assert!(turtle.contains(" ;\n    rdfs:label "), "{turtle}");
+            assert_eq!(turtle.contains("_:"), !inline_blank_nodes);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn blank_nodes() -> color_eyre::Result<()> {
+        init();
+        let ex = |s: &str| format!("http://example.org/{s}");
+        let triples = [
+            [ex("s"), ex("p"), "_:chain1".to_owned()],
+            ["_:chain1".to_owned(), ex("p"), "_:chain2".to_owned()],
+            ["_:chain2".to_owned(), ex("p"), "\"line\nbreak\"".to_owned()],
+            ["_:chain2".to_owned(), ex("q"), "\"42\"^^<http://www.w3.org/2001/XMLSchema#integer>".to_owned()],
+            ["_:chain2".to_owned(), ex("q"), "\"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>".to_owned()],
+            ["_:cycle1".to_owned(), ex("p"), "_:cycle2".to_owned()],
+            ["_:cycle2".to_owned(), ex("p"), "_:cycle1".to_owned()],
+            [ex("s"), ex("q"), "_:shared".to_owned()],
+            [ex("t"), ex("q"), "_:shared".to_owned()],
+            [ex("t"), ex("r"), "_:leaf".to_owned()],
+        ];
+        let hdt = Hdt::from_triples(triples.clone(), "http://example.org/dataset")?;
+        let mut turtle = Vec::new();
+        hdt.write_turtle(&mut turtle, &TurtleOptions { prefixes: 0, inline_blank_nodes: true })?;
+        let mut expected: Vec<[String; 3]> = triples
+            .map(|t| t.map(|t| if t.starts_with("_:") { "_:".to_owned() } else { t.replace('\n', "\\n") }))
+            .to_vec();
+        expected.sort_unstable();
+        assert_eq!(parse(&turtle)?, expected);
+        let turtle = String::from_utf8(turtle)?;
+        assert!(turtle.contains("<http://example.org/r> []"), "{turtle}");
+        assert!(turtle.contains("42, true"), "{turtle}");
+        assert!(turtle.contains("\"line\\nbreak\""), "{turtle}");
+        assert!(turtle.contains("_:cycle1") && turtle.contains("_:cycle2") && turtle.contains("_:shared"));
+        assert!(!turtle.contains("_:chain") && !turtle.contains("_:leaf"), "{turtle}");
+        Ok(())
+    }
+
+    #[cfg(feature = "nt")]
+    #[test]
+    fn long_list() -> color_eyre::Result<()> {
+        const N: usize = 100_000;
+        init();
+        let rdf = |s: &str| format!("http://www.w3.org/1999/02/22-rdf-syntax-ns#{s}");
+        let mut triples =
+            vec![["http://example.org/s".to_owned(), "http://example.org/p".to_owned(), "_:l0".to_owned()]];
+        for i in 0..N {
+            let rest = if i + 1 < N { format!("_:l{}", i + 1) } else { rdf("nil") };
+            triples.push([format!("_:l{i}"), rdf("first"), format!("\"{i}\"")]);
+            triples.push([format!("_:l{i}"), rdf("rest"), rest]);
+        }
+        let hdt = Hdt::from_triples(triples.clone(), "http://example.org/dataset")?;
+        let mut turtle = Vec::new();
+        hdt.write_turtle(&mut turtle, &TurtleOptions { prefixes: 0, inline_blank_nodes: true })?;
+        assert_eq!(parse(&turtle)?.len(), triples.len());
+        let turtle = String::from_utf8(turtle)?;
+        let max_indent = turtle.lines().map(|l| l.len() - l.trim_start().len()).max().unwrap_or_default();
+        assert!(max_indent <= INDENT.len() * (MAX_INLINE_DEPTH as usize + 1), "{max_indent}");
+        assert!(turtle.contains(" ["));
+        Ok(())
+    }
+}
@@ -22,3 +22,30 @@ pub const HDT_DICT_BLOCK_SIZE: &str = "http://purl.org/HDT/hdt#dictionaryblockSi
 pub const HDT_TYPE_BITMAP: &str = "http://purl.org/HDT/hdt#triplesBitmap";
 pub const HDT_DICTIONARY_TYPE_FOUR: &str = "http://purl.org/HDT/hdt#dictionaryFour";
 pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+pub const VOID_ENTITIES: &str = "http://rdfs.org/ns/void#entities";
+pub const VOID_CLASSES: &str = "http://rdfs.org/ns/void#classes";
+pub const VOID_CLASS: &str = "http://rdfs.org/ns/void#class";
+pub const VOID_PROPERTY: &str = "http://rdfs.org/ns/void#property";
+pub const VOID_CLASS_PARTITION: &str = "http://rdfs.org/ns/void#classPartition";
+pub const VOID_PROPERTY_PARTITION: &str = "http://rdfs.org/ns/void#propertyPartition";
+pub const SD_SERVICE: &str = "http://www.w3.org/ns/sparql-service-description#Service";
+pub const SD_ENDPOINT: &str = "http://www.w3.org/ns/sparql-service-description#endpoint";
+pub const SD_SUPPORTED_LANGUAGE: &str = "http://www.w3.org/ns/sparql-service-description#supportedLanguage";
+pub const SD_SPARQL11_QUERY: &str = "http://www.w3.org/ns/sparql-service-description#SPARQL11Query";
+pub const SD_RESULT_FORMAT: &str = "http://www.w3.org/ns/sparql-service-description#resultFormat";
+pub const SD_DEFAULT_DATASET: &str = "http://www.w3.org/ns/sparql-service-description#defaultDataset";
+pub const SD_DATASET: &str = "http://www.w3.org/ns/sparql-service-description#Dataset";
+pub const SD_DEFAULT_GRAPH: &str = "http://www.w3.org/ns/sparql-service-description#defaultGraph";
+pub const SD_GRAPH: &str = "http://www.w3.org/ns/sparql-service-description#Graph";
+pub const FORMAT_SPARQL_RESULTS_JSON: &str = "http://www.w3.org/ns/formats/SPARQL_Results_JSON";
+pub const FORMAT_SPARQL_RESULTS_XML: &str = "http://www.w3.org/ns/formats/SPARQL_Results_XML";
+pub const FORMAT_SPARQL_RESULTS_CSV: &str = "http://www.w3.org/ns/formats/SPARQL_Results_CSV";
+pub const FORMAT_SPARQL_RESULTS_TSV: &str = "http://www.w3.org/ns/formats/SPARQL_Results_TSV";
+pub const FORMAT_N_TRIPLES: &str = "http://www.w3.org/ns/formats/N-Triples";
+pub const FORMAT_TURTLE: &str = "http://www.w3.org/ns/formats/Turtle";
+pub const RDFS_SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+pub const RDFS_SUB_PROPERTY_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subPropertyOf";
+pub const RDFS_DOMAIN: &str = "http://www.w3.org/2000/01/rdf-schema#domain";
+pub const RDFS_RANGE: &str = "http://www.w3.org/2000/01/rdf-schema#range";
@@ -0,0 +1,203 @@
+//! VoID and SPARQL service descriptions, see [`Hdt::void_description`] and [`Hdt::service_description`].
+use crate::containers::rdf::{Id, Literal, Term, Triple};
+use crate::triples::PredicateIter;
+use crate::vocab::*;
+use crate::{Hdt, IdKind};
+use log::error;
+use qwt::RankUnsigned;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Collects triples in the same format as the HDT header.
+struct Description(BTreeSet<Triple>);
+
+impl Description {
+    fn count(&mut self, s: &Id, p: &str, n: usize) {
+        self.0.insert(Triple::new(s.clone(), p.to_owned(), Term::Literal(Literal::new(n.to_string()))));
+    }
+
+    fn id(&mut self, s: &Id, p: &str, o: Id) {
+        self.0.insert(Triple::new(s.clone(), p.to_owned(), Term::Id(o)));
+    }
+
+    fn iri(&mut self, s: &Id, p: &str, o: &str) {
+        self.id(s, p, Id::Named(o.to_owned()));
+    }
+}
+
+impl Hdt {
+    /// Describes the dataset using the VoID vocabulary with the given base IRI as the `void:Dataset`.
+    /// Includes `void:triples`, `void:entities` (distinct IRI subjects), `void:classes`, `void:properties`,
+    /// `void:distinctSubjects` and `void:distinctObjects` as well as a class partition for each `rdf:type` object
+    /// and a property partition for each predicate.
+    /// Everything except for the entities is computed in ID space without decoding the dictionary.
+    /// See [`Hdt::add_void_to_header`] to store the description in the header.
+    /// # Example
+    /// ```
+    /// let hdt = hdt::Hdt::read_from_path("tests/resources/snikmeta.hdt").unwrap();
+    /// for triple in hdt.void_description("http://www.snik.eu/ontology/meta") {
+    ///     println!("{triple}");
+    /// }
+    /// ```
+    pub fn void_description(&self, base_iri: &str) -> BTreeSet<Triple> {
+        let dict = &self.dict;
+        let base = Id::Named(base_iri.to_owned());
+        let mut d = Description(BTreeSet::new());
+        let shared = dict.shared.num_strings();
+        let entities = (1..=shared as u32)
+            .map(|id| dict.shared.extract(id))
+            .chain((1..=dict.subjects.num_strings() as u32).map(|id| dict.subjects.extract(id)))
+            .filter(|s| match s {
+                Ok(s) => !s.starts_with("_:"),
+                Err(e) => {
+                    error!("{e}");
+                    false
+                }
+            })
+            .count();
+        // rdf:type objects with their number of instances, a triple set has no duplicate subjects per class
+        let mut classes = BTreeMap::<u32, usize>::new();
+        let type_id = dict.string_to_id(RDF_TYPE, IdKind::Predicate);
+        if type_id != 0 {
+            for [_, _, o] in PredicateIter::new(&self.triples, type_id) {
+                *classes.entry(o).or_default() += 1;
+            }
+        }
+
+        d.iri(&base, RDF_TYPE, VOID_DATASET);
+        d.count(&base, VOID_TRIPLES, self.triples.len());
+        d.count(&base, VOID_ENTITIES, entities);
+        d.count(&base, VOID_CLASSES, classes.len());
+        d.count(&base, VOID_PROPERTIES, dict.predicates.num_strings());
+        d.count(&base, VOID_DISTINCT_SUBJECTS, shared + dict.subjects.num_strings());
+        d.count(&base, VOID_DISTINCT_OBJECTS, shared + dict.objects.num_strings());
+
+        for (o, instances) in classes {
+            let class = match dict.id_to_string(o, IdKind::Object) {
+                Ok(class) => class,
+                Err(e) => {
+                    error!("{e}");
+                    continue;
+                }
+            };
+            let partition = Id::Blank(format!("classPartition{o}"));
+            d.id(&base, VOID_CLASS_PARTITION, partition.clone());
+            d.iri(&partition, VOID_CLASS, &class);
+            d.count(&partition, VOID_ENTITIES, instances);
+        }
+
+        let wavelet_y = &self.triples.wavelet_y;
+        for (p, triples) in self.predicate_triple_counts().into_iter().enumerate().skip(1) {
+            let property = match dict.id_to_string(p as u32, IdKind::Predicate) {
+                Ok(property) => property,
+                Err(e) => {
+                    error!("{e}");
+                    continue;
+                }
+            };
+            let partition = Id::Blank(format!("propertyPartition{p}"));
+            d.id(&base, VOID_PROPERTY_PARTITION, partition.clone());
+            d.iri(&partition, VOID_PROPERTY, &property);
+            d.count(&partition, VOID_TRIPLES, triples);
+            // each subject occurs at most once per predicate in the wavelet matrix
+            d.count(&partition, VOID_DISTINCT_SUBJECTS, wavelet_y.rank(p, wavelet_y.len()).unwrap_or(0));
+        }
+        d.0
+    }
+
+    /// Describes a SPARQL endpoint serving this dataset as its default graph using the SPARQL 1.1 Service Description vocabulary.
+    /// The dataset itself is identified by the base IRI as in [`Hdt::void_description`].
+    pub fn service_description(&self, endpoint_iri: &str, base_iri: &str) -> BTreeSet<Triple> {
+        let service = Id::Named(endpoint_iri.to_owned());
+        let dataset = Id::Blank("defaultDataset".to_owned());
+        let graph = Id::Named(base_iri.to_owned());
+        let mut d = Description(BTreeSet::new());
+        d.iri(&service, RDF_TYPE, SD_SERVICE);
+        d.iri(&service, SD_ENDPOINT, endpoint_iri);
+        d.iri(&service, SD_SUPPORTED_LANGUAGE, SD_SPARQL11_QUERY);
+        for format in [
+            FORMAT_SPARQL_RESULTS_JSON, FORMAT_SPARQL_RESULTS_XML, FORMAT_SPARQL_RESULTS_CSV,
+            FORMAT_SPARQL_RESULTS_TSV, FORMAT_N_TRIPLES, FORMAT_TURTLE,
+        ] {
+            d.iri(&service, SD_RESULT_FORMAT, format);
+        }
+        d.id(&service, SD_DEFAULT_DATASET, dataset.clone());
+        d.iri(&dataset, RDF_TYPE, SD_DATASET);
+        d.id(&dataset, SD_DEFAULT_GRAPH, graph.clone());
+        d.iri(&graph, RDF_TYPE, SD_GRAPH);
+        d.count(&graph, VOID_TRIPLES, self.triples.len());
+        d.0
+    }
+
+    /// Adds the [VoID description](Hdt::void_description) to the header so that it is included when writing the HDT file.
+    pub fn add_void_to_header(&mut self, base_iri: &str) {
+        let void = self.void_description(base_iri);
+        self.extend_header(void);
+    }
+
+    /// Adds the [service description](Hdt::service_description) to the header so that it is included when writing the HDT file.
+    pub fn add_service_description_to_header(&mut self, endpoint_iri: &str, base_iri: &str) {
+        let sd = self.service_description(endpoint_iri, base_iri);
+        self.extend_header(sd);
+    }
+
+    fn extend_header(&mut self, triples: BTreeSet<Triple>) {
+        let header = &mut self.header;
+        header.body.extend(triples);
+        header.length = header.body.iter().map(|t| t.to_string().len() + 1).sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::containers::rdf::{Id, Literal, Term, Triple};
+    use crate::hdt::tests::snikmeta;
+    use crate::tests::init;
+    use crate::vocab::*;
+    use pretty_assertions::assert_eq;
+
+    const BASE: &str = "http://www.snik.eu/ontology/meta";
+
+    fn value(void: &std::collections::BTreeSet<Triple>, s: &Id, p: &str) -> Option<String> {
+        void.iter().find(|t| &t.subject == s && t.predicate == p).map(|t| format!("{:?}", t.object))
+    }
+
+    #[test]
+    fn void_description() -> color_eyre::Result<()> {
+        init();
+        let mut hdt = snikmeta()?;
+        let void = hdt.void_description(BASE);
+        let base = Id::Named(BASE.to_owned());
+        let lit = |n: usize| Some(format!("{:?}", Literal::new(n.to_string())));
+        assert_eq!(value(&void, &base, VOID_TRIPLES), lit(328));
+        assert_eq!(value(&void, &base, VOID_PROPERTIES), lit(23));
+        assert_eq!(value(&void, &base, VOID_DISTINCT_SUBJECTS), lit(49));
+        assert_eq!(value(&void, &base, VOID_DISTINCT_OBJECTS), lit(176));
+        let properties = void.iter().filter(|t| t.predicate == VOID_PROPERTY_PARTITION).count();
+        assert_eq!(properties, 23);
+        let classes = void.iter().filter(|t| t.predicate == VOID_CLASS_PARTITION).count();
+        assert_eq!(value(&void, &base, VOID_CLASSES), lit(classes));
+        // the triple counts of the property partitions add up to the total
+        let partition_triples: usize = void
+            .iter()
+            .filter(|t| matches!(t.subject, Id::Blank(ref b) if b.starts_with("propertyPartition")))
+            .filter(|t| t.predicate == VOID_TRIPLES)
+            .map(|t| format!("{:?}", t.object).trim_matches('"').parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(partition_triples, 328);
+        // round trip via header
+        hdt.add_void_to_header(BASE);
+        let mut buf = Vec::<u8>::new();
+        hdt.write(&mut buf)?;
+        let hdt2 = crate::Hdt::read(std::io::Cursor::new(buf))?;
+        assert!(void.is_subset(&hdt2.header.body));
+        let sd = hdt.service_description("http://www.snik.eu/sparql", BASE);
+        let graph = Triple::new(base, RDF_TYPE.to_owned(), Term::Id(Id::Named(SD_GRAPH.to_owned())));
+        assert!(sd.contains(&graph));
+        hdt.add_service_description_to_header("http://www.snik.eu/sparql", BASE);
+        let mut buf = Vec::<u8>::new();
+        hdt.write(&mut buf)?;
+        let hdt3 = crate::Hdt::read(std::io::Cursor::new(buf))?;
+        assert!(void.is_subset(&hdt3.header.body) && sd.is_subset(&hdt3.header.body));
+        Ok(())
+    }
+}
@@ -1,7 +1,177 @@
+//! WebAssembly bindings for JavaScript, see [`HdtWasm`] and [`HdtLoaderWasm`].
+//! WebAssembly code can only address its own linear memory, and the sections are parsed into Rust data structures that own their data,
+//! so they cannot be backed by an `ArrayBuffer` of the caller. Sharing the caller's buffer without copying is therefore not supported:
+//! the file is copied into the loader buffer in chunks and from there into the sections as each of them is parsed.
 use crate::Hdt;
 use crate::IdKind;
-use std::io::Cursor;
+use crate::loader::HdtLoader;
+use crate::triples::{Id, TriplesBitmap};
+use js_sys::{Array, ArrayBuffer, Function, Object, Reflect, Uint8Array, WebAssembly};
+use qwt::{RankUnsigned, SelectUnsigned};
+use std::fmt::Display;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// Maximum number of bytes that are copied from JavaScript into WebAssembly memory at once.
+const CHUNK_SIZE: u32 = 1 << 20;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+/** A triple in the string format of the HDT dictionary: IRIs without angle brackets, blank nodes with "_:" and literals in quotes. */
+export interface Triple {
+    subject: string;
+    predicate: string;
+    object: string;
+}
+/** Position of a term in a triple. */
+export type Position = "subject" | "predicate" | "object";
+/** A dictionary entry with its ID for the given position. */
+export interface Term {
+    id: number;
+    value: string;
+}
+/** A header triple in N-Triples syntax: IRIs in angle brackets, blank nodes with "_:" and literals in quotes. */
+export interface HeaderTriple {
+    subject: string;
+    predicate: string;
+    object: string;
+}
+/** The header of an HDT file, which describes the dataset. */
+export interface Header {
+    format: string;
+    length: number;
+    triples: HeaderTriple[];
+}
+/** Memory usage in bytes, `wasm` is the size of the whole WebAssembly memory, which only grows and thus shows the peak usage. */
+export interface HdtMemory {
+    wasm: number;
+    dictionary: number;
+    triples: number;
+}
+/** Memory usage in bytes while loading, `buffered` is the received data that has not been parsed yet. */
+export interface LoaderMemory {
+    wasm: number;
+    buffered: number;
+    consumed: number;
+}
+/** A solution of a SPARQL SELECT query, which maps the bound variables without "?" to terms in N-Triples syntax. */
+export type Solution = Record<string, string>;
+"#;
+
+/// Number of triples with the given predicate ID, counted from the positions of the predicate in the wavelet matrix.
+fn predicate_len(triples: &TriplesBitmap, p: Id) -> usize {
+    let wavelet_y = &triples.wavelet_y;
+    let occs = wavelet_y.rank(p as usize, wavelet_y.len()).unwrap_or(0);
+    (0..occs)
+        .filter_map(|i| wavelet_y.select(p as usize, i))
+        .map(|pos_y| triples.adjlist_z.last(pos_y) + 1 - triples.adjlist_z.find(pos_y))
+        .sum()
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Triple[]")]
+    pub type TripleArray;
+    #[wasm_bindgen(typescript_type = "Position")]
+    pub type Position;
+    #[wasm_bindgen(typescript_type = "Term[]")]
+    pub type TermArray;
+    #[wasm_bindgen(typescript_type = "Header")]
+    pub type HeaderObject;
+    #[wasm_bindgen(typescript_type = "Solution[]")]
+    pub type SolutionArray;
+    #[wasm_bindgen(typescript_type = "HdtMemory")]
+    pub type HdtMemory;
+    #[wasm_bindgen(typescript_type = "LoaderMemory")]
+    pub type LoaderMemory;
+    #[wasm_bindgen(typescript_type = "ReadableStream<Uint8Array>")]
+    pub type ByteStream;
+    #[wasm_bindgen(method, js_name = getReader)]
+    fn get_reader(this: &ByteStream) -> StreamReader;
+    type StreamReader;
+    #[wasm_bindgen(method)]
+    fn read(this: &StreamReader) -> js_sys::Promise;
+}
+
+/// Creates a JavaScript error named "HdtError", so that callers can distinguish it from other errors.
+fn error(message: impl Display) -> JsValue {
+    let e = js_sys::Error::new(&message.to_string());
+    e.set_name("HdtError");
+    e.into()
+}
+
+/// Creates a JavaScript object with the given properties.
+fn object(entries: &[(&str, &JsValue)]) -> Result<Object, JsValue> {
+    let o = Object::new();
+    for (key, value) in entries {
+        Reflect::set(&o, &JsValue::from_str(key), value)?;
+    }
+    Ok(o)
+}
+
+fn triple(s: &str, p: &str, o: &str) -> Result<Object, JsValue> {
+    object(&[("subject", &s.into()), ("predicate", &p.into()), ("object", &o.into())])
+}
+
+/// Size of the WebAssembly linear memory, which contains the Rust heap with all loaded sections and the loader buffer.
+/// It only grows and is never returned to JavaScript, so it reflects the peak usage.
+fn wasm_memory() -> u32 {
+    wasm_bindgen::memory()
+        .unchecked_into::<WebAssembly::Memory>()
+        .buffer()
+        .unchecked_into::<ArrayBuffer>()
+        .byte_length()
+}
+
+/// Builds an HDT from chunks of data, so that an HDT file never needs to be copied into WebAssembly memory as a whole.
+#[wasm_bindgen(js_name = "HdtLoader")]
+pub struct HdtLoaderWasm {
+    loader: HdtLoader,
+}
+
+#[wasm_bindgen(js_class = "HdtLoader")]
+impl HdtLoaderWasm {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> HdtLoaderWasm {
+        std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+        Self { loader: HdtLoader::new() }
+    }
+
+    /// Appends the next chunk of the HDT file, parsing all sections that are complete now.
+    /// The chunk is copied directly into the loader buffer in parts of at most 1 MiB, see [`HdtLoader`] for its size.
+    pub fn push(&mut self, chunk: &Uint8Array) -> Result<(), JsValue> {
+        let len = chunk.length();
+        let mut start = 0;
+        while start < len {
+            let end = len.min(start + CHUNK_SIZE);
+            let part = chunk.subarray(start, end);
+            self.loader.push_with((end - start) as usize, |buf| part.copy_to(buf)).map_err(error)?;
+            start = end;
+        }
+        Ok(())
+    }
+
+    /// Memory usage of the loader.
+    pub fn memory(&self) -> Result<LoaderMemory, JsValue> {
+        let o = object(&[
+            ("wasm", &wasm_memory().into()),
+            ("buffered", &self.loader.buffered().into()),
+            ("consumed", &self.loader.consumed().into()),
+        ])?;
+        Ok(o.unchecked_into())
+    }
+
+    /// Builds the HDT after the last chunk has been pushed.
+    pub fn finish(self) -> Result<HdtWasm, JsValue> {
+        Ok(HdtWasm { hdt: self.loader.finish().map_err(error)? })
+    }
+}
+
+impl Default for HdtLoaderWasm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[wasm_bindgen(js_name = "Hdt")]
 pub struct HdtWasm {
@@ -10,12 +180,44 @@ pub struct HdtWasm {
 
 #[wasm_bindgen(js_class = "Hdt")]
 impl HdtWasm {
+    /// Loads an HDT file, which is copied into WebAssembly memory in chunks so that it is never held there as a whole.
     #[wasm_bindgen(constructor)]
-    pub fn new(data: Vec<u8>) -> Result<HdtWasm, JsError> {
-        std::panic::set_hook(Box::new(console_error_panic_hook::hook));
-        let cursor = Cursor::new(data);
-        let hdt = Hdt::read(cursor).map_err(|e| JsError::new(&e.to_string()))?;
-        Ok(Self { hdt })
+    pub fn new(data: &Uint8Array) -> Result<HdtWasm, JsValue> {
+        let mut loader = HdtLoaderWasm::new();
+        loader.push(data)?;
+        loader.finish()
+    }
+
+    /// Loads an HDT file from a stream such as the body of a fetch response, parsing it while it arrives.
+    /// The optional callback is called with the memory usage of the loader after each chunk.
+    #[allow(clippy::needless_pass_by_value)]
+    pub async fn from_stream(stream: ByteStream, on_progress: Option<Function>) -> Result<HdtWasm, JsValue> {
+        let reader = stream.get_reader();
+        let mut loader = HdtLoaderWasm::new();
+        loop {
+            let result = JsFuture::from(reader.read()).await?;
+            if Reflect::get(&result, &"done".into())?.is_truthy() {
+                break;
+            }
+            let chunk = Reflect::get(&result, &"value".into())?;
+            let chunk = chunk.dyn_ref::<Uint8Array>().ok_or_else(|| error("stream chunks must be Uint8Arrays"))?;
+            loader.push(chunk)?;
+            if let Some(f) = &on_progress {
+                let memory = loader.memory()?;
+                f.call1(&JsValue::NULL, &memory)?;
+            }
+        }
+        loader.finish()
+    }
+
+    /// Memory usage of the dictionary and the triples.
+    pub fn memory(&self) -> Result<HdtMemory, JsValue> {
+        let o = object(&[
+            ("wasm", &wasm_memory().into()),
+            ("dictionary", &self.hdt.dict.size_in_bytes().into()),
+            ("triples", &self.hdt.triples.size_in_bytes().into()),
+        ])?;
+        Ok(o.unchecked_into())
     }
 
     /// Returns a flat Int32Array of IDs [s1, p1, o1, s2, p2, o2, ...].
@@ -29,8 +231,75 @@ impl HdtWasm {
         let ids = self.hdt.triple_ids_with_pattern(sp.as_deref(), pp.as_deref(), op.as_deref());
         // flatten into a single array for higher JavaScript performance
         // prevents inefficient generation of JavaScript array of arrays
-        let flat: Box<[u32]> = ids.flat_map(|[s, p, o]| [s as u32, p as u32, o as u32]).collect();
-        flat
+        ids.flatten().collect()
+    }
+
+    /// Returns at most `limit` triples matching the pattern after skipping the first `offset` ones.
+    /// Skipped triples are not translated to strings, so paging through large results stays cheap.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn triples_with_pattern(
+        &self, sp: Option<String>, pp: Option<String>, op: Option<String>, offset: usize, limit: usize,
+    ) -> Result<TripleArray, JsValue> {
+        let array = Array::new();
+        let ids = self.hdt.triple_ids_with_pattern(sp.as_deref(), pp.as_deref(), op.as_deref());
+        for t in ids.skip(offset).take(limit) {
+            let [s, p, o] = [0, 1, 2].map(|i| self.hdt.dict.id_to_string(t[i], IdKind::KINDS[i]));
+            let t = triple(&s.map_err(error)?, &p.map_err(error)?, &o.map_err(error)?)?;
+            array.push(&t);
+        }
+        Ok(array.unchecked_into())
+    }
+
+    /// Number of triples matching the pattern, without translating them to strings.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn count(&self, sp: Option<String>, pp: Option<String>, op: Option<String>) -> usize {
+        match (&sp, &pp, &op) {
+            (None, None, None) => return self.hdt.triples.adjlist_z.len(),
+            (None, Some(p), None) => {
+                let p = self.hdt.dict.string_to_id(p, IdKind::Predicate);
+                return if p == 0 { 0 } else { predicate_len(&self.hdt.triples, p) };
+            }
+            _ => {}
+        }
+        self.hdt.triple_ids_with_pattern(sp.as_deref(), pp.as_deref(), op.as_deref()).count()
+    }
+
+    /// Returns at most `limit` dictionary entries of the given position that start with the prefix in ascending order, for example for autocompletion.
+    /// Uses the HDT string format, so IRIs are given without angle brackets and literals start with a quotation mark.
+    pub fn terms_with_prefix(
+        &self, prefix: &str, position: &Position, limit: usize,
+    ) -> Result<TermArray, JsValue> {
+        let position = position.as_string().unwrap_or_default();
+        let kind = match position.as_str() {
+            "subject" => IdKind::Subject,
+            "predicate" => IdKind::Predicate,
+            "object" => IdKind::Object,
+            _ => {
+                return Err(error(format!(
+                    "invalid position \"{position}\", expected subject, predicate or object"
+                )));
+            }
+        };
+        let array = Array::new();
+        for (id, value) in self.hdt.dict.strings_with_prefix(prefix, kind).take(limit) {
+            let term = object(&[("id", &id.into()), ("value", &value.into())])?;
+            array.push(&term);
+        }
+        Ok(array.unchecked_into())
+    }
+
+    /// The header of the HDT file.
+    pub fn header(&self) -> Result<HeaderObject, JsValue> {
+        let h = &self.hdt.header;
+        let triples = Array::new();
+        for t in &h.body {
+            let t =
+                triple(&format!("{:?}", t.subject), &format!("<{}>", t.predicate), &format!("{:?}", t.object))?;
+            triples.push(&t);
+        }
+        let o =
+            object(&[("format", &h.format.as_str().into()), ("length", &h.length.into()), ("triples", &triples)])?;
+        Ok(o.unchecked_into())
     }
 
     // --- Translation Functions ---
@@ -38,19 +307,41 @@ impl HdtWasm {
     /// ids: flat Int32Array of IDs [s1, p1, o1, s2, p2, o2, ...].
     /// Returns string triples as a flat array of strings [s1, p1, o1, s2, p2, o2, ...].
     /// WASM memory is limited, several million triple IDs may lead to OOM crashes reported as "RuntimeError: unreachable executed"
-    pub fn ids_to_strings(&self, ids: &[u32]) -> Result<Vec<String>, JsError> {
+    pub fn ids_to_strings(&self, ids: &[Id]) -> Result<Vec<String>, JsValue> {
         if !ids.len().is_multiple_of(3) {
-            return Err(JsError::new("Input array length must be a multiple of 3"));
+            return Err(error("Input array length must be a multiple of 3"));
         }
         let mut strings = Vec::with_capacity(ids.len());
         for (i, id) in ids.iter().enumerate() {
             strings.push(
                 self.hdt
                     .dict
-                    .id_to_string(*id as usize, IdKind::KINDS[i % 3])
-                    .map_err(|_| JsError::new(&format!("{:?} ID {id} does not exist", IdKind::KINDS[i % 3])))?,
+                    .id_to_string(*id, IdKind::KINDS[i % 3])
+                    .map_err(|_| error(format!("{:?} ID {id} does not exist", IdKind::KINDS[i % 3])))?,
             );
         }
         Ok(strings)
     }
 }
+
+#[cfg(feature = "sparql")]
+#[wasm_bindgen(js_class = "Hdt")]
+impl HdtWasm {
+    /// Evaluates a SPARQL SELECT query and returns all solutions.
+    pub fn sparql(&self, query: &str) -> Result<SolutionArray, JsValue> {
+        let spareval::QueryResults::Solutions(solutions) =
+            crate::sparql::query(query, &self.hdt).map_err(error)?
+        else {
+            return Err(error("only SELECT queries are supported"));
+        };
+        let array = Array::new();
+        for solution in solutions {
+            let o = Object::new();
+            for (variable, term) in solution.map_err(error)?.iter() {
+                Reflect::set(&o, &variable.as_str().into(), &term.to_string().into())?;
+            }
+            array.push(&o);
+        }
+        Ok(array.unchecked_into())
+    }
+}
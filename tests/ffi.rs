@@ -0,0 +1,44 @@
+//! Compiles the C test program in tests/ffi against the shared library and runs it.
+#![cfg(all(feature = "ffi", unix))]
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn header_up_to_date() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let generated = Path::new(env!("OUT_DIR")).join("hdt.h");
+    let generated = std::fs::read_to_string(&generated)
+        .unwrap_or_else(|e| panic!("reading {generated:?} failed, see the build script warnings: {e}"));
+    let committed = std::fs::read_to_string(root.join("include/hdt.h")).unwrap();
+    assert!(
+        generated == committed,
+        "include/hdt.h is outdated, replace it with {}",
+        Path::new(env!("OUT_DIR")).join("hdt.h").display()
+    );
+}
+
+#[test]
+fn c_api() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // the shared library is built next to the test executable in target/<profile>/deps
+    let exe = std::env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_test");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = Command::new(cc)
+        .arg(root.join("tests/ffi/test.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lhdt")
+        .arg("-o")
+        .arg(&out)
+        .status()
+        .expect("a C compiler is required, set CC to use another one than cc");
+    assert!(status.success(), "compiling tests/ffi/test.c failed");
+    let output = Command::new(&out).arg(root.join("tests/resources/snikmeta.hdt")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
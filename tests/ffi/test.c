@@ -0,0 +1,101 @@
+// Test of the C API, compiled and run by tests/ffi.rs with the path of snikmeta.hdt as argument.
+#include <assert.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "hdt.h"
+
+#define TOP "http://www.snik.eu/ontology/meta/Top"
+#define LABEL "http://www.w3.org/2000/01/rdf-schema#label"
+
+int main(int argc, char **argv) {
+	assert(argc == 2);
+
+	// errors
+	assert(hdt_open("does/not/exist.hdt") == NULL);
+	assert(hdt_last_error() != NULL);
+	assert(strstr(hdt_last_error(), "exist.hdt") != NULL);
+	assert(hdt_open_buffer((const uint8_t *)"invalid", 7) == NULL);
+	assert(hdt_last_error() != NULL);
+
+	const Hdt *hdt = hdt_open(argv[1]);
+	assert(hdt != NULL);
+	assert(hdt_last_error() == NULL);
+	assert(hdt_triple_count(hdt) == 328);
+
+	// the same file from a buffer
+	FILE *file = fopen(argv[1], "rb");
+	assert(file != NULL);
+	fseek(file, 0, SEEK_END);
+	size_t len = (size_t)ftell(file);
+	rewind(file);
+	uint8_t *data = malloc(len);
+	assert(fread(data, 1, len, file) == len);
+	fclose(file);
+	const Hdt *buffered = hdt_open_buffer(data, len);
+	free(data);
+	assert(buffered != NULL);
+	assert(hdt_triple_count(buffered) == 328);
+	hdt_close(buffered);
+
+	// string cursor
+	HdtCursor *cursor = hdt_query(hdt, TOP, LABEL, NULL);
+	assert(cursor != NULL);
+	HdtTriple triple;
+	size_t labels = 0;
+	while (hdt_cursor_next(cursor, &triple)) {
+		assert(strcmp(triple.subject, TOP) == 0);
+		assert(strcmp(triple.predicate, LABEL) == 0);
+		assert(triple.object[0] == '"');
+		labels++;
+	}
+	assert(hdt_last_error() == NULL);
+	assert(labels > 0);
+	hdt_cursor_free(cursor);
+
+	// ID cursor, which keeps the HDT alive after closing it
+	cursor = hdt_query(hdt, NULL, NULL, NULL);
+	const Hdt *second = hdt_open(argv[1]);
+	HdtCursor *ids_cursor = hdt_query_ids(second, 0, 0, 0);
+	hdt_close(second);
+	uint32_t ids[3];
+	size_t count = 0;
+	while (hdt_cursor_next_ids(ids_cursor, ids)) {
+		assert(ids[0] > 0 && ids[1] > 0 && ids[2] > 0);
+		assert(hdt_cursor_next(cursor, &triple));
+		char *s = hdt_id_to_string(hdt, ids[0], HDT_POSITION_SUBJECT);
+		assert(strcmp(s, triple.subject) == 0);
+		hdt_string_free(s);
+		count++;
+	}
+	assert(count == 328);
+	assert(!hdt_cursor_next(cursor, &triple));
+	hdt_cursor_free(cursor);
+	hdt_cursor_free(ids_cursor);
+
+	// unknown constants result in no triples
+	cursor = hdt_query(hdt, "http://example.org/doesnotexist", NULL, NULL);
+	assert(cursor != NULL);
+	assert(!hdt_cursor_next(cursor, &triple));
+	hdt_cursor_free(cursor);
+
+	// dictionary
+	uint32_t id = hdt_string_to_id(hdt, TOP, HDT_POSITION_SUBJECT);
+	assert(id > 0);
+	char *s = hdt_id_to_string(hdt, id, HDT_POSITION_SUBJECT);
+	assert(strcmp(s, TOP) == 0);
+	hdt_string_free(s);
+	assert(hdt_string_to_id(hdt, "http://example.org/doesnotexist", HDT_POSITION_OBJECT) == 0);
+	assert(hdt_id_to_string(hdt, 1000000, HDT_POSITION_PREDICATE) == NULL);
+	assert(hdt_last_error() != NULL);
+
+	// header
+	char *header = hdt_header(hdt);
+	assert(header != NULL);
+	assert(strstr(header, "<http://rdfs.org/ns/void#triples> \"328\"") != NULL);
+	hdt_string_free(header);
+
+	hdt_close(hdt);
+	printf("ok\n");
+	return 0;
+}
@@ -0,0 +1,110 @@
+# Tests of the Python bindings, run with `python -m unittest discover tests/python` after `maturin develop`, see README.
+import array
+import os
+import tempfile
+import unittest
+
+from hdt_rs import Hdt, HdtError, sparql
+
+try:
+    import rdflib
+except ImportError:
+    rdflib = None
+
+RESOURCES = os.path.join(os.path.dirname(__file__), "..", "resources")
+META = "http://www.snik.eu/ontology/meta"
+TOP = META + "/Top"
+LABEL = "http://www.w3.org/2000/01/rdf-schema#label"
+hdt = Hdt.read(os.path.join(RESOURCES, "snikmeta.hdt"))
+
+
+class HdtTest(unittest.TestCase):
+    def test_read(self):
+        self.assertEqual(len(hdt), 328)
+        with self.assertRaisesRegex(HdtError, "doesnotexist"):
+            Hdt.read("doesnotexist.hdt")
+
+    def test_triples_with_pattern(self):
+        triples = hdt.triples_with_pattern()
+        self.assertIs(iter(triples), triples)
+        first = next(triples)
+        self.assertEqual(len(first), 3)
+        self.assertEqual(1 + sum(1 for _ in triples), 328)
+        labels = list(hdt.triples_with_pattern(TOP, LABEL))
+        self.assertTrue(labels)
+        for s, p, o in labels:
+            self.assertEqual((s, p), (TOP, LABEL))
+            self.assertTrue(o.startswith('"'))
+        self.assertEqual(list(hdt.triples_with_pattern(object="doesnotexist")), [])
+
+    def test_ids(self):
+        ids = hdt.triple_ids_with_pattern(TOP, LABEL)
+        self.assertIsInstance(ids, array.array)
+        self.assertEqual(ids.typecode, "I")
+        self.assertEqual(len(ids), 3 * len(list(hdt.triples_with_pattern(TOP, LABEL))))
+        self.assertEqual(len(hdt.triple_ids_with_id_pattern()), 3 * 328)
+        s, p = hdt.string_to_id(TOP, "subject"), hdt.string_to_id(LABEL, "predicate")
+        self.assertEqual(list(hdt.triple_ids_with_id_pattern(s, p)), list(ids))
+        self.assertEqual(hdt.id_to_string(s, "subject"), TOP)
+        self.assertEqual(hdt.ids_to_strings(ids), list(hdt.triples_with_pattern(TOP, LABEL)))
+        self.assertEqual(hdt.string_to_id("doesnotexist", "object"), 0)
+        with self.assertRaises(ValueError):
+            hdt.string_to_id(TOP, "graph")
+        with self.assertRaises(ValueError):
+            hdt.ids_to_strings([1, 1])
+        with self.assertRaises(HdtError):
+            hdt.id_to_string(100000, "object")
+        for pattern in [(100000, 0, 0), (0, 100000, 0), (0, 0, 100000)]:
+            with self.assertRaisesRegex(HdtError, "does not exist"):
+                hdt.triple_ids_with_id_pattern(*pattern)
+
+    def test_numpy(self):
+        try:
+            import numpy
+        except ImportError:
+            self.skipTest("numpy is not installed")
+        ids = numpy.frombuffer(hdt.triple_ids_with_pattern(), dtype=numpy.uint32).reshape(-1, 3)
+        self.assertEqual(ids.shape, (328, 3))
+
+    def test_conversion(self):
+        triples = [("http://example.org/s", "http://example.org/p", '"o"@en'), ("_:b1", "http://example.org/p", "http://example.org/o")]
+        converted = Hdt.from_triples(triples, "http://example.org/dataset")
+        self.assertEqual(sorted(converted.triples_with_pattern()), sorted(triples))
+        with tempfile.TemporaryDirectory() as dir:
+            path = os.path.join(dir, "converted.hdt")
+            converted.write(path)
+            self.assertEqual(len(Hdt.read(path)), 2)
+        nt = Hdt.read_nt(os.path.join(RESOURCES, "snikmeta.nt"))
+        self.assertEqual(sorted(nt.triples_with_pattern()), sorted(hdt.triples_with_pattern()))
+
+    def test_sparql(self):
+        solutions = sparql.query(hdt, f"SELECT ?label {{ <{TOP}> <{LABEL}> ?label }}")
+        self.assertTrue(solutions)
+        self.assertEqual(list(solutions[0]), ["label"])
+        self.assertTrue(solutions[0]["label"].startswith('"'))
+        self.assertIs(sparql.query(hdt, "ASK { ?s ?p ?o }"), True)
+        graph = sparql.query(hdt, f"CONSTRUCT {{ ?s <{LABEL}> ?o }} WHERE {{ ?s <{LABEL}> ?o }}")
+        self.assertEqual(sorted(graph), sorted(hdt.triples_with_pattern(predicate=LABEL)))
+        with self.assertRaises(HdtError):
+            sparql.query(hdt, "SELECT")
+
+
+@unittest.skipIf(rdflib is None, "rdflib is not installed")
+class StoreTest(unittest.TestCase):
+    def test_graph(self):
+        from hdt_rs.store import HDTStore
+
+        graph = rdflib.Graph(store=HDTStore(os.path.join(RESOURCES, "snikmeta.hdt")))
+        self.assertEqual(len(graph), 328)
+        labels = list(graph.objects(rdflib.URIRef(TOP), rdflib.RDFS.label))
+        self.assertTrue(labels)
+        for label in labels:
+            self.assertIsInstance(label, rdflib.Literal)
+            self.assertEqual(list(graph.subjects(rdflib.RDFS.label, label)), [rdflib.URIRef(TOP)])
+        self.assertEqual(len(graph.query(f"SELECT ?o {{ <{TOP}> <{LABEL}> ?o }}")), len(labels))
+        with self.assertRaises(TypeError):
+            graph.add((rdflib.URIRef(TOP), rdflib.RDFS.label, rdflib.Literal("x")))
+
+
+if __name__ == "__main__":
+    unittest.main()
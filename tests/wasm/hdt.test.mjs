@@ -0,0 +1,128 @@
+// Headless tests of the WebAssembly API, run with `node --test tests/wasm/` after building the Node.js package, see README.
+// The package directory can be changed with the HDT_WASM_PKG environment variable.
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { createRequire } from "node:module";
+import { resolve } from "node:path";
+
+const root = new URL("../../", import.meta.url).pathname;
+const pkg = resolve(root, process.env.HDT_WASM_PKG ?? "pkg-node");
+const { Hdt, HdtLoader } = createRequire(import.meta.url)(resolve(pkg, "hdt.js"));
+const data = readFileSync(resolve(root, "tests/resources/snikmeta.hdt"));
+const hdt = new Hdt(data);
+
+const META = "http://www.snik.eu/ontology/meta";
+const TOP = `${META}/Top`;
+const LABEL = "http://www.w3.org/2000/01/rdf-schema#label";
+
+test("count", () => {
+	assert.equal(hdt.count(null, null, null), 328);
+	assert.equal(hdt.count(TOP, null, null), hdt.triple_ids_with_pattern(TOP, null, null).length / 3);
+	assert.equal(hdt.count("http://example.org/doesnotexist", null, null), 0);
+	assert.equal(hdt.count(null, LABEL, null), hdt.triple_ids_with_pattern(null, LABEL, null).length / 3);
+	assert.equal(hdt.count(null, "http://example.org/doesnotexist", null), 0);
+});
+
+test("paging", () => {
+	const all = hdt.triples_with_pattern(null, null, null, 0, 1000);
+	assert.equal(all.length, 328);
+	const pages = [];
+	for (let offset = 0; offset < 328; offset += 50) {
+		const page = hdt.triples_with_pattern(null, null, null, offset, 50);
+		assert.ok(page.length <= 50);
+		pages.push(...page);
+	}
+	assert.deepEqual(pages, all);
+	assert.deepEqual(hdt.triples_with_pattern(null, null, null, 328, 10), []);
+	const labels = hdt.triples_with_pattern(TOP, LABEL, null, 0, 10);
+	assert.ok(labels.length > 0);
+	for (const t of labels) {
+		assert.deepEqual(Object.keys(t), ["subject", "predicate", "object"]);
+		assert.equal(t.subject, TOP);
+		assert.equal(t.predicate, LABEL);
+		assert.ok(t.object.startsWith('"'));
+	}
+});
+
+test("prefix", () => {
+	const terms = hdt.terms_with_prefix(`${META}/`, "subject", 5);
+	assert.equal(terms.length, 5);
+	const values = terms.map((t) => t.value);
+	assert.deepEqual(values, [...values].sort());
+	for (const { id, value } of terms) {
+		assert.ok(value.startsWith(`${META}/`));
+		assert.deepEqual(hdt.ids_to_strings(new Uint32Array([id, 1, 1]))[0], value);
+	}
+	const predicates = hdt.terms_with_prefix("http://www.w3.org/2000/01/rdf-schema#lab", "predicate", 10);
+	assert.deepEqual(predicates.map((t) => t.value), [LABEL]);
+	assert.deepEqual(hdt.terms_with_prefix("doesnotexist", "object", 10), []);
+});
+
+/** Stream of the test data in chunks of the given size. */
+function stream(size) {
+	let offset = 0;
+	return new ReadableStream({
+		pull(controller) {
+			if (offset >= data.length) {
+				controller.close();
+				return;
+			}
+			controller.enqueue(data.subarray(offset, (offset += size)));
+		},
+	});
+}
+
+test("loader", () => {
+	const loader = new HdtLoader();
+	for (let offset = 0; offset < data.length; offset += 1000) {
+		loader.push(data.subarray(offset, offset + 1000));
+		const memory = loader.memory();
+		assert.equal(memory.consumed + memory.buffered, Math.min(offset + 1000, data.length));
+		assert.ok(memory.wasm > 0);
+	}
+	const loaded = loader.finish();
+	assert.deepEqual(loaded.triples_with_pattern(null, null, null, 0, 1000), hdt.triples_with_pattern(null, null, null, 0, 1000));
+	assert.throws(() => loader.memory(), /null pointer|moved|destroyed/i);
+	const truncated = new HdtLoader();
+	truncated.push(data.subarray(0, data.length - 10));
+	assert.throws(() => truncated.finish(), { name: "HdtError" });
+});
+
+test("stream", async () => {
+	const reports = [];
+	const streamed = await Hdt.from_stream(stream(100), (memory) => reports.push(memory));
+	assert.equal(streamed.count(null, null, null), 328);
+	assert.equal(reports.length, Math.ceil(data.length / 100));
+	assert.equal(reports.at(-1).consumed + reports.at(-1).buffered, data.length);
+	await assert.rejects(Hdt.from_stream(new ReadableStream({ start: (c) => (c.enqueue("text"), c.close()) })), {
+		name: "HdtError",
+	});
+	const memory = streamed.memory();
+	assert.ok(memory.dictionary > 0 && memory.triples > 0);
+	assert.ok(memory.wasm >= memory.dictionary + memory.triples);
+});
+
+test("header", () => {
+	const header = hdt.header();
+	assert.equal(header.format, "ntriples");
+	assert.ok(header.length > 0);
+	assert.ok(header.triples.length > 0);
+	assert.ok(header.triples.some((t) => t.predicate === "<http://rdfs.org/ns/void#triples>" && t.object.startsWith('"328"')));
+});
+
+test("errors", () => {
+	assert.throws(() => hdt.terms_with_prefix("", "graph", 1), { name: "HdtError", message: /invalid position "graph"/ });
+	assert.throws(() => hdt.ids_to_strings(new Uint32Array([1, 1])), { name: "HdtError" });
+	assert.throws(() => hdt.ids_to_strings(new Uint32Array([1, 1, 100000])), { name: "HdtError" });
+	assert.throws(() => new Hdt(new Uint8Array([1, 2, 3])), { name: "HdtError" });
+});
+
+test("sparql", { skip: typeof hdt.sparql !== "function" && "built without the sparql feature" }, () => {
+	const solutions = hdt.sparql(`SELECT ?label { <${TOP}> <${LABEL}> ?label }`);
+	assert.ok(solutions.length > 0);
+	assert.deepEqual(Object.keys(solutions[0]), ["label"]);
+	assert.ok(solutions[0].label.startsWith('"'));
+	assert.throws(() => hdt.sparql(`ASK { ?s ?p ?o }`), { name: "HdtError", message: /only SELECT/ });
+	assert.throws(() => hdt.sparql("SELECT"), { name: "HdtError" });
+});